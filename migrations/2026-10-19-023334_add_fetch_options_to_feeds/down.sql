@@ -0,0 +1,8 @@
+DROP INDEX feeds_link_fetch_options_hash_index;
+
+ALTER TABLE feeds DROP COLUMN fetch_options_hash;
+ALTER TABLE feeds DROP COLUMN fetch_options;
+
+-- fails while feeds with the same link and different fetch options exist
+-- so subscriptions of these feeds are not removed silently
+ALTER TABLE feeds ADD CONSTRAINT feeds_link_key UNIQUE (link);
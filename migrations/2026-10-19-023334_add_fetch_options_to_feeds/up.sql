@@ -0,0 +1,5 @@
+ALTER TABLE feeds ADD COLUMN fetch_options TEXT;
+ALTER TABLE feeds ADD COLUMN fetch_options_hash TEXT NOT NULL DEFAULT '';
+
+ALTER TABLE feeds DROP CONSTRAINT feeds_link_key;
+CREATE UNIQUE INDEX feeds_link_fetch_options_hash_index ON feeds(link, fetch_options_hash);
@@ -0,0 +1,2 @@
+DROP TABLE sync_host_tokens;
+DROP TABLE sync_hosts;
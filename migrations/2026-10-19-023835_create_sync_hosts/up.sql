@@ -0,0 +1,17 @@
+CREATE TABLE sync_hosts (
+    host TEXT PRIMARY KEY,
+    next_request_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    backoff_until TIMESTAMP WITH TIME ZONE,
+    backoff_count INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE sync_host_tokens (
+    id BIGSERIAL PRIMARY KEY,
+    host TEXT NOT NULL REFERENCES sync_hosts(host) ON DELETE CASCADE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX sync_host_tokens_host_index ON sync_host_tokens(host);
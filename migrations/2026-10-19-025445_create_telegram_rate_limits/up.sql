@@ -0,0 +1,6 @@
+CREATE TABLE telegram_rate_limits (
+    chat_id BIGINT PRIMARY KEY,
+    next_send_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
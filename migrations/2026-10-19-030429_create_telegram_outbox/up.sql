@@ -0,0 +1,22 @@
+CREATE TABLE telegram_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    feed_id BIGINT NOT NULL,
+    idempotency_key TEXT NOT NULL,
+    message TEXT NOT NULL,
+    thread_id INTEGER,
+    preview_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    status TEXT NOT NULL DEFAULT 'pending',
+    attempts INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    telegram_message_id INTEGER,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (chat_id, feed_id) REFERENCES telegram_subscriptions(chat_id, feed_id)
+        ON DELETE CASCADE ON UPDATE CASCADE,
+    UNIQUE (chat_id, feed_id, idempotency_key)
+);
+
+CREATE INDEX telegram_outbox_status_index ON telegram_outbox(chat_id, feed_id, status, id);
@@ -0,0 +1 @@
+ALTER TABLE telegram_subscriptions ADD COLUMN split_messages BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+ALTER TABLE telegram_outbox DROP COLUMN parse_mode;
+ALTER TABLE telegram_chats DROP COLUMN parse_mode;
@@ -0,0 +1,2 @@
+ALTER TABLE telegram_chats ADD COLUMN parse_mode TEXT NOT NULL DEFAULT 'HTML';
+ALTER TABLE telegram_outbox ADD COLUMN parse_mode TEXT NOT NULL DEFAULT 'HTML';
@@ -0,0 +1,3 @@
+ALTER TABLE telegram_subscriptions DROP COLUMN excluded_authors;
+ALTER TABLE telegram_subscriptions DROP COLUMN muted;
+ALTER TABLE telegram_subscriptions DROP COLUMN item_buttons;
@@ -0,0 +1,3 @@
+ALTER TABLE telegram_subscriptions ADD COLUMN item_buttons TEXT[];
+ALTER TABLE telegram_subscriptions ADD COLUMN muted BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE telegram_subscriptions ADD COLUMN excluded_authors TEXT[];
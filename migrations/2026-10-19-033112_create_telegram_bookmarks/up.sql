@@ -0,0 +1,15 @@
+CREATE TABLE telegram_bookmarks (
+    id BIGSERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL REFERENCES telegram_chats(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    feed_link TEXT NOT NULL,
+    title TEXT NOT NULL,
+    link TEXT NOT NULL,
+    description TEXT,
+    author TEXT,
+    publication_date TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (chat_id, link)
+);
+
+CREATE INDEX telegram_bookmarks_chat_id_index ON telegram_bookmarks(chat_id, created_at);
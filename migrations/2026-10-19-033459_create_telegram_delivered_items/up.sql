@@ -0,0 +1,31 @@
+-- Delivered items are kept after their outbox messages and feed items are removed
+-- so /save and item buttons work for old messages
+CREATE TABLE telegram_delivered_items (
+    chat_id BIGINT NOT NULL REFERENCES telegram_chats(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    telegram_message_id INTEGER NOT NULL,
+    feed_id BIGINT NOT NULL,
+    title TEXT NOT NULL,
+    link TEXT NOT NULL,
+    description TEXT,
+    author TEXT,
+    publication_date TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (chat_id, telegram_message_id)
+);
+
+INSERT INTO telegram_delivered_items
+    (chat_id, telegram_message_id, feed_id, title, link, description, author, publication_date)
+SELECT DISTINCT ON (telegram_outbox.chat_id, telegram_outbox.telegram_message_id)
+    telegram_outbox.chat_id,
+    telegram_outbox.telegram_message_id,
+    feed_items.feed_id,
+    feed_items.title,
+    feed_items.link,
+    feed_items.description,
+    feed_items.author,
+    feed_items.publication_date
+FROM telegram_outbox
+JOIN feed_items ON feed_items.feed_id = telegram_outbox.feed_id
+    AND feed_items.content_hash = split_part(telegram_outbox.idempotency_key, ':', 1)
+WHERE telegram_outbox.telegram_message_id IS NOT NULL
+ORDER BY telegram_outbox.chat_id, telegram_outbox.telegram_message_id;
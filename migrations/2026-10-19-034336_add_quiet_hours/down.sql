@@ -0,0 +1,4 @@
+ALTER TABLE telegram_subscriptions DROP COLUMN silent;
+ALTER TABLE telegram_chats DROP COLUMN quiet_hours_hold;
+ALTER TABLE telegram_chats DROP COLUMN quiet_hours_end;
+ALTER TABLE telegram_chats DROP COLUMN quiet_hours_start;
@@ -0,0 +1,4 @@
+ALTER TABLE telegram_chats ADD COLUMN quiet_hours_start INTEGER;
+ALTER TABLE telegram_chats ADD COLUMN quiet_hours_end INTEGER;
+ALTER TABLE telegram_chats ADD COLUMN quiet_hours_hold BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE telegram_subscriptions ADD COLUMN silent BOOLEAN NOT NULL DEFAULT FALSE;
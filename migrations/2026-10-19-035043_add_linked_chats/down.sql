@@ -0,0 +1,3 @@
+ALTER TABLE telegram_chats DROP COLUMN selected_chat_id;
+
+DROP TABLE telegram_linked_chats;
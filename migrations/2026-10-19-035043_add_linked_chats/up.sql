@@ -0,0 +1,11 @@
+CREATE TABLE telegram_linked_chats (
+    id BIGSERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL REFERENCES telegram_chats(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    linked_chat_id BIGINT NOT NULL REFERENCES telegram_chats(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    admin_checked_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    UNIQUE (chat_id, linked_chat_id)
+);
+
+ALTER TABLE telegram_chats ADD COLUMN selected_chat_id BIGINT REFERENCES telegram_chats(id) ON DELETE SET NULL;
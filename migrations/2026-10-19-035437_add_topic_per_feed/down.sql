@@ -0,0 +1 @@
+ALTER TABLE telegram_chats DROP COLUMN topic_per_feed;
@@ -0,0 +1 @@
+ALTER TABLE telegram_chats ADD COLUMN topic_per_feed BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+ALTER TABLE telegram_subscriptions DROP COLUMN paused_until;
+ALTER TABLE telegram_subscriptions DROP COLUMN paused_at;
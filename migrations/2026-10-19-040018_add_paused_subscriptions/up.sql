@@ -0,0 +1,2 @@
+ALTER TABLE telegram_subscriptions ADD COLUMN paused_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE telegram_subscriptions ADD COLUMN paused_until TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,2 @@
+ALTER TABLE telegram_subscriptions DROP COLUMN hourly_limit;
+ALTER TABLE telegram_subscriptions DROP COLUMN messages_limit;
@@ -0,0 +1,2 @@
+ALTER TABLE telegram_subscriptions ADD COLUMN messages_limit INTEGER;
+ALTER TABLE telegram_subscriptions ADD COLUMN hourly_limit INTEGER;
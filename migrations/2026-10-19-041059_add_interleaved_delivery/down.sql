@@ -0,0 +1,2 @@
+ALTER TABLE telegram_outbox DROP COLUMN published_at;
+ALTER TABLE telegram_chats DROP COLUMN interleave_items;
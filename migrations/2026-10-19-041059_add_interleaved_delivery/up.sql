@@ -0,0 +1,2 @@
+ALTER TABLE telegram_chats ADD COLUMN interleave_items BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE telegram_outbox ADD COLUMN published_at TIMESTAMP WITH TIME ZONE;
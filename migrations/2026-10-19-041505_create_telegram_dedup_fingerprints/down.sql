@@ -0,0 +1,3 @@
+DROP TABLE telegram_dedup_fingerprints;
+
+ALTER TABLE telegram_chats DROP COLUMN dedup_days;
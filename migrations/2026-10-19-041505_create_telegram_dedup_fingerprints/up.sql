@@ -0,0 +1,10 @@
+ALTER TABLE telegram_chats ADD COLUMN dedup_days INTEGER;
+
+CREATE TABLE telegram_dedup_fingerprints (
+    chat_id BIGINT NOT NULL REFERENCES telegram_chats(id) ON DELETE CASCADE,
+    fingerprint TEXT NOT NULL,
+    delivered_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (chat_id, fingerprint)
+);
+
+CREATE INDEX telegram_dedup_fingerprints_delivered_at_index ON telegram_dedup_fingerprints(delivered_at);
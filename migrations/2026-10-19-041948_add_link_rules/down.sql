@@ -0,0 +1,2 @@
+ALTER TABLE telegram_subscriptions DROP COLUMN link_rules;
+ALTER TABLE telegram_chats DROP COLUMN link_rules;
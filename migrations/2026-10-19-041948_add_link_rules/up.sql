@@ -0,0 +1,2 @@
+ALTER TABLE telegram_chats ADD COLUMN link_rules TEXT[];
+ALTER TABLE telegram_subscriptions ADD COLUMN link_rules TEXT[];
@@ -0,0 +1,13 @@
+CREATE TABLE telegram_rewrite_rules (
+    id BIGSERIAL PRIMARY KEY,
+    chat_id BIGINT NOT NULL,
+    feed_id BIGINT NOT NULL,
+    field TEXT NOT NULL,
+    pattern TEXT NOT NULL,
+    replacement TEXT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (chat_id, feed_id) REFERENCES telegram_subscriptions(chat_id, feed_id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX telegram_rewrite_rules_subscription_index ON telegram_rewrite_rules(chat_id, feed_id, id);
@@ -0,0 +1,2 @@
+ALTER TABLE telegram_chats DROP COLUMN date_language;
+ALTER TABLE telegram_chats DROP COLUMN date_format;
@@ -0,0 +1,2 @@
+ALTER TABLE telegram_chats ADD COLUMN date_format TEXT;
+ALTER TABLE telegram_chats ADD COLUMN date_language TEXT;
@@ -1,4 +1,5 @@
 pub mod commands;
+pub mod rate_limiter;
 pub mod telegram_client;
 pub mod update_handler;
 
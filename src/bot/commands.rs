@@ -2,18 +2,30 @@ use crate::bot::SimpleMessageParams;
 use crate::bot::telegram_client;
 use crate::bot::telegram_client::Api;
 use crate::config::Config;
+use crate::db;
 use crate::db::feeds;
 use crate::db::telegram;
 use crate::db::telegram::NewTelegramChat;
 use crate::db::telegram::NewTelegramSubscription;
+use crate::db::telegram_bookmarks;
+use crate::db::telegram_bookmarks::NewTelegramBookmark;
+use crate::db::telegram_delivered_items;
+use crate::db::telegram_linked_chats;
+use crate::deliver::links;
 use crate::models::Feed;
 use crate::models::TelegramChat;
+use crate::models::TelegramDeliveredItem;
 use crate::models::TelegramSubscription;
+use chrono::Duration;
 use diesel::PgConnection;
 use diesel::r2d2::ConnectionManager;
 use diesel::r2d2::PooledConnection;
+use frankenstein::TelegramApi;
+use frankenstein::methods::GetChatMemberParams;
 use frankenstein::methods::SendMessageParams;
 use frankenstein::types::Chat;
+use frankenstein::types::ChatFullInfo;
+use frankenstein::types::ChatMember;
 use frankenstein::types::ChatType;
 use frankenstein::types::InlineKeyboardButton;
 use frankenstein::types::InlineKeyboardMarkup;
@@ -25,76 +37,162 @@ use std::str::FromStr;
 use typed_builder::TypedBuilder;
 use uuid::Uuid;
 
+pub use add_rewrite::AddRewrite;
+pub use backfill::Backfill;
 pub use close::Close;
 pub use commands_keyboard::CommandsKeyboard;
+pub use get_fetch_options::GetFetchOptions;
 pub use get_filter::GetFilter;
 pub use get_global_filter::GetGlobalFilter;
 pub use get_global_template::GetGlobalTemplate;
 pub use get_preview_enabled::GetPreviewEnabled;
+pub use get_quiet_hours::GetQuietHours;
 pub use get_template::GetTemplate;
 pub use get_timezone::GetTimezone;
 pub use help::Help;
 pub use help_command_info::HelpCommandInfo;
 pub use info::Info;
+pub use item_action::ItemAction;
+pub use link_chat::LinkChat;
+pub use list_rewrites::ListRewrites;
 pub use list_subscriptions_keyboard::ListSubscriptionsKeyboard;
+pub use mark_all_read::MarkAllRead;
+pub use pause::Pause;
+pub use remove_fetch_options::RemoveFetchOptions;
 pub use remove_filter::RemoveFilter;
 pub use remove_global_filter::RemoveGlobalFilter;
 pub use remove_global_template::RemoveGlobalTemplate;
+pub use remove_quiet_hours::RemoveQuietHours;
+pub use remove_rewrite::RemoveRewrite;
+pub use remove_saved::RemoveSaved;
 pub use remove_template::RemoveTemplate;
+pub use resume::Resume;
+pub use save::Save;
+pub use saved::Saved;
+pub use select_chat::SelectChat;
 pub use set_content_fields::SetContentFields;
+pub use set_date_format::SetDateFormat;
+pub use set_date_language::SetDateLanguage;
+pub use set_dedup_window::SetDedupWindow;
+pub use set_fetch_options::SetFetchOptions;
 pub use set_filter::SetFilter;
 pub use set_global_filter::SetGlobalFilter;
+pub use set_global_link_rules::SetGlobalLinkRules;
 pub use set_global_template::SetGlobalTemplate;
+pub use set_item_buttons::SetItemButtons;
+pub use set_limits::SetLimits;
+pub use set_link_rules::SetLinkRules;
+pub use set_parse_mode::SetParseMode;
+pub use set_quiet_hours::SetQuietHours;
 pub use set_template::SetTemplate;
 pub use set_timezone::SetTimezone;
+pub use set_topic::SetTopic;
 pub use show_feed_keyboard::ShowFeedKeyboard;
+pub use snooze::Snooze;
 pub use start::Start;
 pub use subscribe::Subscribe;
+pub use toggle_interleave_items::ToggleInterleaveItems;
 pub use toggle_preview_enabled::TogglePreviewEnabled;
+pub use toggle_silent::ToggleSilent;
+pub use toggle_split_messages::ToggleSplitMessages;
+pub use toggle_topic_per_feed::ToggleTopicPerFeed;
 pub use unknown_command::UnknownCommand;
+pub use unlink_chat::UnlinkChat;
 pub use unsubscribe::Unsubscribe;
 
+pub mod add_rewrite;
+pub mod backfill;
 pub mod close;
 pub mod commands_keyboard;
+pub mod get_fetch_options;
 pub mod get_filter;
 pub mod get_global_filter;
 pub mod get_global_template;
 pub mod get_preview_enabled;
+pub mod get_quiet_hours;
 pub mod get_template;
 pub mod get_timezone;
 pub mod help;
 pub mod help_command_info;
 pub mod info;
+pub mod item_action;
+pub mod link_chat;
+pub mod list_rewrites;
 pub mod list_subscriptions_keyboard;
+pub mod mark_all_read;
+pub mod pause;
+pub mod remove_fetch_options;
 pub mod remove_filter;
 pub mod remove_global_filter;
 pub mod remove_global_template;
+pub mod remove_quiet_hours;
+pub mod remove_rewrite;
+pub mod remove_saved;
 pub mod remove_template;
+pub mod resume;
+pub mod save;
+pub mod saved;
+pub mod select_chat;
 pub mod set_content_fields;
+pub mod set_date_format;
+pub mod set_date_language;
+pub mod set_dedup_window;
+pub mod set_fetch_options;
 pub mod set_filter;
 pub mod set_global_filter;
+pub mod set_global_link_rules;
 pub mod set_global_template;
+pub mod set_item_buttons;
+pub mod set_limits;
+pub mod set_link_rules;
+pub mod set_parse_mode;
+pub mod set_quiet_hours;
 pub mod set_template;
 pub mod set_timezone;
+pub mod set_topic;
 pub mod show_feed_keyboard;
+pub mod snooze;
 pub mod start;
 pub mod subscribe;
+pub mod toggle_interleave_items;
 pub mod toggle_preview_enabled;
+pub mod toggle_silent;
+pub mod toggle_split_messages;
+pub mod toggle_topic_per_feed;
 pub mod unknown_command;
+pub mod unlink_chat;
 pub mod unsubscribe;
 
+// Admin rights of the selected chat are checked again after this interval
+const ADMIN_CHECK_INTERVAL_SECONDS: i64 = 300;
+
+fn chat_kind(chat_type: &ChatType) -> &'static str {
+    match chat_type {
+        ChatType::Private => "private",
+        ChatType::Group => "group",
+        ChatType::Supergroup => "supergroup",
+        ChatType::Channel => "channel",
+    }
+}
+
+impl From<ChatFullInfo> for NewTelegramChat {
+    fn from(chat: ChatFullInfo) -> Self {
+        NewTelegramChat {
+            id: chat.id,
+            kind: chat_kind(&chat.type_field).to_string(),
+            username: chat.username,
+            first_name: chat.first_name,
+            last_name: chat.last_name,
+            title: chat.title,
+        }
+    }
+}
+
 impl From<Chat> for NewTelegramChat {
     fn from(chat: Chat) -> Self {
-        let kind = match chat.type_field {
-            ChatType::Private => "private",
-            ChatType::Group => "group",
-            ChatType::Supergroup => "supergroup",
-            ChatType::Channel => "channel",
-        };
-
         NewTelegramChat {
             id: chat.id,
-            kind: kind.to_string(),
+            kind: chat_kind(&chat.type_field).to_string(),
             username: chat.username,
             first_name: chat.first_name,
             last_name: chat.last_name,
@@ -105,32 +203,66 @@ impl From<Chat> for NewTelegramChat {
 
 #[derive(Debug)]
 pub enum BotCommand {
+    Backfill(String),
     Close,
     CommandsKeyboard,
+    GetFetchOptions(String),
     GetFilter(String),
     GetGlobalFilter,
     GetGlobalTemplate,
     GetPreviewEnabled,
+    GetQuietHours,
     GetTemplate(String),
     GetTimezone,
     Help,
     HelpCommandInfo(String),
     Info,
+    ItemAction(String),
     ListSubscriptions,
+    MarkAllRead(String),
+    RemoveFetchOptions(String),
     RemoveFilter(String),
     RemoveGlobalFilter,
     RemoveGlobalTemplate,
+    RemoveQuietHours,
+    LinkChat(String),
+    SelectChat(String),
+    UnlinkChat(String),
+    SetTopic(String),
+    ToggleTopicPerFeed,
+    ToggleInterleaveItems,
+    SetDedupWindow(String),
+    SetLinkRules(String),
+    SetGlobalLinkRules(String),
+    AddRewrite(String),
+    ListRewrites(String),
+    RemoveRewrite(String),
+    SetDateFormat(String),
+    SetDateLanguage(String),
+    Pause(String),
+    Resume(String),
+    Snooze(String),
+    SetLimits(String),
+    RemoveSaved(String),
     RemoveTemplate(String),
+    Save,
+    Saved(String),
     SetContentFields(String),
+    SetFetchOptions(String),
     SetFilter(String),
     SetGlobalFilter(String),
     SetGlobalTemplate(String),
+    SetItemButtons(String),
+    SetParseMode(String),
+    SetQuietHours(String),
     SetTemplate(String),
     SetTimezone(String),
     ShowFeedKeyboard(String),
     Start,
     Subscribe(String),
     TogglePreviewEnabled,
+    ToggleSilent(String),
+    ToggleSplitMessages(String),
     UnknownCommand(String),
     Unsubscribe(String),
 }
@@ -159,6 +291,10 @@ impl FromStr for BotCommand {
             BotCommand::Unsubscribe(args)
         } else if command.starts_with(ListSubscriptionsKeyboard::command()) {
             BotCommand::ListSubscriptions
+        } else if command.starts_with(MarkAllRead::command()) {
+            let args = parse_args(MarkAllRead::command(), command);
+
+            BotCommand::MarkAllRead(args)
         } else if command.starts_with(Start::command()) {
             BotCommand::Start
         } else if command.starts_with(SetTimezone::command()) {
@@ -179,6 +315,18 @@ impl FromStr for BotCommand {
             let args = parse_args(RemoveFilter::command(), command);
 
             BotCommand::RemoveFilter(args)
+        } else if command.starts_with(SetFetchOptions::command()) {
+            let args = parse_args(SetFetchOptions::command(), command);
+
+            BotCommand::SetFetchOptions(args)
+        } else if command.starts_with(GetFetchOptions::command()) {
+            let args = parse_args(GetFetchOptions::command(), command);
+
+            BotCommand::GetFetchOptions(args)
+        } else if command.starts_with(RemoveFetchOptions::command()) {
+            let args = parse_args(RemoveFetchOptions::command(), command);
+
+            BotCommand::RemoveFetchOptions(args)
         } else if command.starts_with(SetTemplate::command()) {
             let args = parse_args(SetTemplate::command(), command);
 
@@ -203,6 +351,28 @@ impl FromStr for BotCommand {
             let args = parse_args(SetGlobalTemplate::command(), command);
 
             BotCommand::SetGlobalTemplate(args)
+        } else if command.starts_with(SetParseMode::command()) {
+            let args = parse_args(SetParseMode::command(), command);
+
+            BotCommand::SetParseMode(args)
+        } else if command.starts_with(SetItemButtons::command()) {
+            let args = parse_args(SetItemButtons::command(), command);
+
+            BotCommand::SetItemButtons(args)
+        } else if command.starts_with(ItemAction::command()) {
+            let args = parse_args(ItemAction::command(), command);
+
+            BotCommand::ItemAction(args)
+        } else if command.starts_with(Saved::command()) {
+            let args = parse_args(Saved::command(), command);
+
+            BotCommand::Saved(args)
+        } else if command.starts_with(Save::command()) {
+            BotCommand::Save
+        } else if command.starts_with(RemoveSaved::command()) {
+            let args = parse_args(RemoveSaved::command(), command);
+
+            BotCommand::RemoveSaved(args)
         } else if command.starts_with(GetGlobalFilter::command()) {
             BotCommand::GetGlobalFilter
         } else if command.starts_with(RemoveGlobalFilter::command()) {
@@ -213,6 +383,10 @@ impl FromStr for BotCommand {
             let args = parse_args(SetContentFields::command(), command);
 
             BotCommand::SetContentFields(args)
+        } else if command.starts_with(Backfill::command()) {
+            let args = parse_args(Backfill::command(), command);
+
+            BotCommand::Backfill(args)
         } else if command.starts_with(ShowFeedKeyboard::command()) {
             let args = parse_args(ShowFeedKeyboard::command(), command);
 
@@ -223,6 +397,90 @@ impl FromStr for BotCommand {
             BotCommand::GetPreviewEnabled
         } else if command.starts_with(TogglePreviewEnabled::command()) {
             BotCommand::TogglePreviewEnabled
+        } else if command.starts_with(ToggleSplitMessages::command()) {
+            let args = parse_args(ToggleSplitMessages::command(), command);
+
+            BotCommand::ToggleSplitMessages(args)
+        } else if command.starts_with(ToggleSilent::command()) {
+            let args = parse_args(ToggleSilent::command(), command);
+
+            BotCommand::ToggleSilent(args)
+        } else if command.starts_with(SetQuietHours::command()) {
+            let args = parse_args(SetQuietHours::command(), command);
+
+            BotCommand::SetQuietHours(args)
+        } else if command.starts_with(GetQuietHours::command()) {
+            BotCommand::GetQuietHours
+        } else if command.starts_with(RemoveQuietHours::command()) {
+            BotCommand::RemoveQuietHours
+        } else if command.starts_with(LinkChat::command()) {
+            let args = parse_args(LinkChat::command(), command);
+
+            BotCommand::LinkChat(args)
+        } else if command.starts_with(SelectChat::command()) {
+            let args = parse_args(SelectChat::command(), command);
+
+            BotCommand::SelectChat(args)
+        } else if command.starts_with(UnlinkChat::command()) {
+            let args = parse_args(UnlinkChat::command(), command);
+
+            BotCommand::UnlinkChat(args)
+        } else if command.starts_with(SetTopic::command()) {
+            let args = parse_args(SetTopic::command(), command);
+
+            BotCommand::SetTopic(args)
+        } else if command.starts_with(ToggleTopicPerFeed::command()) {
+            BotCommand::ToggleTopicPerFeed
+        } else if command.starts_with(ToggleInterleaveItems::command()) {
+            BotCommand::ToggleInterleaveItems
+        } else if command.starts_with(SetDedupWindow::command()) {
+            let args = parse_args(SetDedupWindow::command(), command);
+
+            BotCommand::SetDedupWindow(args)
+        } else if command.starts_with(SetLinkRules::command()) {
+            let args = parse_args(SetLinkRules::command(), command);
+
+            BotCommand::SetLinkRules(args)
+        } else if command.starts_with(SetGlobalLinkRules::command()) {
+            let args = parse_args(SetGlobalLinkRules::command(), command);
+
+            BotCommand::SetGlobalLinkRules(args)
+        } else if command.starts_with(AddRewrite::command()) {
+            let args = parse_args(AddRewrite::command(), command);
+
+            BotCommand::AddRewrite(args)
+        } else if command.starts_with(ListRewrites::command()) {
+            let args = parse_args(ListRewrites::command(), command);
+
+            BotCommand::ListRewrites(args)
+        } else if command.starts_with(RemoveRewrite::command()) {
+            let args = parse_args(RemoveRewrite::command(), command);
+
+            BotCommand::RemoveRewrite(args)
+        } else if command.starts_with(SetDateFormat::command()) {
+            let args = parse_args(SetDateFormat::command(), command);
+
+            BotCommand::SetDateFormat(args)
+        } else if command.starts_with(SetDateLanguage::command()) {
+            let args = parse_args(SetDateLanguage::command(), command);
+
+            BotCommand::SetDateLanguage(args)
+        } else if command.starts_with(Pause::command()) {
+            let args = parse_args(Pause::command(), command);
+
+            BotCommand::Pause(args)
+        } else if command.starts_with(Resume::command()) {
+            let args = parse_args(Resume::command(), command);
+
+            BotCommand::Resume(args)
+        } else if command.starts_with(Snooze::command()) {
+            let args = parse_args(Snooze::command(), command);
+
+            BotCommand::Snooze(args)
+        } else if command.starts_with(SetLimits::command()) {
+            let args = parse_args(SetLimits::command(), command);
+
+            BotCommand::SetLimits(args)
         } else {
             BotCommand::UnknownCommand(command.to_string())
         };
@@ -234,11 +492,15 @@ impl FromStr for BotCommand {
 #[derive(Debug)]
 pub enum ArgBotCommand {
     SetContentFields,
+    SetFetchOptions(String),
     SetFilter(String),
     SetGlobalFilter,
     SetGlobalTemplate,
     SetTemplate(String),
     SetTimezone,
+    SetQuietHours,
+    LinkChat,
+    SetLimits(String),
     Subscribe,
     Cancel,
 }
@@ -251,12 +513,18 @@ impl FromStr for ArgBotCommand {
             Err(())
         } else if command.starts_with(Subscribe::command()) {
             Ok(ArgBotCommand::Subscribe)
-        } else if command.starts_with(SetTimezone::command()) {
+        } else if command.starts_with(SetTimezone::command())
+            && parse_args(SetTimezone::command(), command).is_empty()
+        {
             Ok(ArgBotCommand::SetTimezone)
         } else if command.starts_with(SetFilter::command()) {
             let args = parse_args(SetFilter::command(), command);
 
             Ok(ArgBotCommand::SetFilter(args))
+        } else if command.starts_with(SetFetchOptions::command()) {
+            let args = parse_args(SetFetchOptions::command(), command);
+
+            Ok(ArgBotCommand::SetFetchOptions(args))
         } else if command.starts_with(SetTemplate::command()) {
             let args = parse_args(SetTemplate::command(), command);
 
@@ -267,6 +535,14 @@ impl FromStr for ArgBotCommand {
             Ok(ArgBotCommand::SetGlobalTemplate)
         } else if command.starts_with(SetContentFields::command()) {
             Ok(ArgBotCommand::SetContentFields)
+        } else if command.starts_with(SetQuietHours::command()) {
+            Ok(ArgBotCommand::SetQuietHours)
+        } else if command.starts_with(LinkChat::command()) {
+            Ok(ArgBotCommand::LinkChat)
+        } else if command.starts_with(SetLimits::command()) {
+            let args = parse_args(SetLimits::command(), command);
+
+            Ok(ArgBotCommand::SetLimits(args))
         } else if command.starts_with("/cancel") {
             Ok(ArgBotCommand::Cancel)
         } else {
@@ -279,12 +555,31 @@ impl fmt::Display for ArgBotCommand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
             ArgBotCommand::SetContentFields => write!(f, "OK. Send me content fields"),
+            ArgBotCommand::SetFetchOptions(_) => write!(f, "OK. Send me fetch options"),
             ArgBotCommand::SetFilter(_) => write!(f, "OK. Send me filter words"),
             ArgBotCommand::SetGlobalFilter => write!(f, "OK. Send me global filter words"),
             ArgBotCommand::SetGlobalTemplate => write!(f, "OK. Send me global template"),
             ArgBotCommand::SetTemplate(_) => write!(f, "OK. Send me template"),
-            ArgBotCommand::SetTimezone => write!(f, "OK. Send me timezone in minutes"),
-            ArgBotCommand::Subscribe => write!(f, "OK. Send me a feed url"),
+            ArgBotCommand::SetTimezone => write!(
+                f,
+                "OK. Send me timezone name (Europe/Berlin), city or offset in minutes"
+            ),
+            ArgBotCommand::SetQuietHours => write!(
+                f,
+                "OK. Send me quiet hours, for example 23:00-07:00. Add hold to deliver items after quiet hours instead of sending them without notifications"
+            ),
+            ArgBotCommand::SetLimits(_) => write!(
+                f,
+                "OK. Send me limits, for example per_run=5 per_hour=20. Use per_hour=none to remove the hourly limit"
+            ),
+            ArgBotCommand::LinkChat => write!(
+                f,
+                "OK. Send me the username of a channel or a group (for example @my_channel) or its id. Both you and the bot should be its administrators"
+            ),
+            ArgBotCommand::Subscribe => write!(
+                f,
+                "OK. Send me a feed url. Add new, last=N, since=YYYY-MM-DD or all after the url to choose how many past items you'll receive"
+            ),
             ArgBotCommand::Cancel => write!(f, "The command was cancelled"),
         }
     }
@@ -356,7 +651,28 @@ pub trait Command {
     }
 
     fn simple_keyboard(&self, text: String, back_command: String, message: &Message) -> Response {
+        self.options_keyboard(text, vec![], back_command, message)
+    }
+
+    // Every option is a row with a (text, callback data) button
+    fn options_keyboard(
+        &self,
+        text: String,
+        options: Vec<(String, String)>,
+        back_command: String,
+        message: &Message,
+    ) -> Response {
         let mut buttons: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+
+        for (option_text, callback_data) in options {
+            let button = InlineKeyboardButton::builder()
+                .text(option_text)
+                .callback_data(callback_data)
+                .build();
+
+            buttons.push(vec![button]);
+        }
+
         let mut row: Vec<InlineKeyboardButton> = Vec::new();
 
         let button = InlineKeyboardButton::builder()
@@ -403,6 +719,80 @@ pub trait Command {
         telegram_client::api().clone()
     }
 
+    fn is_chat_admin(&self, chat_id: i64, user_id: u64) -> bool {
+        let params = GetChatMemberParams::builder()
+            .chat_id(chat_id)
+            .user_id(user_id)
+            .build();
+
+        match self.api().get_chat_member(&params) {
+            Ok(response) => matches!(
+                response.result,
+                ChatMember::Creator(_) | ChatMember::Administrator(_)
+            ),
+            Err(error) => {
+                error!("Failed to fetch a chat member {error:?}");
+
+                false
+            }
+        }
+    }
+
+    fn create_forum_topic(&self, chat_id: i64, feed: &Feed) -> Result<i32, String> {
+        match self.api().create_feed_topic(chat_id, feed) {
+            Ok(thread_id) => Ok(thread_id),
+            Err(error) => {
+                error!("Failed to create a forum topic {error:?}");
+
+                Err("Failed to create a topic. The chat should be a forum and the bot should be able to manage topics".to_string())
+            }
+        }
+    }
+
+    // A private chat can manage subscriptions of the linked chat selected with /select_chat
+    fn selected_chat(
+        &self,
+        db_connection: &mut PgConnection,
+        message: &Message,
+    ) -> Option<TelegramChat> {
+        if !matches!(message.chat.type_field, ChatType::Private) {
+            return None;
+        }
+
+        let chat = telegram::find_chat(db_connection, message.chat.id)?;
+        let selected_chat_id = chat.selected_chat_id?;
+
+        let admin_checked_at =
+            telegram_linked_chats::find_admin_checked_at(db_connection, chat.id, selected_chat_id)
+                .ok()??;
+
+        // the user could have lost admin rights since the chat was selected
+        if admin_checked_at < db::current_time() - Duration::seconds(ADMIN_CHECK_INTERVAL_SECONDS) {
+            if !self.is_chat_admin(selected_chat_id, chat.id as u64) {
+                if let Err(error) = telegram::set_selected_chat(db_connection, &chat, None) {
+                    error!("Failed to clear the selected chat {error:?}");
+                }
+
+                return None;
+            }
+
+            if let Err(error) =
+                telegram_linked_chats::mark_admin_checked(db_connection, chat.id, selected_chat_id)
+            {
+                error!("Failed to update the admin check time {error:?}");
+            }
+        }
+
+        telegram::find_chat(db_connection, selected_chat_id)
+    }
+
+    fn target_chat_id(&self, db_connection: &mut PgConnection, message: &Message) -> i64 {
+        match self.selected_chat(db_connection, message) {
+            Some(chat) => chat.id,
+            None => message.chat.id,
+        }
+    }
+
     fn find_subscription(
         &self,
         db_connection: &mut PgConnection,
@@ -426,12 +816,12 @@ pub trait Command {
         chat_id: i64,
         feed_url: &str,
     ) -> Result<TelegramSubscription, String> {
-        let not_exists_error = Err("Subscription does not exist".to_string());
-        let feed = self.find_feed(db_connection, feed_url)?;
+        self.find_feed(db_connection, feed_url)?;
 
-        match self.find_subscription_by_chat_id_and_feed_id(db_connection, chat_id, feed.id) {
+        match telegram::find_subscription_by_chat_id_and_feed_link(db_connection, chat_id, feed_url)
+        {
             Some(subscription) => Ok(subscription),
-            None => not_exists_error,
+            None => Err("Subscription does not exist".to_string()),
         }
     }
 
@@ -449,6 +839,48 @@ pub trait Command {
         telegram::find_subscription(db_connection, telegram_subscription)
     }
 
+    // Delivered messages are found by their telegram id
+    fn find_delivered_item(
+        &self,
+        db_connection: &mut PgConnection,
+        chat_id: i64,
+        telegram_message_id: i32,
+    ) -> Result<TelegramDeliveredItem, String> {
+        match telegram_delivered_items::find(db_connection, chat_id, telegram_message_id) {
+            Ok(Some(item)) => Ok(item),
+            _ => Err("The item is not available anymore".to_string()),
+        }
+    }
+
+    // Bookmarks keep a copy of the item because old items are removed
+    fn save_bookmark(
+        &self,
+        db_connection: &mut PgConnection,
+        chat_id: i64,
+        item: TelegramDeliveredItem,
+    ) -> String {
+        let feed_link = match feeds::find(db_connection, item.feed_id) {
+            Some(feed) => feed.link,
+            None => return "Feed does not exist".to_string(),
+        };
+
+        let bookmark = NewTelegramBookmark {
+            chat_id,
+            feed_link,
+            title: item.title,
+            link: item.link,
+            description: item.description,
+            author: item.author,
+            publication_date: item.publication_date,
+        };
+
+        match telegram_bookmarks::create(db_connection, bookmark) {
+            Ok(Some(bookmark)) => format!("Saved {}. See /saved", bookmark.link),
+            Ok(None) => "The item is already saved. See /saved".to_string(),
+            Err(_) => "Failed to save the item".to_string(),
+        }
+    }
+
     fn find_feed(&self, db_connection: &mut PgConnection, feed_url: &str) -> Result<Feed, String> {
         match feeds::find_by_link(db_connection, feed_url) {
             Some(feed) => Ok(feed),
@@ -469,6 +901,19 @@ pub trait Command {
 
         Ok(filter_words)
     }
+
+    fn parse_link_rules(&self, params: &str) -> Result<Vec<String>, String> {
+        let link_rules = links::parse_rules(params)?;
+
+        let filter_limit = Config::filter_limit();
+
+        if link_rules.len() > filter_limit {
+            let err = format!("The number of link rules is limited by {filter_limit}");
+            return Err(err);
+        }
+
+        Ok(link_rules.iter().map(|rule| rule.to_string()).collect())
+    }
 }
 
 #[derive(TypedBuilder)]
@@ -479,6 +924,10 @@ pub struct CommandProcessor {
     text: String,
 
     callback: bool,
+
+    // the user who pressed a button of a callback query
+    #[builder(default)]
+    user_id: Option<u64>,
 }
 
 impl CommandProcessor {
@@ -595,11 +1044,19 @@ impl CommandProcessor {
                 .build()
                 .run(),
 
+            BotCommand::MarkAllRead(args) => MarkAllRead::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
             BotCommand::Start => Start::builder().message(self.message.clone()).build().run(),
 
             BotCommand::SetTimezone(args) => SetTimezone::builder()
                 .message(self.message.clone())
                 .args(args)
+                .callback(self.callback)
                 .build()
                 .run(),
 
@@ -628,6 +1085,26 @@ impl CommandProcessor {
                 .build()
                 .run(),
 
+            BotCommand::SetFetchOptions(args) => SetFetchOptions::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::GetFetchOptions(args) => GetFetchOptions::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::RemoveFetchOptions(args) => RemoveFetchOptions::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
             BotCommand::SetTemplate(args) => SetTemplate::builder()
                 .message(self.message.clone())
                 .args(args)
@@ -654,6 +1131,40 @@ impl CommandProcessor {
                 .build()
                 .run(),
 
+            BotCommand::SetParseMode(args) => SetParseMode::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::SetItemButtons(args) => SetItemButtons::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::ItemAction(args) => ItemAction::builder()
+                .message(self.message.clone())
+                .args(args)
+                .user_id(self.user_id)
+                .build()
+                .run(),
+
+            BotCommand::Save => Save::builder().message(self.message.clone()).build().run(),
+
+            BotCommand::Saved(args) => Saved::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::RemoveSaved(args) => RemoveSaved::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
             BotCommand::RemoveGlobalTemplate => RemoveGlobalTemplate::builder()
                 .message(self.message.clone())
                 .build()
@@ -688,6 +1199,12 @@ impl CommandProcessor {
                 .build()
                 .run(),
 
+            BotCommand::Backfill(args) => Backfill::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
             BotCommand::UnknownCommand(args) => UnknownCommand::builder()
                 .message(self.message.clone())
                 .args(args)
@@ -712,6 +1229,148 @@ impl CommandProcessor {
                 .build()
                 .run(),
 
+            BotCommand::ToggleSplitMessages(args) => ToggleSplitMessages::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::ToggleSilent(args) => ToggleSilent::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::SetQuietHours(args) => SetQuietHours::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::GetQuietHours => GetQuietHours::builder()
+                .message(self.message.clone())
+                .build()
+                .run(),
+
+            BotCommand::RemoveQuietHours => RemoveQuietHours::builder()
+                .message(self.message.clone())
+                .build()
+                .run(),
+
+            BotCommand::LinkChat(args) => LinkChat::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::SelectChat(args) => SelectChat::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::UnlinkChat(args) => UnlinkChat::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::SetTopic(args) => SetTopic::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::ToggleTopicPerFeed => ToggleTopicPerFeed::builder()
+                .message(self.message.clone())
+                .build()
+                .run(),
+
+            BotCommand::ToggleInterleaveItems => ToggleInterleaveItems::builder()
+                .message(self.message.clone())
+                .build()
+                .run(),
+
+            BotCommand::SetDedupWindow(args) => SetDedupWindow::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::SetLinkRules(args) => SetLinkRules::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::SetGlobalLinkRules(args) => SetGlobalLinkRules::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::AddRewrite(args) => AddRewrite::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::ListRewrites(args) => ListRewrites::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::RemoveRewrite(args) => RemoveRewrite::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::SetDateFormat(args) => SetDateFormat::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::SetDateLanguage(args) => SetDateLanguage::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
+            BotCommand::Pause(args) => Pause::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::Resume(args) => Resume::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::Snooze(args) => Snooze::builder()
+                .message(self.message.clone())
+                .args(args)
+                .callback(self.callback)
+                .build()
+                .run(),
+
+            BotCommand::SetLimits(args) => SetLimits::builder()
+                .message(self.message.clone())
+                .args(args)
+                .build()
+                .run(),
+
             BotCommand::ShowFeedKeyboard(args) => ShowFeedKeyboard::builder()
                 .message(self.message.clone())
                 .feed_url_or_external_id(args)
@@ -0,0 +1,218 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::config::Config;
+use crate::db::telegram_rewrite_rules;
+use crate::db::telegram_rewrite_rules::NewTelegramRewriteRule;
+use crate::deliver::rewrite;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/add_rewrite";
+
+#[derive(TypedBuilder)]
+pub struct AddRewrite {
+    message: Message,
+    args: String,
+}
+
+impl AddRewrite {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn add_rewrite(&self, db_connection: &mut PgConnection) -> String {
+        let (feed_url_or_external_id, rule) = match self.args.split_once(' ') {
+            Some(args) => args,
+            None => {
+                return "Rewrite rules should look like: url title pattern => replacement"
+                    .to_string();
+            }
+        };
+
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription =
+            match self.find_subscription(db_connection, chat_id, feed_url_or_external_id) {
+                Err(message) => return message,
+                Ok(subscription) => subscription,
+            };
+
+        let rewrite = match rewrite::parse(rule) {
+            Ok(rewrite) => rewrite,
+            Err(message) => return message,
+        };
+
+        let rules =
+            match telegram_rewrite_rules::find_for_subscription(db_connection, &subscription) {
+                Ok(rules) => rules,
+                Err(_) => return "Failed to add the rewrite rule".to_string(),
+            };
+
+        let rewrite_limit = Config::rewrite_limit();
+
+        if rules.len() >= rewrite_limit {
+            return format!("The number of rewrite rules is limited by {rewrite_limit}");
+        }
+
+        let new_rule = NewTelegramRewriteRule {
+            chat_id: subscription.chat_id,
+            feed_id: subscription.feed_id,
+            field: rewrite.field.to_string(),
+            pattern: rewrite.pattern,
+            replacement: rewrite.replacement,
+        };
+
+        match telegram_rewrite_rules::create(db_connection, new_rule) {
+            Ok(rule) => format!(
+                "The rewrite rule was added:\n\n{}",
+                rewrite::format_rule(&rule)
+            ),
+            Err(_) => "Failed to add the rewrite rule".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for AddRewrite {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.add_rewrite(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
+
+#[cfg(test)]
+mod add_rewrite_tests {
+    use super::AddRewrite;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_rewrite_rules;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    fn add_rewrite(connection: &mut diesel::PgConnection, args: &str) -> String {
+        let message = Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(Chat::builder().id(64).type_field(ChatType::Private).build())
+            .build();
+
+        AddRewrite::builder()
+            .message(message)
+            .args(args.to_string())
+            .build()
+            .add_rewrite(connection)
+    }
+
+    fn create_subscription(connection: &mut diesel::PgConnection) -> NewTelegramSubscription {
+        let chat = telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 64,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap();
+        let feed = feeds::create(connection, "Link94", "rss".to_string()).unwrap();
+        let new_subscription = NewTelegramSubscription::builder()
+            .chat_id(chat.id)
+            .feed_id(feed.id)
+            .build();
+
+        telegram::create_subscription(connection, new_subscription).unwrap();
+
+        new_subscription
+    }
+
+    #[test]
+    fn adds_rewrite_rules() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_subscription(connection);
+
+            assert_eq!(
+                "The rewrite rule was added:\n\ntitle ^\\[Ad\\]\\s*",
+                add_rewrite(connection, "Link94 title ^\\[Ad\\]\\s*")
+            );
+            assert_eq!(
+                "The rewrite rule was added:\n\ndescription (?i)rust => Rust",
+                add_rewrite(connection, "Link94 description  (?i)rust   =>  Rust ")
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+            let rules =
+                telegram_rewrite_rules::find_for_subscription(connection, &subscription).unwrap();
+
+            assert_eq!(2, rules.len());
+            assert_eq!("title", rules[0].field);
+            assert_eq!("^\\[Ad\\]\\s*", rules[0].pattern);
+            assert_eq!("", rules[0].replacement);
+            assert_eq!("description", rules[1].field);
+            assert_eq!("(?i)rust", rules[1].pattern);
+            assert_eq!("Rust", rules[1].replacement);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_subscription(connection);
+
+            assert_eq!(
+                "Rewrite rules should look like: url title pattern => replacement",
+                add_rewrite(connection, "Link94")
+            );
+            assert_eq!(
+                "Feed does not exist",
+                add_rewrite(connection, "Link95 title ad")
+            );
+            assert_eq!(
+                "Unknown field author. Use title or description",
+                add_rewrite(connection, "Link94 author ad")
+            );
+            assert_eq!(
+                "Rewrite rules should look like: title pattern => replacement",
+                add_rewrite(connection, "Link94 title")
+            );
+            assert_eq!(
+                "The pattern can not be empty",
+                add_rewrite(connection, "Link94 title => ad")
+            );
+            assert!(
+                add_rewrite(connection, "Link94 title [ad")
+                    .starts_with("Invalid pattern: regex parse error")
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert!(
+                telegram_rewrite_rules::find_for_subscription(connection, &subscription)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,81 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::unknown_command::UnknownCommand;
+use crate::config::Config;
+use crate::sync::archive;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/backfill";
+
+#[derive(TypedBuilder)]
+pub struct Backfill {
+    message: Message,
+    args: String,
+}
+
+impl Backfill {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    pub fn backfill(&self, db_connection: &mut PgConnection) -> String {
+        let feed_url = self.args.trim();
+
+        if feed_url.is_empty() {
+            return "Feed url can not be empty".to_string();
+        }
+
+        let feed = match self.find_feed(db_connection, feed_url) {
+            Err(message) => return message,
+            Ok(feed) => feed,
+        };
+
+        match archive::backfill(db_connection, &feed) {
+            Ok(0) => "No archived items were found".to_string(),
+            Ok(count) => format!("{count} archived items were saved"),
+            Err(error) => format!("Failed to backfill the feed: {error}"),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+
+    fn unknown_command(&self) {
+        UnknownCommand::builder()
+            .message(self.message.clone())
+            .args(self.message.text.clone().unwrap())
+            .build()
+            .run();
+    }
+}
+
+impl Command for Backfill {
+    fn execute(&self, message: &Message, command: &str) {
+        match Config::admin_telegram_id() {
+            None => self.unknown_command(),
+            Some(id) => {
+                if id == message.chat.id {
+                    info!("{:?} wrote: {}", message.chat.id, command);
+
+                    if let Response::Simple(text) = self.response() {
+                        self.reply_to_message(message, text);
+                    }
+                } else {
+                    self.unknown_command()
+                }
+            }
+        }
+    }
+
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.backfill(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
@@ -3,14 +3,19 @@ use super::Command;
 use super::GetGlobalFilter;
 use super::GetGlobalTemplate;
 use super::GetPreviewEnabled;
+use super::GetQuietHours;
 use super::GetTimezone;
 use super::Help;
+use super::LinkChat;
 use super::ListSubscriptionsKeyboard;
 use super::RemoveGlobalFilter;
 use super::RemoveGlobalTemplate;
+use super::RemoveQuietHours;
 use super::Response;
+use super::SelectChat;
 use super::SetGlobalFilter;
 use super::SetGlobalTemplate;
+use super::SetQuietHours;
 use super::SetTimezone;
 use super::Start;
 use super::Subscribe;
@@ -60,6 +65,11 @@ impl CommandsKeyboard {
                 ("Get timezone", GetTimezone::command()),
                 ("Set timezone", SetTimezone::command()),
             ],
+            vec![
+                ("Get quiet hours", GetQuietHours::command()),
+                ("Set quiet hours", SetQuietHours::command()),
+                ("Remove quiet hours", RemoveQuietHours::command()),
+            ],
             vec![
                 (
                     "Check if previews are enabled",
@@ -67,6 +77,10 @@ impl CommandsKeyboard {
                 ),
                 ("Toggle previews", TogglePreviewEnabled::command()),
             ],
+            vec![
+                ("Link chat", LinkChat::command()),
+                ("Select chat", SelectChat::command()),
+            ],
             vec![("Help", Help::command()), ("Start", Start::command())],
         ];
 
@@ -0,0 +1,75 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::ShowFeedKeyboard;
+use crate::db::feeds;
+use crate::sync::fetch_options::FetchOptions;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/get_fetch_options";
+
+#[derive(TypedBuilder)]
+pub struct GetFetchOptions {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl GetFetchOptions {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn get_fetch_options(&self, db_connection: &mut PgConnection) -> Response {
+        let subscription =
+            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
+                Ok(subscription) => subscription,
+                Err(error) => return Response::Simple(error),
+            };
+
+        let response = match feeds::find(db_connection, subscription.feed_id) {
+            None => "Feed does not exist".to_string(),
+            Some(feed) => match FetchOptions::from_feed(&feed) {
+                Ok(options) if options.is_empty() => {
+                    "You did not set fetch options for this subscription".to_string()
+                }
+                Ok(options) => options.to_string(),
+                Err(error) => error.to_string(),
+            },
+        };
+
+        if self.callback {
+            self.simple_keyboard(
+                response,
+                format!(
+                    "{} {}",
+                    ShowFeedKeyboard::command(),
+                    subscription.external_id
+                ),
+                &self.message,
+            )
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for GetFetchOptions {
+    fn response(&self) -> Response {
+        match self.fetch_db_connection() {
+            Ok(mut connection) => self.get_fetch_options(&mut connection),
+
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
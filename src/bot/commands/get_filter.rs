@@ -21,11 +21,12 @@ impl GetFilter {
     }
 
     fn get_filter(&self, db_connection: &mut PgConnection) -> Response {
-        let subscription =
-            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
-                Ok(subscription) => subscription,
-                Err(error) => return Response::Simple(error),
-            };
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription = match self.find_subscription(db_connection, chat_id, &self.args) {
+            Ok(subscription) => subscription,
+            Err(error) => return Response::Simple(error),
+        };
 
         let response = match subscription.filter_words {
             None => "You did not set a filter for this subcription".to_string(),
@@ -65,3 +66,101 @@ impl Command for GetFilter {
         self.send_message_and_remove(send_message_params, &self.message);
     }
 }
+
+#[cfg(test)]
+mod get_filter_tests {
+    use super::GetFilter;
+    use crate::bot::commands::Response;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_linked_chats;
+    use crate::db::telegram_linked_chats::NewTelegramLinkedChat;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    #[test]
+    fn returns_filter_of_the_selected_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            for (id, kind) in [(53, "private"), (-54, "channel")] {
+                telegram::create_chat(
+                    connection,
+                    NewTelegramChat {
+                        id,
+                        kind: kind.to_string(),
+                        username: None,
+                        first_name: None,
+                        last_name: None,
+                        title: None,
+                    },
+                )
+                .unwrap();
+            }
+
+            telegram_linked_chats::create(
+                connection,
+                NewTelegramLinkedChat {
+                    chat_id: 53,
+                    linked_chat_id: -54,
+                },
+            )
+            .unwrap();
+
+            let feed = feeds::create(connection, "Link95", "rss".to_string()).unwrap();
+
+            for chat_id in [53, -54] {
+                let new_subscription = NewTelegramSubscription::builder()
+                    .chat_id(chat_id)
+                    .feed_id(feed.id)
+                    .build();
+
+                let subscription =
+                    telegram::create_subscription(connection, new_subscription).unwrap();
+
+                telegram::set_filter(
+                    connection,
+                    &subscription,
+                    Some(vec![format!("word{chat_id}")]),
+                )
+                .unwrap();
+            }
+
+            let get_filter = |connection: &mut diesel::PgConnection| {
+                let chat = Chat::builder().id(53).type_field(ChatType::Private).build();
+
+                GetFilter::builder()
+                    .message(
+                        Message::builder()
+                            .message_id(1)
+                            .date(1_u64)
+                            .chat(chat)
+                            .build(),
+                    )
+                    .args("Link95".to_string())
+                    .callback(false)
+                    .build()
+                    .get_filter(connection)
+            };
+
+            let text = |response| match response {
+                Response::Simple(text) => text,
+                Response::Params(_) => panic!("unexpected keyboard"),
+            };
+
+            assert_eq!("word53", text(get_filter(connection)));
+
+            let chat = telegram::find_chat(connection, 53).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-54)).unwrap();
+
+            assert_eq!("word-54", text(get_filter(connection)));
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,58 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use crate::deliver::quiet_hours;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/get_quiet_hours";
+
+#[derive(TypedBuilder)]
+pub struct GetQuietHours {
+    message: Message,
+}
+
+impl GetQuietHours {
+    pub fn run(&self) {
+        self.execute(&self.message, Self::command());
+    }
+
+    fn get_quiet_hours(&self, db_connection: &mut PgConnection) -> String {
+        let chat = match telegram::find_chat(db_connection, self.message.chat.id) {
+            Some(chat) => chat,
+            None => return "You don't have quiet hours set".to_string(),
+        };
+
+        match (chat.quiet_hours_start, chat.quiet_hours_end) {
+            (Some(start), Some(end)) => {
+                let mode = if chat.quiet_hours_hold {
+                    "hold"
+                } else {
+                    "silent"
+                };
+
+                format!(
+                    "Your quiet hours are {} ({mode})",
+                    quiet_hours::format(start, end)
+                )
+            }
+            _ => "You don't have quiet hours set".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for GetQuietHours {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.get_quiet_hours(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
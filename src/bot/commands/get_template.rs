@@ -21,7 +21,9 @@ impl GetTemplate {
     }
 
     fn get_template(&self, db_connection: &mut PgConnection) -> String {
-        match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        match self.find_subscription(db_connection, chat_id, &self.args) {
             Err(message) => message,
             Ok(subscription) => match subscription.template {
                 None => "You did not set a template for this subcription".to_string(),
@@ -57,3 +59,95 @@ impl Command for GetTemplate {
         self.send_message_and_remove(send_message_params, &self.message);
     }
 }
+
+#[cfg(test)]
+mod get_template_tests {
+    use super::GetTemplate;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_linked_chats;
+    use crate::db::telegram_linked_chats::NewTelegramLinkedChat;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    #[test]
+    fn returns_template_of_the_selected_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            for (id, kind) in [(57, "private"), (-58, "channel")] {
+                telegram::create_chat(
+                    connection,
+                    NewTelegramChat {
+                        id,
+                        kind: kind.to_string(),
+                        username: None,
+                        first_name: None,
+                        last_name: None,
+                        title: None,
+                    },
+                )
+                .unwrap();
+            }
+
+            telegram_linked_chats::create(
+                connection,
+                NewTelegramLinkedChat {
+                    chat_id: 57,
+                    linked_chat_id: -58,
+                },
+            )
+            .unwrap();
+
+            let feed = feeds::create(connection, "Link97", "rss".to_string()).unwrap();
+
+            for chat_id in [57, -58] {
+                let new_subscription = NewTelegramSubscription::builder()
+                    .chat_id(chat_id)
+                    .feed_id(feed.id)
+                    .build();
+
+                let subscription =
+                    telegram::create_subscription(connection, new_subscription).unwrap();
+
+                telegram::set_template(
+                    connection,
+                    &subscription,
+                    Some(format!("{{{{title}}}} {chat_id}")),
+                )
+                .unwrap();
+            }
+
+            let get_template = |connection: &mut diesel::PgConnection| {
+                let chat = Chat::builder().id(57).type_field(ChatType::Private).build();
+
+                GetTemplate::builder()
+                    .message(
+                        Message::builder()
+                            .message_id(1)
+                            .date(1_u64)
+                            .chat(chat)
+                            .build(),
+                    )
+                    .args("Link97".to_string())
+                    .callback(false)
+                    .build()
+                    .get_template(connection)
+            };
+
+            assert_eq!("{{title}} 57", get_template(connection));
+
+            let chat = telegram::find_chat(connection, 57).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-58)).unwrap();
+
+            assert_eq!("{{title}} -58", get_template(connection));
+
+            Ok(())
+        });
+    }
+}
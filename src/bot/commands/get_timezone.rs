@@ -2,6 +2,8 @@ use super::Command;
 use super::Message;
 use super::Response;
 use crate::db::telegram;
+use crate::deliver::timezone;
+use chrono::Utc;
 use diesel::PgConnection;
 use typed_builder::TypedBuilder;
 
@@ -20,9 +22,13 @@ impl GetTimezone {
     fn get_timezone(&self, db_connection: &mut PgConnection) -> String {
         match telegram::find_chat(db_connection, self.message.chat.id) {
             None => "You don't have timezone set".to_string(),
-            Some(chat) => match chat.utc_offset_minutes {
-                None => "You don't have timezone set".to_string(),
-                Some(value) => format!("Your timezone offset is {value} minutes"),
+            Some(chat) => match (chat.timezone, chat.utc_offset_minutes) {
+                (Some(timezone), _) => format!(
+                    "Your timezone is {timezone}. The current offset is UTC{}",
+                    timezone::offset_at(Some(&timezone), None, Utc::now())
+                ),
+                (None, Some(value)) => format!("Your timezone offset is {value} minutes"),
+                (None, None) => "You don't have timezone set".to_string(),
             },
         }
     }
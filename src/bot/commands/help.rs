@@ -1,26 +1,57 @@
+use super::AddRewrite;
 use super::Close;
 use super::Command;
+use super::GetFetchOptions;
 use super::GetFilter;
 use super::GetGlobalFilter;
 use super::GetGlobalTemplate;
 use super::GetPreviewEnabled;
+use super::GetQuietHours;
 use super::GetTemplate;
 use super::GetTimezone;
 use super::HelpCommandInfo;
+use super::LinkChat;
+use super::ListRewrites;
 use super::ListSubscriptionsKeyboard;
+use super::MarkAllRead;
+use super::Pause;
+use super::RemoveFetchOptions;
 use super::RemoveFilter;
 use super::RemoveGlobalFilter;
 use super::RemoveGlobalTemplate;
+use super::RemoveQuietHours;
+use super::RemoveRewrite;
 use super::RemoveTemplate;
 use super::Response;
+use super::Resume;
+use super::Save;
+use super::Saved;
+use super::SelectChat;
+use super::SetDateFormat;
+use super::SetDateLanguage;
+use super::SetDedupWindow;
+use super::SetFetchOptions;
 use super::SetFilter;
 use super::SetGlobalFilter;
+use super::SetGlobalLinkRules;
 use super::SetGlobalTemplate;
+use super::SetItemButtons;
+use super::SetLimits;
+use super::SetLinkRules;
+use super::SetParseMode;
+use super::SetQuietHours;
 use super::SetTemplate;
 use super::SetTimezone;
+use super::SetTopic;
+use super::Snooze;
 use super::Start;
 use super::Subscribe;
+use super::ToggleInterleaveItems;
 use super::TogglePreviewEnabled;
+use super::ToggleSilent;
+use super::ToggleSplitMessages;
+use super::ToggleTopicPerFeed;
+use super::UnlinkChat;
 use super::Unsubscribe;
 use frankenstein::methods::SendMessageParams;
 use frankenstein::types::InlineKeyboardButton;
@@ -45,6 +76,7 @@ pub enum HelpCommand {
     Subscribe,
     Unsubscribe,
     ListSubscriptions,
+    MarkAllRead,
     Start,
     SetTimezone,
     GetTimezone,
@@ -54,6 +86,9 @@ pub enum HelpCommand {
     SetTemplate,
     GetTemplate,
     RemoveTemplate,
+    SetFetchOptions,
+    GetFetchOptions,
+    RemoveFetchOptions,
     GetGlobalFilter,
     SetGlobalFilter,
     RemoveGlobalFilter,
@@ -63,6 +98,33 @@ pub enum HelpCommand {
     UnknownCommand,
     GetPreviewEnabled,
     TogglePreviewEnabled,
+    ToggleSplitMessages,
+    SetParseMode,
+    SetItemButtons,
+    Save,
+    Saved,
+    SetQuietHours,
+    GetQuietHours,
+    RemoveQuietHours,
+    ToggleSilent,
+    LinkChat,
+    SelectChat,
+    UnlinkChat,
+    SetTopic,
+    ToggleTopicPerFeed,
+    Pause,
+    Resume,
+    Snooze,
+    SetLimits,
+    ToggleInterleaveItems,
+    SetDedupWindow,
+    SetLinkRules,
+    SetGlobalLinkRules,
+    AddRewrite,
+    ListRewrites,
+    RemoveRewrite,
+    SetDateFormat,
+    SetDateLanguage,
 }
 
 impl fmt::Display for HelpCommand {
@@ -73,6 +135,7 @@ impl fmt::Display for HelpCommand {
             HelpCommand::Subscribe => write!(f, "{}", Subscribe::command()),
             HelpCommand::Unsubscribe => write!(f, "{}", Unsubscribe::command()),
             HelpCommand::ListSubscriptions => write!(f, "{}", ListSubscriptionsKeyboard::command()),
+            HelpCommand::MarkAllRead => write!(f, "{}", MarkAllRead::command()),
             HelpCommand::SetTimezone => write!(f, "{}", SetTimezone::command()),
             HelpCommand::GetTimezone => write!(f, "{}", GetTimezone::command()),
             HelpCommand::SetFilter => write!(f, "{}", SetFilter::command()),
@@ -81,6 +144,9 @@ impl fmt::Display for HelpCommand {
             HelpCommand::SetTemplate => write!(f, "{}", SetTemplate::command()),
             HelpCommand::GetTemplate => write!(f, "{}", GetTemplate::command()),
             HelpCommand::RemoveTemplate => write!(f, "{}", RemoveTemplate::command()),
+            HelpCommand::SetFetchOptions => write!(f, "{}", SetFetchOptions::command()),
+            HelpCommand::GetFetchOptions => write!(f, "{}", GetFetchOptions::command()),
+            HelpCommand::RemoveFetchOptions => write!(f, "{}", RemoveFetchOptions::command()),
             HelpCommand::GetGlobalFilter => write!(f, "{}", GetGlobalFilter::command()),
             HelpCommand::SetGlobalFilter => write!(f, "{}", SetGlobalFilter::command()),
             HelpCommand::RemoveGlobalFilter => write!(f, "{}", RemoveGlobalFilter::command()),
@@ -89,6 +155,35 @@ impl fmt::Display for HelpCommand {
             HelpCommand::RemoveGlobalTemplate => write!(f, "{}", RemoveGlobalTemplate::command()),
             HelpCommand::GetPreviewEnabled => write!(f, "{}", GetPreviewEnabled::command()),
             HelpCommand::TogglePreviewEnabled => write!(f, "{}", TogglePreviewEnabled::command()),
+            HelpCommand::ToggleSplitMessages => write!(f, "{}", ToggleSplitMessages::command()),
+            HelpCommand::SetParseMode => write!(f, "{}", SetParseMode::command()),
+            HelpCommand::SetItemButtons => write!(f, "{}", SetItemButtons::command()),
+            HelpCommand::Save => write!(f, "{}", Save::command()),
+            HelpCommand::Saved => write!(f, "{}", Saved::command()),
+            HelpCommand::SetQuietHours => write!(f, "{}", SetQuietHours::command()),
+            HelpCommand::GetQuietHours => write!(f, "{}", GetQuietHours::command()),
+            HelpCommand::RemoveQuietHours => write!(f, "{}", RemoveQuietHours::command()),
+            HelpCommand::ToggleSilent => write!(f, "{}", ToggleSilent::command()),
+            HelpCommand::LinkChat => write!(f, "{}", LinkChat::command()),
+            HelpCommand::SelectChat => write!(f, "{}", SelectChat::command()),
+            HelpCommand::UnlinkChat => write!(f, "{}", UnlinkChat::command()),
+            HelpCommand::SetTopic => write!(f, "{}", SetTopic::command()),
+            HelpCommand::ToggleTopicPerFeed => write!(f, "{}", ToggleTopicPerFeed::command()),
+            HelpCommand::Pause => write!(f, "{}", Pause::command()),
+            HelpCommand::Resume => write!(f, "{}", Resume::command()),
+            HelpCommand::Snooze => write!(f, "{}", Snooze::command()),
+            HelpCommand::SetLimits => write!(f, "{}", SetLimits::command()),
+            HelpCommand::SetDedupWindow => write!(f, "{}", SetDedupWindow::command()),
+            HelpCommand::SetLinkRules => write!(f, "{}", SetLinkRules::command()),
+            HelpCommand::AddRewrite => write!(f, "{}", AddRewrite::command()),
+            HelpCommand::ListRewrites => write!(f, "{}", ListRewrites::command()),
+            HelpCommand::RemoveRewrite => write!(f, "{}", RemoveRewrite::command()),
+            HelpCommand::SetDateFormat => write!(f, "{}", SetDateFormat::command()),
+            HelpCommand::SetDateLanguage => write!(f, "{}", SetDateLanguage::command()),
+            HelpCommand::SetGlobalLinkRules => write!(f, "{}", SetGlobalLinkRules::command()),
+            HelpCommand::ToggleInterleaveItems => {
+                write!(f, "{}", ToggleInterleaveItems::command())
+            }
             // just a placeholder
             HelpCommand::UnknownCommand => write!(f, "/unknown_command"),
         }
@@ -107,6 +202,8 @@ impl FromStr for HelpCommand {
             HelpCommand::Unsubscribe
         } else if command.starts_with(ListSubscriptionsKeyboard::command()) {
             HelpCommand::ListSubscriptions
+        } else if command.starts_with(MarkAllRead::command()) {
+            HelpCommand::MarkAllRead
         } else if command.starts_with(Start::command()) {
             HelpCommand::Start
         } else if command.starts_with(SetTimezone::command()) {
@@ -125,6 +222,12 @@ impl FromStr for HelpCommand {
             HelpCommand::GetTemplate
         } else if command.starts_with(RemoveTemplate::command()) {
             HelpCommand::RemoveTemplate
+        } else if command.starts_with(SetFetchOptions::command()) {
+            HelpCommand::SetFetchOptions
+        } else if command.starts_with(GetFetchOptions::command()) {
+            HelpCommand::GetFetchOptions
+        } else if command.starts_with(RemoveFetchOptions::command()) {
+            HelpCommand::RemoveFetchOptions
         } else if command.starts_with(SetGlobalFilter::command()) {
             HelpCommand::SetGlobalFilter
         } else if command.starts_with(RemoveGlobalTemplate::command()) {
@@ -141,6 +244,60 @@ impl FromStr for HelpCommand {
             HelpCommand::GetPreviewEnabled
         } else if command.starts_with(TogglePreviewEnabled::command()) {
             HelpCommand::TogglePreviewEnabled
+        } else if command.starts_with(ToggleSplitMessages::command()) {
+            HelpCommand::ToggleSplitMessages
+        } else if command.starts_with(SetParseMode::command()) {
+            HelpCommand::SetParseMode
+        } else if command.starts_with(SetItemButtons::command()) {
+            HelpCommand::SetItemButtons
+        } else if command.starts_with(Saved::command()) {
+            HelpCommand::Saved
+        } else if command.starts_with(Save::command()) {
+            HelpCommand::Save
+        } else if command.starts_with(SetQuietHours::command()) {
+            HelpCommand::SetQuietHours
+        } else if command.starts_with(GetQuietHours::command()) {
+            HelpCommand::GetQuietHours
+        } else if command.starts_with(RemoveQuietHours::command()) {
+            HelpCommand::RemoveQuietHours
+        } else if command.starts_with(ToggleSilent::command()) {
+            HelpCommand::ToggleSilent
+        } else if command.starts_with(LinkChat::command()) {
+            HelpCommand::LinkChat
+        } else if command.starts_with(SelectChat::command()) {
+            HelpCommand::SelectChat
+        } else if command.starts_with(UnlinkChat::command()) {
+            HelpCommand::UnlinkChat
+        } else if command.starts_with(SetTopic::command()) {
+            HelpCommand::SetTopic
+        } else if command.starts_with(ToggleTopicPerFeed::command()) {
+            HelpCommand::ToggleTopicPerFeed
+        } else if command.starts_with(Pause::command()) {
+            HelpCommand::Pause
+        } else if command.starts_with(Resume::command()) {
+            HelpCommand::Resume
+        } else if command.starts_with(Snooze::command()) {
+            HelpCommand::Snooze
+        } else if command.starts_with(SetLimits::command()) {
+            HelpCommand::SetLimits
+        } else if command.starts_with(ToggleInterleaveItems::command()) {
+            HelpCommand::ToggleInterleaveItems
+        } else if command.starts_with(SetDedupWindow::command()) {
+            HelpCommand::SetDedupWindow
+        } else if command.starts_with(SetLinkRules::command()) {
+            HelpCommand::SetLinkRules
+        } else if command.starts_with(SetGlobalLinkRules::command()) {
+            HelpCommand::SetGlobalLinkRules
+        } else if command.starts_with(AddRewrite::command()) {
+            HelpCommand::AddRewrite
+        } else if command.starts_with(ListRewrites::command()) {
+            HelpCommand::ListRewrites
+        } else if command.starts_with(RemoveRewrite::command()) {
+            HelpCommand::RemoveRewrite
+        } else if command.starts_with(SetDateFormat::command()) {
+            HelpCommand::SetDateFormat
+        } else if command.starts_with(SetDateLanguage::command()) {
+            HelpCommand::SetDateLanguage
         } else {
             HelpCommand::UnknownCommand
         };
@@ -163,12 +320,14 @@ impl Help {
         let rows = [
             vec![HelpCommand::Help, HelpCommand::Start],
             vec![HelpCommand::Subscribe, HelpCommand::Unsubscribe],
-            vec![HelpCommand::ListSubscriptions],
+            vec![HelpCommand::ListSubscriptions, HelpCommand::MarkAllRead],
             vec![HelpCommand::SetTimezone, HelpCommand::GetTimezone],
             vec![HelpCommand::SetFilter, HelpCommand::GetFilter],
             vec![HelpCommand::RemoveFilter],
             vec![HelpCommand::SetTemplate, HelpCommand::GetTemplate],
             vec![HelpCommand::RemoveTemplate],
+            vec![HelpCommand::SetFetchOptions, HelpCommand::GetFetchOptions],
+            vec![HelpCommand::RemoveFetchOptions],
             vec![HelpCommand::GetGlobalFilter, HelpCommand::SetGlobalFilter],
             vec![HelpCommand::RemoveGlobalFilter],
             vec![
@@ -180,6 +339,24 @@ impl Help {
                 HelpCommand::TogglePreviewEnabled,
             ],
             vec![HelpCommand::RemoveGlobalTemplate],
+            vec![HelpCommand::ToggleSplitMessages, HelpCommand::SetParseMode],
+            vec![HelpCommand::SetItemButtons],
+            vec![HelpCommand::Save, HelpCommand::Saved],
+            vec![HelpCommand::SetQuietHours, HelpCommand::GetQuietHours],
+            vec![HelpCommand::RemoveQuietHours, HelpCommand::ToggleSilent],
+            vec![HelpCommand::LinkChat, HelpCommand::SelectChat],
+            vec![HelpCommand::UnlinkChat],
+            vec![HelpCommand::SetTopic, HelpCommand::ToggleTopicPerFeed],
+            vec![HelpCommand::Pause, HelpCommand::Snooze, HelpCommand::Resume],
+            vec![HelpCommand::SetLimits, HelpCommand::ToggleInterleaveItems],
+            vec![HelpCommand::SetDedupWindow],
+            vec![HelpCommand::SetLinkRules, HelpCommand::SetGlobalLinkRules],
+            vec![
+                HelpCommand::AddRewrite,
+                HelpCommand::ListRewrites,
+                HelpCommand::RemoveRewrite,
+            ],
+            vec![HelpCommand::SetDateFormat, HelpCommand::SetDateLanguage],
         ];
 
         for command_row in rows {
@@ -8,18 +8,27 @@ use std::str::FromStr;
 use typed_builder::TypedBuilder;
 
 static START: &str = "/start - show the description of the bot and its contact information";
-static SUBSCRIBE: &str = "/subscribe url - subscribe to a feed";
+static SUBSCRIBE: &str = "/subscribe url [options] - subscribe to a feed. By default, up to 10 last items are sent right away. You can choose it with one of these options:\n\
+     - new - receive only new items\n\
+     - last=N - receive N last items\n\
+     - since=YYYY-MM-DD - receive items published since the date\n\
+     - all - receive all items of the feed\n\
+     At most 50 past items are sent\n\
+     Example: /subscribe https://www.badykov.com/feed.xml last=3\n\
+     Also, fetch options can be passed. See /set_fetch_options";
 static UNSUBSCRIBE: &str = "/unsubscribe url - unsubscribe from a feed";
 static LIST_SUBSCRIPTIONS: &str = "/list_subscriptions - list your subscriptions";
+static MARK_ALL_READ: &str = "/mark_all_read url - skip all undelivered items of the subscription";
 static HELP: &str = "/help - show available commands";
-static SET_TIMEZONE: &str = "/set_timezone timezone_minutes - set your timezone. All received dates will be converted to this timezone. It should be offset in minutes from UTC. For example, if you live in UTC +10 timezone, your offset is equal to 60 x 10 = 600";
+static SET_TIMEZONE: &str = "/set_timezone timezone - set your timezone. All received dates will be converted to this timezone. It can be a timezone name like Europe/Berlin, which follows daylight saving time, or a city to search for it, for example /set_timezone berlin. Without a value common timezones are suggested. It can also be a fixed offset in minutes from UTC. For example, if you live in UTC +10 timezone, your offset is equal to 60 x 10 = 600";
 static GET_TIMEZONE: &str = "/get_timezone - get your timezone";
 static GET_TEMPLATE: &str = "/get_template feed_url - get the template for the subscription";
 static SET_TEMPLATE: &str = "/set_template url template - set a template for all received feed items for the specified subscription. All new updates will be converted to the format defined by this subscription. Supported fields you can use for templates:\n\
      - bot_feed_name - name of the feed\n\
      - bot_feed_link - url of the feed\n\
      - bot_item_name - name of the item\n\
-     - bot_item_link - url of the item\n\
+     - bot_item_link - url of the item after link rules (see /set_link_rules)\n\
+     - bot_item_original_link - url of the item as it is in the feed\n\
      - bot_item_description - description of the item\n\
      - bot_item_author - author of the item\n\
      - bot_date - publication date of the feed\n\
@@ -28,10 +37,26 @@ static SET_TEMPLATE: &str = "/set_template url template - set a template for all
      - `substring` helper that can be used to limit the number of characters. For example, {{substring bot_item_description 100}}\n\
      - `create_link` helper. This helper creates an html link. For example, {{create_link bot_item_name bot_item_link}} or {{create_link \"custom_name\" bot_item_link}}\n\
      - `italic` helper. Usage: {{italic bot_item_description}}\n\
-     - `bold` helper. Usage:  {{bold bot_item_name}}\n\n";
+     - `bold` helper. Usage:  {{bold bot_item_name}}\n\
+     - `format_date` helper formats the publication date with a strftime pattern. Usage: {{format_date \"%d %B %Y\"}}\n\
+     - `relative_date` helper prints how long ago the item was published, for example 2 hours ago. It's always in English. Usage: {{relative_date}}\n\n\
+     The default format of bot_date can be changed with /set_date_format. Names of months and weekdays are in English until a language is set with /set_date_language. The date language is a separate chat setting, it's not taken from the Telegram app language\n\n";
 
 static REMOVE_TEMPLATE: &str =
     "/remove_template feed_url - remove the template for the subscription";
+static SET_FETCH_OPTIONS: &str = "/set_fetch_options url options - set HTTP options used to fetch the feed. Options are separated by spaces:\n\
+     - basic=username:password - basic authentication\n\
+     - bearer=token - bearer token authentication\n\
+     - header=Name:value - a custom header\n\
+     - cookie=name=value - a cookie\n\
+     - user_agent=value - a custom user agent\n\
+     - proxy=url - a proxy (http, https, socks5). Use proxy=tor to fetch the feed through Tor\n\
+     Example: /set_fetch_options https://www.badykov.com/feed.xml bearer=token user_agent=my_reader\n\
+     Options can also be passed to /subscribe after the url. Secrets are stored encrypted and your message is removed";
+static GET_FETCH_OPTIONS: &str =
+    "/get_fetch_options url - get fetch options for the subscription. Secrets are masked";
+static REMOVE_FETCH_OPTIONS: &str =
+    "/remove_fetch_options url - remove fetch options for the subscription";
 static SET_GLOBAL_TEMPLATE: &str = "/set_global_template - set the global template. This template will be used for all subscriptions. If the subscription has its own template, it will be used instead. See /set_template for available fields.";
 static GET_GLOBAL_TEMPLATE: &str = "/get_global_template - get global template";
 static REMOVE_GLOBAL_TEMPLATE: &str = "/get_global_template - get the global template";
@@ -43,6 +68,41 @@ static GET_GLOBAL_FILTER: &str = "/get_global_filter - get a global filter";
 static REMOVE_GLOBAL_FILTER: &str = "/remove_global_filter - remove the global filter";
 static GET_PREVIEW_ENABLED: &str = "/get_preview_enabled - check if previews are enabled for the current chat. by default, previews are enabled";
 static TOGGLE_PREVIEW_ENABLED: &str = "/toggle_preview_enabled - disable or enable previews";
+static SET_PARSE_MODE: &str = "/set_parse_mode html|markdownv2 - choose how templates of the chat are formatted. With markdownv2 the `bold`, `italic`, `create_link` and `substring` helpers produce Telegram MarkdownV2 and all fields are escaped. The default is html";
+static SET_ITEM_BUTTONS: &str = "/set_item_buttons url buttons - add buttons to delivered items of the subscription. Buttons are separated by commas:\n\
+     - mute - stop delivering the feed until the button is pressed again\n\
+     - unsubscribe - unsubscribe from the feed\n\
+     - description - show the full description of the item\n\
+     - author - stop receiving items by the author of the item\n\
+     - save - save the item for later, see /saved\n\
+     Example: /set_item_buttons https://www.badykov.com/feed.xml description,mute\n\
+     Use `none` to remove buttons. In groups and channels only administrators can use the buttons";
+static SAVE: &str = "/save - reply with this command to a delivered item to save it for later. Saved items are kept even after the bot removes old items of the feed";
+static SAVED: &str =
+    "/saved - list your saved items. Use the buttons to switch pages and remove items";
+static SET_QUIET_HOURS: &str = "/set_quiet_hours start-end [hold|silent] - set quiet hours in your timezone, for example /set_quiet_hours 23:00-07:00. By default items are sent without notifications during quiet hours. With hold they are delivered after quiet hours end";
+static GET_QUIET_HOURS: &str = "/get_quiet_hours - get your quiet hours";
+static REMOVE_QUIET_HOURS: &str = "/remove_quiet_hours - remove quiet hours";
+static TOGGLE_SILENT: &str = "/toggle_silent url - always send items of the subscription without notifications. Send it again to enable notifications";
+static LINK_CHAT: &str = "/link_chat @username_or_id - link a channel or a group to manage its subscriptions from the private chat with the bot. Both you and the bot should be administrators of the chat";
+static SELECT_CHAT: &str = "/select_chat - select the chat managed by /subscribe, /set_filter, /set_template and /list_subscriptions. It can be this chat or one of the linked chats";
+static UNLINK_CHAT: &str = "/unlink_chat id - unlink a chat. Its subscriptions are kept";
+static SET_TOPIC: &str = "/set_topic url [topic] - move a subscription to another forum topic. Without the topic it's moved to the topic where the command was sent, so the topic is required for a chat selected with /select_chat. The topic can be a topic id, general or new. With new a topic named after the feed is created";
+static TOGGLE_TOPIC_PER_FEED: &str = "/toggle_topic_per_feed - create a forum topic named after the feed for every new subscription. If the topic of a subscription is closed, a new topic is created. Send it again to disable";
+static PAUSE: &str = "/pause url - pause a subscription. Its filter, template and delivery position are kept. The feed isn't synced while all its subscriptions are paused";
+static RESUME: &str = "/resume url [deliver|skip] - resume a paused subscription. With deliver the items published while it was paused are delivered, with skip they are marked as read";
+static SNOOZE: &str = "/snooze url duration - pause a subscription for some time, for example /snooze url 3d. The duration can be in minutes (30m), hours (8h), days (3d) or weeks (2w). Items published while it's snoozed are delivered after";
+static SET_LIMITS: &str = "/set_limits url [per_run=N] [per_hour=N] - set how many items of the subscription are delivered at once (10 by default, per_run=default resets it) and at most per hour (per_hour=none removes the limit). Items over the hourly limit are sent as one summary message with their links. Without options it shows the current limits";
+static TOGGLE_INTERLEAVE_ITEMS: &str = "/toggle_interleave_items - send new items of all subscriptions together in publication order instead of feed by feed. Send it again to disable";
+static SET_DEDUP_WINDOW: &str = "/set_dedup_window [days|off] - skip items whose link (without tracking parameters), guid or title matches an item delivered to the chat in the last days (up to 30). Useful when several feeds publish the same articles. Without options it shows the current window";
+static SET_LINK_RULES: &str = "/set_link_rules url [rules|none|default] - rewrite item links of the subscription before they're sent. Rules are separated by spaces: strip_trackers removes tracking parameters like utm_source or fbclid, unwrap_redirects replaces redirect links that contain the target url (Google, Facebook, YouTube, FeedBurner and others) with the target url, host=other.host replaces the host, for example twitter.com=nitter.net. Rules of the subscription are used instead of global link rules. none turns off link rewriting for the subscription, default restores global link rules. Without rules it shows the current rules";
+static SET_GLOBAL_LINK_RULES: &str = "/set_global_link_rules [rules|none|default] - rewrite item links of all subscriptions. Rules are the same as for /set_link_rules. By default tracking parameters are stripped (strip_trackers). none turns off link rewriting, default restores strip_trackers. Without rules it shows the current rules";
+static ADD_REWRITE: &str = "/add_rewrite url field pattern => replacement - rewrite titles or descriptions of the subscription's items with a regular expression before they're filtered and sent. The field is title or description. The pattern and the replacement are separated by => with spaces around it. Patterns can contain => without spaces, use \\s=>\\s to match it with spaces. Without the replacement matches are removed. For example, /add_rewrite url title ^\\[Sponsored\\]\\s* or /add_rewrite url description The post .* appeared first on .*";
+static LIST_REWRITES: &str = "/list_rewrites url - list rewrite rules of the subscription";
+static REMOVE_REWRITE: &str = "/remove_rewrite url number - remove a rewrite rule of the subscription. The number is shown by /list_rewrites";
+static SET_DATE_FORMAT: &str = "/set_date_format [format|default] - set the format of bot_date in templates of the chat. The format is a strftime pattern, for example /set_date_format %d %B %Y, %H:%M. default restores the default format. Without a format it shows the current format";
+static SET_DATE_LANGUAGE: &str = "/set_date_language [language|default] - set the language of month and weekday names in dates of the chat. Supported languages: en, de, es, fr, it, pt, ru, uk. It's separate from the Telegram app language and is en by default. default restores en. Without a language it shows the current language";
+static TOGGLE_SPLIT_MESSAGES: &str = "/toggle_split_messages url - split long items of the subscription into several messages instead of truncating them. Send it again to switch back to truncation";
 static UNKNOWN_COMMAND: &str = "unknown command";
 
 static COMMAND: &str = "/help_command";
@@ -78,6 +138,7 @@ impl HelpCommandInfo {
             HelpCommand::Subscribe => SUBSCRIBE.to_string(),
             HelpCommand::Unsubscribe => UNSUBSCRIBE.to_string(),
             HelpCommand::ListSubscriptions => LIST_SUBSCRIPTIONS.to_string(),
+            HelpCommand::MarkAllRead => MARK_ALL_READ.to_string(),
             HelpCommand::SetTimezone => SET_TIMEZONE.to_string(),
             HelpCommand::GetTimezone => GET_TIMEZONE.to_string(),
             HelpCommand::SetFilter => SET_FILTER.to_string(),
@@ -86,6 +147,9 @@ impl HelpCommandInfo {
             HelpCommand::SetTemplate => SET_TEMPLATE.to_string(),
             HelpCommand::GetTemplate => GET_TEMPLATE.to_string(),
             HelpCommand::RemoveTemplate => REMOVE_TEMPLATE.to_string(),
+            HelpCommand::SetFetchOptions => SET_FETCH_OPTIONS.to_string(),
+            HelpCommand::GetFetchOptions => GET_FETCH_OPTIONS.to_string(),
+            HelpCommand::RemoveFetchOptions => REMOVE_FETCH_OPTIONS.to_string(),
             HelpCommand::GetGlobalFilter => GET_GLOBAL_FILTER.to_string(),
             HelpCommand::SetGlobalFilter => SET_GLOBAL_FILTER.to_string(),
             HelpCommand::RemoveGlobalFilter => REMOVE_GLOBAL_FILTER.to_string(),
@@ -94,6 +158,33 @@ impl HelpCommandInfo {
             HelpCommand::RemoveGlobalTemplate => REMOVE_GLOBAL_TEMPLATE.to_string(),
             HelpCommand::GetPreviewEnabled => GET_PREVIEW_ENABLED.to_string(),
             HelpCommand::TogglePreviewEnabled => TOGGLE_PREVIEW_ENABLED.to_string(),
+            HelpCommand::ToggleSplitMessages => TOGGLE_SPLIT_MESSAGES.to_string(),
+            HelpCommand::SetParseMode => SET_PARSE_MODE.to_string(),
+            HelpCommand::SetItemButtons => SET_ITEM_BUTTONS.to_string(),
+            HelpCommand::Save => SAVE.to_string(),
+            HelpCommand::Saved => SAVED.to_string(),
+            HelpCommand::SetQuietHours => SET_QUIET_HOURS.to_string(),
+            HelpCommand::GetQuietHours => GET_QUIET_HOURS.to_string(),
+            HelpCommand::RemoveQuietHours => REMOVE_QUIET_HOURS.to_string(),
+            HelpCommand::ToggleSilent => TOGGLE_SILENT.to_string(),
+            HelpCommand::LinkChat => LINK_CHAT.to_string(),
+            HelpCommand::SelectChat => SELECT_CHAT.to_string(),
+            HelpCommand::UnlinkChat => UNLINK_CHAT.to_string(),
+            HelpCommand::SetTopic => SET_TOPIC.to_string(),
+            HelpCommand::ToggleTopicPerFeed => TOGGLE_TOPIC_PER_FEED.to_string(),
+            HelpCommand::Pause => PAUSE.to_string(),
+            HelpCommand::Resume => RESUME.to_string(),
+            HelpCommand::Snooze => SNOOZE.to_string(),
+            HelpCommand::SetLimits => SET_LIMITS.to_string(),
+            HelpCommand::SetDedupWindow => SET_DEDUP_WINDOW.to_string(),
+            HelpCommand::SetLinkRules => SET_LINK_RULES.to_string(),
+            HelpCommand::AddRewrite => ADD_REWRITE.to_string(),
+            HelpCommand::ListRewrites => LIST_REWRITES.to_string(),
+            HelpCommand::RemoveRewrite => REMOVE_REWRITE.to_string(),
+            HelpCommand::SetDateFormat => SET_DATE_FORMAT.to_string(),
+            HelpCommand::SetDateLanguage => SET_DATE_LANGUAGE.to_string(),
+            HelpCommand::SetGlobalLinkRules => SET_GLOBAL_LINK_RULES.to_string(),
+            HelpCommand::ToggleInterleaveItems => TOGGLE_INTERLEAVE_ITEMS.to_string(),
             HelpCommand::UnknownCommand => UNKNOWN_COMMAND.to_string(),
         }
     }
@@ -0,0 +1,309 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::bot::SimpleMessageParams;
+use crate::db::feeds;
+use crate::db::telegram;
+use crate::db::telegram::NewTelegramSubscription;
+use crate::deliver::render_message;
+use crate::models::TelegramDeliveredItem;
+use crate::models::TelegramSubscription;
+use diesel::PgConnection;
+use frankenstein::types::ChatType;
+use frankenstein::types::InlineKeyboardButton;
+use frankenstein::types::InlineKeyboardMarkup;
+use std::str::FromStr;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/item_action";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ItemButton {
+    Mute,
+    Unsubscribe,
+    Description,
+    Author,
+    Save,
+}
+
+impl ItemButton {
+    pub const ALL: [ItemButton; 5] = [
+        ItemButton::Mute,
+        ItemButton::Unsubscribe,
+        ItemButton::Description,
+        ItemButton::Author,
+        ItemButton::Save,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ItemButton::Mute => "mute",
+            ItemButton::Unsubscribe => "unsubscribe",
+            ItemButton::Description => "description",
+            ItemButton::Author => "author",
+            ItemButton::Save => "save",
+        }
+    }
+
+    fn text(&self) -> &'static str {
+        match self {
+            ItemButton::Mute => "Mute this feed",
+            ItemButton::Unsubscribe => "Unsubscribe",
+            ItemButton::Description => "Show full description",
+            ItemButton::Author => "Filter out this author",
+            ItemButton::Save => "Save for later",
+        }
+    }
+}
+
+impl FromStr for ItemButton {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        ItemButton::ALL
+            .into_iter()
+            .find(|button| button.name() == name)
+            .ok_or(())
+    }
+}
+
+#[derive(TypedBuilder)]
+pub struct ItemAction {
+    message: Message,
+    args: String,
+    // the user who pressed the button
+    user_id: Option<u64>,
+}
+
+impl ItemAction {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+
+    // Buttons attached to delivered items of the subscription.
+    // Callback data is limited by 64 bytes so the subscription is referenced by its external id
+    pub fn keyboard(subscription: &TelegramSubscription) -> Option<InlineKeyboardMarkup> {
+        let buttons: Vec<InlineKeyboardButton> = subscription
+            .item_buttons
+            .as_ref()?
+            .iter()
+            .filter_map(|name| ItemButton::from_str(name).ok())
+            .map(|button| {
+                InlineKeyboardButton::builder()
+                    .text(button.text())
+                    .callback_data(format!(
+                        "{} {} {}",
+                        Self::command(),
+                        button.name(),
+                        subscription.external_id
+                    ))
+                    .build()
+            })
+            .collect();
+
+        if buttons.is_empty() {
+            return None;
+        }
+
+        let rows = buttons.chunks(2).map(|row| row.to_vec()).collect();
+
+        Some(
+            InlineKeyboardMarkup::builder()
+                .inline_keyboard(rows)
+                .build(),
+        )
+    }
+
+    fn item_action(&self, db_connection: &mut PgConnection) -> String {
+        let (button, external_id) = match self.args.split_once(' ') {
+            Some((button, external_id)) => match ItemButton::from_str(button) {
+                Ok(button) => (button, external_id),
+                Err(_) => return "Unknown action".to_string(),
+            },
+            None => return "Wrong number of parameters".to_string(),
+        };
+
+        let subscription =
+            match self.find_subscription(db_connection, self.message.chat.id, external_id) {
+                Ok(subscription) if subscription.chat_id == self.message.chat.id => subscription,
+                _ => return "The subscription does not exist".to_string(),
+            };
+
+        if !self.allowed() {
+            return "Only administrators can manage subscriptions of this chat".to_string();
+        }
+
+        match button {
+            ItemButton::Mute => self.toggle_muted(db_connection, &subscription),
+            ItemButton::Unsubscribe => self.unsubscribe(db_connection, &subscription),
+            ItemButton::Description => self.show_description(db_connection, &subscription),
+            ItemButton::Author => self.exclude_author(db_connection, &subscription),
+            ItemButton::Save => self.save(db_connection, &subscription),
+        }
+    }
+
+    // In groups and channels anyone who sees the message can press the button
+    fn allowed(&self) -> bool {
+        if let ChatType::Private = self.message.chat.type_field {
+            return true;
+        }
+
+        let user_id = match self.user_id {
+            Some(user_id) => user_id,
+            None => return false,
+        };
+
+        self.is_chat_admin(self.message.chat.id, user_id)
+    }
+
+    fn toggle_muted(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> String {
+        let link = self.feed_link(db_connection, subscription);
+
+        match telegram::set_muted(db_connection, subscription, !subscription.muted) {
+            Ok(subscription) if subscription.muted => {
+                format!("{link} is muted. Press the button again to unmute it")
+            }
+            Ok(_) => format!("{link} is unmuted"),
+            Err(_) => "Failed to update the subscription".to_string(),
+        }
+    }
+
+    fn unsubscribe(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> String {
+        let link = self.feed_link(db_connection, subscription);
+
+        let telegram_subscription = NewTelegramSubscription::builder()
+            .chat_id(subscription.chat_id)
+            .feed_id(subscription.feed_id)
+            .build();
+
+        match telegram::remove_subscription(db_connection, telegram_subscription) {
+            Ok(_) => format!("Successfully unsubscribed from {link}"),
+            Err(_) => format!("Failed to unsubscribe from {link}"),
+        }
+    }
+
+    fn show_description(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> String {
+        let item = match self.find_item(db_connection, subscription) {
+            Ok(item) => item,
+            Err(error) => return error,
+        };
+
+        let description = match item.description {
+            Some(description) => render_message::render_description(&description),
+            None => vec![],
+        };
+
+        let (last, parts) = match description.split_last() {
+            Some(split) => split,
+            None => return "The item doesn't have a description".to_string(),
+        };
+
+        // all parts except the last one are sent here, the last one is the response
+        for part in parts {
+            let message_params = SimpleMessageParams::builder()
+                .message(part.clone())
+                .chat_id(self.message.chat.id)
+                .reply_message_id(self.message.message_id)
+                .message_thread_id(self.message.message_thread_id)
+                .build();
+
+            if let Err(error) = self.api().reply_with_text_message(&message_params) {
+                log::error!("Failed to send a description {error:?}");
+            }
+        }
+
+        last.clone()
+    }
+
+    fn exclude_author(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> String {
+        let item = match self.find_item(db_connection, subscription) {
+            Ok(item) => item,
+            Err(error) => return error,
+        };
+
+        let author = match item.author {
+            Some(author) if !author.trim().is_empty() => author.trim().to_string(),
+            _ => return "The item doesn't have an author".to_string(),
+        };
+
+        let mut excluded_authors = subscription.excluded_authors.clone().unwrap_or_default();
+
+        if !excluded_authors
+            .iter()
+            .any(|excluded| excluded.eq_ignore_ascii_case(&author))
+        {
+            excluded_authors.push(author.clone());
+        }
+
+        match telegram::set_excluded_authors(db_connection, subscription, Some(excluded_authors)) {
+            Ok(_) => format!("Items by {author} will be filtered out"),
+            Err(_) => "Failed to update the subscription".to_string(),
+        }
+    }
+
+    fn save(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> String {
+        match self.find_item(db_connection, subscription) {
+            Ok(item) => self.save_bookmark(db_connection, subscription.chat_id, item),
+            Err(error) => error,
+        }
+    }
+
+    fn find_item(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> Result<TelegramDeliveredItem, String> {
+        match self.find_delivered_item(db_connection, subscription.chat_id, self.message.message_id)
+        {
+            Ok(item) if item.feed_id == subscription.feed_id => Ok(item),
+            Ok(_) => Err("The item is not available anymore".to_string()),
+            Err(error) => Err(error),
+        }
+    }
+
+    fn feed_link(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> String {
+        match feeds::find(db_connection, subscription.feed_id) {
+            Some(feed) => feed.link,
+            None => "The feed".to_string(),
+        }
+    }
+}
+
+impl Command for ItemAction {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.item_action(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
@@ -0,0 +1,189 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::SelectChat;
+use crate::db::telegram;
+use crate::db::telegram_linked_chats;
+use crate::db::telegram_linked_chats::NewTelegramLinkedChat;
+use diesel::Connection;
+use diesel::PgConnection;
+use frankenstein::TelegramApi;
+use frankenstein::methods::GetChatParams;
+use frankenstein::types::ChatFullInfo;
+use frankenstein::types::ChatId;
+use frankenstein::types::ChatType;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/link_chat";
+
+#[derive(TypedBuilder)]
+pub struct LinkChat {
+    message: Message,
+    args: String,
+}
+
+impl LinkChat {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn link_chat(&self, db_connection: &mut PgConnection) -> String {
+        if !matches!(self.message.chat.type_field, ChatType::Private) {
+            return "Chats can be linked only in the private chat with the bot".to_string();
+        }
+
+        let chat = match self.fetch_chat() {
+            Ok(chat) => chat,
+            Err(message) => return message,
+        };
+
+        if let ChatType::Private = chat.type_field {
+            return "Only groups and channels can be linked".to_string();
+        }
+
+        // in private chats the chat id is the id of the user
+        if !self.is_chat_admin(chat.id, self.message.chat.id as u64) {
+            return "You should be an administrator of the chat".to_string();
+        }
+
+        let bot_id = match self.api().get_me() {
+            Ok(response) => response.result.id,
+            Err(_) => return "Failed to link the chat".to_string(),
+        };
+
+        if !self.is_chat_admin(chat.id, bot_id) {
+            return "The bot should be an administrator of the chat".to_string();
+        }
+
+        let result = db_connection.transaction::<_, diesel::result::Error, _>(|db_connection| {
+            let private_chat =
+                telegram::create_chat(db_connection, (*self.message.chat.clone()).into())?;
+            let linked_chat = telegram::create_chat(db_connection, chat.into())?;
+
+            telegram_linked_chats::create(
+                db_connection,
+                NewTelegramLinkedChat {
+                    chat_id: private_chat.id,
+                    linked_chat_id: linked_chat.id,
+                },
+            )?;
+
+            Ok(linked_chat)
+        });
+
+        match result {
+            Ok(linked_chat) => format!(
+                "{} was linked. Select it with {} to manage its subscriptions from this chat",
+                SelectChat::chat_name(&linked_chat),
+                SelectChat::command()
+            ),
+            Err(_) => "Failed to link the chat".to_string(),
+        }
+    }
+
+    fn fetch_chat(&self) -> Result<ChatFullInfo, String> {
+        let args = self.args.trim();
+
+        let chat_id = match args.parse::<i64>() {
+            Ok(id) => ChatId::Integer(id),
+            Err(_) if args.starts_with('@') && args.len() > 1 => ChatId::String(args.to_string()),
+            Err(_) => {
+                return Err(
+                    "Send me the username of the chat (for example @my_channel) or its id"
+                        .to_string(),
+                );
+            }
+        };
+
+        let params = GetChatParams::builder().chat_id(chat_id).build();
+
+        match self.api().get_chat(&params) {
+            Ok(response) => Ok(response.result),
+            Err(_) => Err(
+                "The chat was not found. Add the bot to the chat as an administrator first"
+                    .to_string(),
+            ),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for LinkChat {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.link_chat(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
+
+#[cfg(test)]
+mod link_chat_tests {
+    use super::LinkChat;
+    use crate::db;
+    use crate::db::telegram_linked_chats;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    fn link_chat(connection: &mut diesel::PgConnection, chat_type: ChatType, args: &str) -> String {
+        let chat = Chat::builder().id(46).type_field(chat_type).build();
+        let message = Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(chat)
+            .build();
+
+        LinkChat::builder()
+            .message(message)
+            .args(args.to_string())
+            .build()
+            .link_chat(connection)
+    }
+
+    #[test]
+    fn links_chats_only_from_private_chats() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            assert_eq!(
+                "Chats can be linked only in the private chat with the bot",
+                link_chat(connection, ChatType::Group, "@my_channel")
+            );
+            assert_eq!(
+                "Chats can be linked only in the private chat with the bot",
+                link_chat(connection, ChatType::Channel, "-47")
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn requires_username_or_id_of_the_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            for args in ["", "@", "my_channel"] {
+                assert_eq!(
+                    "Send me the username of the chat (for example @my_channel) or its id",
+                    link_chat(connection, ChatType::Private, args)
+                );
+            }
+
+            assert!(
+                telegram_linked_chats::find_chats(connection, 46)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,58 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram_rewrite_rules;
+use crate::deliver::rewrite;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/list_rewrites";
+
+#[derive(TypedBuilder)]
+pub struct ListRewrites {
+    message: Message,
+    args: String,
+}
+
+impl ListRewrites {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn list_rewrites(&self, db_connection: &mut PgConnection) -> String {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription = match self.find_subscription(db_connection, chat_id, &self.args) {
+            Err(message) => return message,
+            Ok(subscription) => subscription,
+        };
+
+        match telegram_rewrite_rules::find_for_subscription(db_connection, &subscription) {
+            Ok(rules) if rules.is_empty() => {
+                "The subscription doesn't have rewrite rules".to_string()
+            }
+            Ok(rules) => rules
+                .iter()
+                .enumerate()
+                .map(|(idx, rule)| format!("{}. {}", idx + 1, rewrite::format_rule(rule)))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            Err(_) => "Failed to load rewrite rules".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for ListRewrites {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.list_rewrites(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
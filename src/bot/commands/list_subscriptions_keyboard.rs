@@ -1,6 +1,7 @@
 use super::Close;
 use super::Command;
 use super::Response;
+use super::SelectChat;
 use crate::db::telegram;
 use diesel::PgConnection;
 use frankenstein::methods::SendMessageParams;
@@ -23,7 +24,13 @@ impl ListSubscriptionsKeyboard {
     }
 
     fn feeds_keyboard_params(&self, db_connection: &mut PgConnection) -> SendMessageParams {
-        let feeds = match telegram::find_feeds_by_chat_id(db_connection, self.message.chat.id) {
+        let selected_chat = self.selected_chat(db_connection, &self.message);
+        let chat_id = match &selected_chat {
+            Some(chat) => chat.id,
+            None => self.message.chat.id,
+        };
+
+        let feeds = match telegram::find_feeds_by_chat_id(db_connection, chat_id) {
             Ok(feeds) => feeds,
             Err(_) => {
                 return SendMessageParams::builder()
@@ -33,10 +40,14 @@ impl ListSubscriptionsKeyboard {
             }
         };
 
-        let message = if feeds.is_empty() {
-            "You don't have any subscriptions".to_string()
-        } else {
-            "Select a feed:".to_string()
+        let message = match (feeds.is_empty(), &selected_chat) {
+            (true, None) => "You don't have any subscriptions".to_string(),
+            (true, Some(chat)) => format!(
+                "{} doesn't have any subscriptions",
+                SelectChat::chat_name(chat)
+            ),
+            (false, None) => "Select a feed:".to_string(),
+            (false, Some(chat)) => format!("Select a feed of {}:", SelectChat::chat_name(chat)),
         };
 
         let mut buttons: Vec<Vec<InlineKeyboardButton>> = Vec::new();
@@ -44,11 +55,7 @@ impl ListSubscriptionsKeyboard {
         for feed in feeds {
             let mut row: Vec<InlineKeyboardButton> = Vec::new();
             let subscription = self
-                .find_subscription_by_chat_id_and_feed_id(
-                    db_connection,
-                    self.message.chat.id,
-                    feed.id,
-                )
+                .find_subscription_by_chat_id_and_feed_id(db_connection, chat_id, feed.id)
                 .unwrap();
 
             let feed_button = InlineKeyboardButton::builder()
@@ -0,0 +1,152 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::ShowFeedKeyboard;
+use crate::db;
+use crate::db::feed_items;
+use crate::db::telegram;
+use crate::db::telegram_outbox;
+use crate::models::TelegramSubscription;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/mark_all_read";
+
+#[derive(TypedBuilder)]
+pub struct MarkAllRead {
+    message: Message,
+    args: String,
+    #[builder(default = false)]
+    callback: bool,
+}
+
+impl MarkAllRead {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn mark_all_read(&self, db_connection: &mut PgConnection) -> String {
+        let subscription =
+            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
+                Err(message) => return message,
+                Ok(subscription) => subscription,
+            };
+
+        if telegram_outbox::delete_pending(db_connection, &subscription).is_err() {
+            return "Failed to mark items as read".to_string();
+        }
+
+        match mark_subscription_read(db_connection, &subscription) {
+            Ok(_) => "All items of the subscription were marked as read".to_string(),
+            Err(_) => "Failed to mark items as read".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+pub fn mark_subscription_read(
+    db_connection: &mut PgConnection,
+    subscription: &TelegramSubscription,
+) -> Result<TelegramSubscription, diesel::result::Error> {
+    let last_delivered_at = match feed_items::get_latest_item(db_connection, subscription.feed_id) {
+        Some(item) => item.created_at,
+        None => db::current_time(),
+    };
+
+    telegram::set_subscription_last_delivered_at(db_connection, subscription, last_delivered_at)?;
+    telegram::mark_subscription_delivered(db_connection, subscription)
+}
+
+impl Command for MarkAllRead {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.mark_all_read(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        if self.callback {
+            self.simple_keyboard(
+                response,
+                format!("{} {}", ShowFeedKeyboard::command(), self.args),
+                &self.message,
+            )
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
+
+#[cfg(test)]
+mod mark_all_read_tests {
+    use crate::db;
+    use crate::db::feed_items;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::{NewTelegramChat, NewTelegramSubscription};
+    use crate::sync::FetchedFeedItem;
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    #[test]
+    fn mark_subscription_read_skips_undelivered_items() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let feed = feeds::create(connection, "Link", "rss".to_string()).unwrap();
+            let chat = telegram::create_chat(
+                connection,
+                NewTelegramChat {
+                    id: 42,
+                    kind: "private".to_string(),
+                    username: None,
+                    first_name: None,
+                    last_name: None,
+                    title: None,
+                },
+            )
+            .unwrap();
+            let new_subscription = NewTelegramSubscription::builder()
+                .chat_id(chat.id)
+                .feed_id(feed.id)
+                .build();
+            let subscription = telegram::create_subscription(connection, new_subscription).unwrap();
+
+            let items = vec![FetchedFeedItem {
+                title: "FeedItem".to_string(),
+                description: None,
+                link: "Link".to_string(),
+                author: None,
+                guid: None,
+                publication_date: db::current_time(),
+            }];
+
+            feed_items::create(connection, &feed, items).unwrap();
+
+            assert_eq!(
+                1,
+                telegram::find_undelivered_feed_items(connection, &subscription, 10)
+                    .unwrap()
+                    .len()
+            );
+
+            let subscription = super::mark_subscription_read(connection, &subscription).unwrap();
+
+            assert!(!subscription.has_updates);
+            assert!(
+                telegram::find_undelivered_feed_items(connection, &subscription, 10)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+    }
+}
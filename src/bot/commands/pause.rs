@@ -0,0 +1,201 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::Resume;
+use super::ShowFeedKeyboard;
+use crate::db::telegram;
+use crate::models::TelegramSubscription;
+use chrono::Utc;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/pause";
+
+#[derive(TypedBuilder)]
+pub struct Pause {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl Pause {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn pause(&self, db_connection: &mut PgConnection) -> Response {
+        let subscription =
+            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
+                Ok(subscription) => subscription,
+                Err(error) => return Response::Simple(error),
+            };
+
+        let response = if is_paused(&subscription) {
+            format!(
+                "The subscription is already paused. Send {} to resume it",
+                Resume::command()
+            )
+        } else {
+            match telegram::pause_subscription(db_connection, &subscription, None) {
+                Ok(_) => format!(
+                    "The subscription is paused until you resume it with {}",
+                    Resume::command()
+                ),
+                Err(_) => "Failed to update the subscription".to_string(),
+            }
+        };
+
+        if self.callback {
+            self.simple_keyboard(
+                response,
+                format!(
+                    "{} {}",
+                    ShowFeedKeyboard::command(),
+                    subscription.external_id
+                ),
+                &self.message,
+            )
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+// Snoozed subscriptions are active again after `paused_until`
+pub fn is_paused(subscription: &TelegramSubscription) -> bool {
+    match (subscription.paused_at, subscription.paused_until) {
+        (None, _) => false,
+        (Some(_), None) => true,
+        (Some(_), Some(paused_until)) => paused_until > Utc::now(),
+    }
+}
+
+pub fn paused_status(subscription: &TelegramSubscription) -> Option<String> {
+    if !is_paused(subscription) {
+        return None;
+    }
+
+    match subscription.paused_until {
+        Some(paused_until) => Some(format!(
+            "Snoozed until {}",
+            paused_until.format("%Y-%m-%d %H:%M UTC")
+        )),
+        None => Some("Paused".to_string()),
+    }
+}
+
+impl Command for Pause {
+    fn response(&self) -> Response {
+        match self.fetch_db_connection() {
+            Ok(mut connection) => self.pause(&mut connection),
+
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
+
+#[cfg(test)]
+mod pause_tests {
+    use super::Pause;
+    use crate::bot::commands::Response;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use chrono::{Duration, Utc};
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    fn pause(connection: &mut diesel::PgConnection) -> String {
+        let message = Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(Chat::builder().id(61).type_field(ChatType::Private).build())
+            .build();
+
+        let response = Pause::builder()
+            .message(message)
+            .args("Link91".to_string())
+            .callback(false)
+            .build()
+            .pause(connection);
+
+        match response {
+            Response::Simple(text) => text,
+            Response::Params(_) => panic!("unexpected keyboard"),
+        }
+    }
+
+    #[test]
+    fn pauses_subscription_until_it_is_resumed() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let chat = telegram::create_chat(
+                connection,
+                NewTelegramChat {
+                    id: 61,
+                    kind: "private".to_string(),
+                    username: None,
+                    first_name: None,
+                    last_name: None,
+                    title: None,
+                },
+            )
+            .unwrap();
+            let feed = feeds::create(connection, "Link91", "rss".to_string()).unwrap();
+            let new_subscription = NewTelegramSubscription::builder()
+                .chat_id(chat.id)
+                .feed_id(feed.id)
+                .build();
+            let subscription = telegram::create_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(None, super::paused_status(&subscription));
+            assert_eq!(
+                "The subscription is paused until you resume it with /resume",
+                pause(connection)
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert!(subscription.paused_at.is_some());
+            assert_eq!(None, subscription.paused_until);
+            assert_eq!(
+                Some("Paused".to_string()),
+                super::paused_status(&subscription)
+            );
+            assert_eq!(
+                "The subscription is already paused. Send /resume to resume it",
+                pause(connection)
+            );
+
+            // a snoozed subscription is active again after `paused_until`
+            let subscription = telegram::pause_subscription(
+                connection,
+                &subscription,
+                Some(Utc::now() - Duration::minutes(1)),
+            )
+            .unwrap();
+
+            assert!(!super::is_paused(&subscription));
+            assert_eq!(
+                "The subscription is paused until you resume it with /resume",
+                pause(connection)
+            );
+
+            Ok(())
+        });
+    }
+}
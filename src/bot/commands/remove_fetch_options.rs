@@ -0,0 +1,74 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::ShowFeedKeyboard;
+use super::set_fetch_options;
+use crate::db::feeds;
+use crate::sync::fetch_options::FetchOptions;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/remove_fetch_options";
+
+#[derive(TypedBuilder)]
+pub struct RemoveFetchOptions {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl RemoveFetchOptions {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    pub fn remove_fetch_options(&self, db_connection: &mut PgConnection) -> String {
+        let subscription =
+            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
+                Err(message) => return message,
+                Ok(subscription) => subscription,
+            };
+
+        match feeds::find(db_connection, subscription.feed_id) {
+            Some(feed) if feed.fetch_options.is_none() => {
+                return "You did not set fetch options for this subscription".to_string();
+            }
+            _ => (),
+        }
+
+        let options = FetchOptions::default();
+
+        match set_fetch_options::update_fetch_options(db_connection, &subscription, &options) {
+            Ok(_) => "The fetch options were removed".to_string(),
+            Err(message) => message,
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for RemoveFetchOptions {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.remove_fetch_options(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        if self.callback {
+            self.simple_keyboard(
+                response,
+                format!("{} {}", ShowFeedKeyboard::command(), self.args),
+                &self.message,
+            )
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
@@ -22,11 +22,12 @@ impl RemoveFilter {
     }
 
     pub fn remove_filter(&self, db_connection: &mut PgConnection) -> String {
-        let subscription =
-            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
-                Err(message) => return message,
-                Ok(subscription) => subscription,
-            };
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription = match self.find_subscription(db_connection, chat_id, &self.args) {
+            Err(message) => return message,
+            Ok(subscription) => subscription,
+        };
 
         match telegram::set_filter(db_connection, &subscription, None) {
             Ok(_) => "The filter was removed".to_string(),
@@ -61,3 +62,110 @@ impl Command for RemoveFilter {
         self.send_message_and_remove(send_message_params, &self.message);
     }
 }
+
+#[cfg(test)]
+mod remove_filter_tests {
+    use super::RemoveFilter;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_linked_chats;
+    use crate::db::telegram_linked_chats::NewTelegramLinkedChat;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    #[test]
+    fn removes_filter_of_the_selected_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            for (id, kind) in [(55, "private"), (-56, "channel")] {
+                telegram::create_chat(
+                    connection,
+                    NewTelegramChat {
+                        id,
+                        kind: kind.to_string(),
+                        username: None,
+                        first_name: None,
+                        last_name: None,
+                        title: None,
+                    },
+                )
+                .unwrap();
+            }
+
+            telegram_linked_chats::create(
+                connection,
+                NewTelegramLinkedChat {
+                    chat_id: 55,
+                    linked_chat_id: -56,
+                },
+            )
+            .unwrap();
+
+            let feed = feeds::create(connection, "Link96", "rss".to_string()).unwrap();
+            let mut subscriptions = Vec::new();
+
+            for chat_id in [55, -56] {
+                let new_subscription = NewTelegramSubscription::builder()
+                    .chat_id(chat_id)
+                    .feed_id(feed.id)
+                    .build();
+
+                let subscription =
+                    telegram::create_subscription(connection, new_subscription).unwrap();
+
+                telegram::set_filter(
+                    connection,
+                    &subscription,
+                    Some(vec![format!("word{chat_id}")]),
+                )
+                .unwrap();
+
+                subscriptions.push(subscription);
+            }
+
+            let remove_filter = |connection: &mut diesel::PgConnection| {
+                let chat = Chat::builder().id(55).type_field(ChatType::Private).build();
+
+                RemoveFilter::builder()
+                    .message(
+                        Message::builder()
+                            .message_id(1)
+                            .date(1_u64)
+                            .chat(chat)
+                            .build(),
+                    )
+                    .args("Link96".to_string())
+                    .callback(false)
+                    .build()
+                    .remove_filter(connection)
+            };
+
+            let chat = telegram::find_chat(connection, 55).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-56)).unwrap();
+
+            assert_eq!("The filter was removed", remove_filter(connection));
+
+            let private = telegram::find_subscription_by_external_id(
+                connection,
+                subscriptions[0].external_id,
+            )
+            .unwrap();
+            let channel = telegram::find_subscription_by_external_id(
+                connection,
+                subscriptions[1].external_id,
+            )
+            .unwrap();
+
+            assert_eq!(Some(vec!["word55".to_string()]), private.filter_words);
+            assert_eq!(None, channel.filter_words);
+
+            Ok(())
+        });
+    }
+}
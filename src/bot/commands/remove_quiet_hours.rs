@@ -0,0 +1,46 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/remove_quiet_hours";
+
+#[derive(TypedBuilder)]
+pub struct RemoveQuietHours {
+    message: Message,
+}
+
+impl RemoveQuietHours {
+    pub fn run(&self) {
+        self.execute(&self.message, Self::command());
+    }
+
+    fn remove_quiet_hours(&self, db_connection: &mut PgConnection) -> String {
+        let chat = match telegram::find_chat(db_connection, self.message.chat.id) {
+            Some(chat) => chat,
+            None => return "You don't have any subcriptions".to_string(),
+        };
+
+        match telegram::set_quiet_hours(db_connection, &chat, None, false) {
+            Ok(_) => "Quiet hours were removed".to_string(),
+            Err(_) => "Failed to remove quiet hours".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for RemoveQuietHours {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.remove_quiet_hours(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
@@ -0,0 +1,75 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram_rewrite_rules;
+use crate::deliver::rewrite;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/remove_rewrite";
+
+#[derive(TypedBuilder)]
+pub struct RemoveRewrite {
+    message: Message,
+    args: String,
+}
+
+impl RemoveRewrite {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn remove_rewrite(&self, db_connection: &mut PgConnection) -> String {
+        let args: Vec<&str> = self.args.split_whitespace().collect();
+
+        let (feed_url_or_external_id, number) = match args.as_slice() {
+            [feed_url_or_external_id, number] => match number.parse::<usize>() {
+                Ok(number) if number > 0 => (*feed_url_or_external_id, number),
+                _ => return "The number should be a number from /list_rewrites".to_string(),
+            },
+            _ => return "Wrong number of parameters".to_string(),
+        };
+
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription =
+            match self.find_subscription(db_connection, chat_id, feed_url_or_external_id) {
+                Err(message) => return message,
+                Ok(subscription) => subscription,
+            };
+
+        let rules =
+            match telegram_rewrite_rules::find_for_subscription(db_connection, &subscription) {
+                Ok(rules) => rules,
+                Err(_) => return "Failed to remove the rewrite rule".to_string(),
+            };
+
+        let rule = match rules.get(number - 1) {
+            Some(rule) => rule,
+            None => return format!("The subscription doesn't have rewrite rule {number}"),
+        };
+
+        match telegram_rewrite_rules::delete(db_connection, rule) {
+            Ok(_) => format!(
+                "The rewrite rule was removed:\n\n{}",
+                rewrite::format_rule(rule)
+            ),
+            Err(_) => "Failed to remove the rewrite rule".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for RemoveRewrite {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.remove_rewrite(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
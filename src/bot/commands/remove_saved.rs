@@ -0,0 +1,59 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::Saved;
+use crate::db::telegram_bookmarks;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/remove_saved";
+
+#[derive(TypedBuilder)]
+pub struct RemoveSaved {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl RemoveSaved {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn remove_saved(&self, db_connection: &mut PgConnection) -> String {
+        let id = match self.args.parse::<i64>() {
+            Ok(id) => id,
+            Err(_) => return "The id is not a number".to_string(),
+        };
+
+        match telegram_bookmarks::delete(db_connection, self.message.chat.id, id) {
+            Ok(1) => "The saved item was removed".to_string(),
+            Ok(_) => "The saved item does not exist".to_string(),
+            Err(_) => "Failed to remove the saved item".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for RemoveSaved {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.remove_saved(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        if self.callback {
+            self.simple_keyboard(response, Saved::command().to_string(), &self.message)
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
@@ -22,11 +22,12 @@ impl RemoveTemplate {
     }
 
     fn remove_template(&self, db_connection: &mut PgConnection) -> String {
-        let subscription =
-            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
-                Err(message) => return message,
-                Ok(subscription) => subscription,
-            };
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription = match self.find_subscription(db_connection, chat_id, &self.args) {
+            Err(message) => return message,
+            Ok(subscription) => subscription,
+        };
 
         match telegram::set_template(db_connection, &subscription, None) {
             Ok(_) => "The template was removed".to_string(),
@@ -61,3 +62,110 @@ impl Command for RemoveTemplate {
         self.send_message_and_remove(send_message_params, &self.message);
     }
 }
+
+#[cfg(test)]
+mod remove_template_tests {
+    use super::RemoveTemplate;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_linked_chats;
+    use crate::db::telegram_linked_chats::NewTelegramLinkedChat;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    #[test]
+    fn removes_template_of_the_selected_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            for (id, kind) in [(59, "private"), (-60, "channel")] {
+                telegram::create_chat(
+                    connection,
+                    NewTelegramChat {
+                        id,
+                        kind: kind.to_string(),
+                        username: None,
+                        first_name: None,
+                        last_name: None,
+                        title: None,
+                    },
+                )
+                .unwrap();
+            }
+
+            telegram_linked_chats::create(
+                connection,
+                NewTelegramLinkedChat {
+                    chat_id: 59,
+                    linked_chat_id: -60,
+                },
+            )
+            .unwrap();
+
+            let feed = feeds::create(connection, "Link98", "rss".to_string()).unwrap();
+            let mut subscriptions = Vec::new();
+
+            for chat_id in [59, -60] {
+                let new_subscription = NewTelegramSubscription::builder()
+                    .chat_id(chat_id)
+                    .feed_id(feed.id)
+                    .build();
+
+                let subscription =
+                    telegram::create_subscription(connection, new_subscription).unwrap();
+
+                telegram::set_template(
+                    connection,
+                    &subscription,
+                    Some(format!("{{{{title}}}} {chat_id}")),
+                )
+                .unwrap();
+
+                subscriptions.push(subscription);
+            }
+
+            let remove_template = |connection: &mut diesel::PgConnection| {
+                let chat = Chat::builder().id(59).type_field(ChatType::Private).build();
+
+                RemoveTemplate::builder()
+                    .message(
+                        Message::builder()
+                            .message_id(1)
+                            .date(1_u64)
+                            .chat(chat)
+                            .build(),
+                    )
+                    .args("Link98".to_string())
+                    .callback(false)
+                    .build()
+                    .remove_template(connection)
+            };
+
+            let chat = telegram::find_chat(connection, 59).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-60)).unwrap();
+
+            assert_eq!("The template was removed", remove_template(connection));
+
+            let private = telegram::find_subscription_by_external_id(
+                connection,
+                subscriptions[0].external_id,
+            )
+            .unwrap();
+            let channel = telegram::find_subscription_by_external_id(
+                connection,
+                subscriptions[1].external_id,
+            )
+            .unwrap();
+
+            assert_eq!(Some("{{title}} 59".to_string()), private.template);
+            assert_eq!(None, channel.template);
+
+            Ok(())
+        });
+    }
+}
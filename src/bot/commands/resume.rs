@@ -0,0 +1,299 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::ShowFeedKeyboard;
+use super::mark_all_read;
+use super::pause;
+use crate::db::telegram;
+use crate::db::telegram_outbox;
+use crate::models::TelegramSubscription;
+use crate::sync::SyncFeedJob;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/resume";
+static DELIVER: &str = "deliver";
+static SKIP: &str = "skip";
+
+#[derive(TypedBuilder)]
+pub struct Resume {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl Resume {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn resume(&self, db_connection: &mut PgConnection) -> Response {
+        let mut args = self.args.split_whitespace();
+        let feed_url_or_external_id = args.next().unwrap_or_default();
+        let option = args.next();
+
+        if args.next().is_some() {
+            return Response::Simple("Wrong number of parameters".to_string());
+        }
+
+        let subscription = match self.find_subscription(
+            db_connection,
+            self.message.chat.id,
+            feed_url_or_external_id,
+        ) {
+            Ok(subscription) => subscription,
+            Err(error) => return Response::Simple(error),
+        };
+
+        let back_command = format!(
+            "{} {}",
+            ShowFeedKeyboard::command(),
+            subscription.external_id
+        );
+
+        let response = match option {
+            _ if !pause::is_paused(&subscription) => "The subscription is not paused".to_string(),
+            None => {
+                let command = format!("{} {}", Self::command(), subscription.external_id);
+                let options = vec![
+                    (
+                        "Deliver missed items".to_string(),
+                        format!("{command} {DELIVER}"),
+                    ),
+                    ("Skip missed items".to_string(), format!("{command} {SKIP}")),
+                ];
+
+                return self.options_keyboard(
+                    "Should the items published while the subscription was paused be delivered?"
+                        .to_string(),
+                    options,
+                    back_command,
+                    &self.message,
+                );
+            }
+            Some(option) if option == DELIVER => self.deliver_missed(db_connection, &subscription),
+            Some(option) if option == SKIP => self.skip_missed(db_connection, &subscription),
+            Some(option) => format!("Unknown option {option}. Use {DELIVER} or {SKIP}"),
+        };
+
+        if self.callback {
+            self.simple_keyboard(response, back_command, &self.message)
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    fn deliver_missed(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> String {
+        match telegram::resume_subscription(db_connection, subscription) {
+            Ok(_) => {
+                "The subscription was resumed. Missed items will be delivered soon".to_string()
+            }
+            Err(_) => "Failed to update the subscription".to_string(),
+        }
+    }
+
+    fn skip_missed(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+    ) -> String {
+        // the feed isn't synced while all its subscriptions are paused
+        if let Err(error) = SyncFeedJob::new(subscription.feed_id).sync_feed(db_connection) {
+            log::error!("Failed to sync feed {}: {error:?}", subscription.feed_id);
+        }
+
+        if telegram_outbox::delete_pending(db_connection, subscription).is_err()
+            || mark_all_read::mark_subscription_read(db_connection, subscription).is_err()
+        {
+            return "Failed to update the subscription".to_string();
+        }
+
+        match telegram::resume_subscription(db_connection, subscription) {
+            Ok(_) => "The subscription was resumed. Missed items were skipped".to_string(),
+            Err(_) => "Failed to update the subscription".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for Resume {
+    fn response(&self) -> Response {
+        match self.fetch_db_connection() {
+            Ok(mut connection) => self.resume(&mut connection),
+
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
+
+#[cfg(test)]
+mod resume_tests {
+    use super::Resume;
+    use crate::bot::commands::Response;
+    use crate::db;
+    use crate::db::feed_items;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::sync::FetchedFeedItem;
+    use chrono::Duration;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+    use frankenstein::types::ReplyMarkup;
+
+    fn resume(connection: &mut diesel::PgConnection, args: &str) -> Response {
+        let message = Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(Chat::builder().id(63).type_field(ChatType::Private).build())
+            .build();
+
+        Resume::builder()
+            .message(message)
+            .args(args.to_string())
+            .callback(false)
+            .build()
+            .resume(connection)
+    }
+
+    fn simple_text(response: Response) -> String {
+        match response {
+            Response::Simple(text) => text,
+            Response::Params(_) => panic!("unexpected keyboard"),
+        }
+    }
+
+    // a paused subscription with an undelivered item
+    fn create_paused_subscription(
+        connection: &mut diesel::PgConnection,
+    ) -> NewTelegramSubscription {
+        let chat = telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 63,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap();
+        let feed = feeds::create(connection, "Link93", "rss".to_string()).unwrap();
+        let new_subscription = NewTelegramSubscription::builder()
+            .chat_id(chat.id)
+            .feed_id(feed.id)
+            .build();
+        let subscription = telegram::create_subscription(connection, new_subscription).unwrap();
+
+        assert_eq!(
+            "The subscription is not paused",
+            simple_text(resume(connection, "Link93"))
+        );
+
+        let item = FetchedFeedItem {
+            title: "Item".to_string(),
+            description: None,
+            link: "https://example.com/item".to_string(),
+            author: None,
+            guid: None,
+            publication_date: db::current_time() - Duration::minutes(1),
+        };
+
+        feed_items::create(connection, &feed, vec![item]).unwrap();
+        telegram::pause_subscription(connection, &subscription, None).unwrap();
+
+        new_subscription
+    }
+
+    #[test]
+    fn resumes_subscription_and_delivers_missed_items() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_paused_subscription(connection);
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert!(
+                telegram::find_unread_subscriptions_for_chat(connection, 63)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            let Response::Params(params) = resume(connection, "Link93") else {
+                panic!("keyboard is missing");
+            };
+            let Some(ReplyMarkup::InlineKeyboardMarkup(keyboard)) = params.reply_markup else {
+                panic!("keyboard is missing");
+            };
+            let command = format!("/resume {}", subscription.external_id);
+
+            assert_eq!(
+                Some(format!("{command} deliver")),
+                keyboard.inline_keyboard[0][0].callback_data
+            );
+            assert_eq!(
+                Some(format!("{command} skip")),
+                keyboard.inline_keyboard[1][0].callback_data
+            );
+            assert_eq!(
+                "Unknown option later. Use deliver or skip",
+                simple_text(resume(connection, "Link93 later"))
+            );
+            assert_eq!(
+                "The subscription was resumed. Missed items will be delivered soon",
+                simple_text(resume(connection, "Link93 deliver"))
+            );
+
+            let subscriptions =
+                telegram::find_unread_subscriptions_for_chat(connection, 63).unwrap();
+
+            assert_eq!(1, subscriptions.len());
+            assert_eq!(None, subscriptions[0].paused_at);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn resumes_subscription_and_skips_missed_items() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_paused_subscription(connection);
+
+            assert_eq!(
+                "The subscription was resumed. Missed items were skipped",
+                simple_text(resume(connection, "Link93 skip"))
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(None, subscription.paused_at);
+            assert!(!subscription.has_updates);
+            assert!(subscription.last_delivered_at.is_some());
+            assert!(
+                telegram::find_unread_subscriptions_for_chat(connection, 63)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,170 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/save";
+
+#[derive(TypedBuilder)]
+pub struct Save {
+    message: Message,
+}
+
+impl Save {
+    pub fn run(&self) {
+        self.execute(&self.message, Self::command());
+    }
+
+    fn save(&self, db_connection: &mut PgConnection) -> String {
+        let delivered_message = match &self.message.reply_to_message {
+            Some(delivered_message) => delivered_message,
+            None => return "Reply with /save to a delivered item to save it".to_string(),
+        };
+
+        match self.find_delivered_item(
+            db_connection,
+            self.message.chat.id,
+            delivered_message.message_id,
+        ) {
+            Ok(item) => self.save_bookmark(db_connection, self.message.chat.id, item),
+            Err(error) => error,
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for Save {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.save(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
+
+#[cfg(test)]
+mod save_tests {
+    use super::Save;
+    use crate::bot::rate_limiter::RateLimiter;
+    use crate::bot::telegram_client::Api;
+    use crate::db;
+    use crate::db::feed_items;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_bookmarks;
+    use crate::db::telegram_outbox;
+    use crate::deliver::deliver_chat_updates_job::DeliverChatUpdates;
+    use crate::http_client;
+    use crate::sync::FetchedFeedItem;
+    use chrono::Duration;
+    use diesel::connection::Connection;
+    use frankenstein::methods::GetUpdatesParams;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+    use std::collections::VecDeque;
+
+    const SENT_MESSAGE: &str = "{\"ok\":true,\"result\":{\"message_id\":7,\"date\":1618207352,\"chat\":{\"id\":43,\"type\":\"private\"},\"text\":\"Item\"}}";
+
+    #[test]
+    fn saves_replied_item_after_it_was_cleaned() {
+        let mut server = mockito::Server::new();
+        let _telegram = server
+            .mock("POST", "/bot/sendMessage")
+            .with_status(200)
+            .with_body(SENT_MESSAGE)
+            .create();
+        let api = Api {
+            api_url: format!("{}/bot", server.url()),
+            update_params: GetUpdatesParams::builder().build(),
+            buffer: VecDeque::new(),
+            http_client: http_client::client().clone(),
+            rate_limiter: RateLimiter::disabled(),
+        };
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let chat = telegram::create_chat(
+                connection,
+                NewTelegramChat {
+                    id: 43,
+                    kind: "private".to_string(),
+                    username: None,
+                    first_name: None,
+                    last_name: None,
+                    title: None,
+                },
+            )
+            .unwrap();
+            let feed = feeds::create(connection, "Link89", "rss".to_string()).unwrap();
+            let subscription = telegram::create_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(chat.id)
+                    .feed_id(feed.id)
+                    .build(),
+            )
+            .unwrap();
+
+            let item = FetchedFeedItem {
+                title: "Item".to_string(),
+                description: None,
+                link: "https://example.com/item".to_string(),
+                author: None,
+                guid: None,
+                publication_date: db::current_time(),
+            };
+            feed_items::create(connection, &feed, vec![item]).unwrap();
+            let feed_id = feed.id;
+
+            DeliverChatUpdates::builder()
+                .chat(chat.clone())
+                .feed(feed)
+                .subscription(subscription)
+                .db_connection(connection)
+                .api(&api)
+                .build()
+                .deliver()
+                .unwrap();
+
+            // the cleaner removes processed outbox messages and old feed items
+            telegram_outbox::delete_processed(connection, db::current_time() + Duration::days(1))
+                .unwrap();
+            feed_items::delete_old_feed_items(connection, feed_id, 0).unwrap();
+
+            assert!(feed_items::find(connection, feed_id).unwrap().is_empty());
+
+            let telegram_chat = Chat::builder().id(43).type_field(ChatType::Private).build();
+            let delivered_message = Message::builder()
+                .message_id(7)
+                .date(1_u64)
+                .chat(telegram_chat.clone())
+                .build();
+            let message = Message::builder()
+                .message_id(8)
+                .date(1_u64)
+                .chat(telegram_chat)
+                .reply_to_message(Box::new(delivered_message))
+                .build();
+
+            let result = Save::builder().message(message).build().save(connection);
+
+            assert_eq!("Saved https://example.com/item. See /saved", result);
+
+            let bookmarks = telegram_bookmarks::find_page(connection, chat.id, 1, 5).unwrap();
+
+            assert_eq!(1, bookmarks.len());
+            assert_eq!("Link89", bookmarks[0].feed_link);
+
+            Ok(())
+        });
+    }
+}
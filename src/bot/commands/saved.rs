@@ -0,0 +1,157 @@
+use super::Close;
+use super::Command;
+use super::RemoveSaved;
+use super::Response;
+use crate::db::telegram_bookmarks;
+use crate::deliver::telegram_html;
+use crate::models::TelegramBookmark;
+use diesel::PgConnection;
+use frankenstein::ParseMode;
+use frankenstein::methods::SendMessageParams;
+use frankenstein::types::InlineKeyboardButton;
+use frankenstein::types::InlineKeyboardMarkup;
+use frankenstein::types::LinkPreviewOptions;
+use frankenstein::types::Message;
+use frankenstein::types::ReplyMarkup;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/saved";
+const PER_PAGE: i64 = 5;
+
+#[derive(TypedBuilder)]
+pub struct Saved {
+    message: Message,
+    args: String,
+}
+
+impl Saved {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+
+    fn saved_keyboard_params(
+        &self,
+        db_connection: &mut PgConnection,
+    ) -> Result<SendMessageParams, String> {
+        let page = match self.args.as_str() {
+            "" => 1,
+            page => match page.parse::<i64>() {
+                Ok(page) if page > 0 => page,
+                _ => return Err("The page should be a positive number".to_string()),
+            },
+        };
+
+        let chat_id = self.message.chat.id;
+        let error = "Failed to get your saved items".to_string();
+
+        let count = telegram_bookmarks::count(db_connection, chat_id).map_err(|_| error.clone())?;
+
+        if count == 0 {
+            return Err(
+                "You don't have saved items. Reply with /save to a delivered item to save it"
+                    .to_string(),
+            );
+        }
+
+        let pages = (count + PER_PAGE - 1) / PER_PAGE;
+        let page = page.min(pages);
+
+        let bookmarks = telegram_bookmarks::find_page(db_connection, chat_id, page, PER_PAGE)
+            .map_err(|_| error)?;
+
+        let offset = (page - 1) * PER_PAGE;
+        let mut text = format!("Saved items, page {page} of {pages}:\n\n");
+        let mut buttons: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+
+        for (idx, bookmark) in bookmarks.iter().enumerate() {
+            let number = offset + idx as i64 + 1;
+
+            text.push_str(&format!(
+                "{number}. {}\n\n",
+                Self::render_bookmark(bookmark)
+            ));
+
+            let button = InlineKeyboardButton::builder()
+                .text(format!("✖ Remove {number}"))
+                .callback_data(format!("{} {}", RemoveSaved::command(), bookmark.id))
+                .build();
+
+            buttons.push(vec![button]);
+        }
+
+        let mut navigation_row: Vec<InlineKeyboardButton> = Vec::new();
+
+        if page > 1 {
+            let button = InlineKeyboardButton::builder()
+                .text("◀ Previous")
+                .callback_data(format!("{} {}", Self::command(), page - 1))
+                .build();
+
+            navigation_row.push(button);
+        }
+
+        if page < pages {
+            let button = InlineKeyboardButton::builder()
+                .text("Next ▶")
+                .callback_data(format!("{} {}", Self::command(), page + 1))
+                .build();
+
+            navigation_row.push(button);
+        }
+
+        if !navigation_row.is_empty() {
+            buttons.push(navigation_row);
+        }
+
+        buttons.push(Close::button_row());
+
+        let keyboard = InlineKeyboardMarkup::builder()
+            .inline_keyboard(buttons)
+            .build();
+
+        let preview_params = LinkPreviewOptions::builder().is_disabled(true).build();
+
+        let mut params = SendMessageParams::builder()
+            .chat_id(chat_id)
+            .text(text.trim_end())
+            .parse_mode(ParseMode::Html)
+            .link_preview_options(preview_params)
+            .reply_markup(ReplyMarkup::InlineKeyboardMarkup(keyboard))
+            .build();
+
+        params.message_thread_id = self.message.message_thread_id;
+
+        Ok(params)
+    }
+
+    fn render_bookmark(bookmark: &TelegramBookmark) -> String {
+        let link = telegram_html::escape_text(&bookmark.link).replace('"', "&quot;");
+
+        format!(
+            "<a href=\"{link}\">{}</a>\n{}",
+            telegram_html::escape_text(&bookmark.title),
+            telegram_html::escape_text(&bookmark.feed_link)
+        )
+    }
+}
+
+impl Command for Saved {
+    fn response(&self) -> Response {
+        let params = self
+            .fetch_db_connection()
+            .and_then(|mut connection| self.saved_keyboard_params(&mut connection));
+
+        match params {
+            Ok(params) => Response::Params(Box::new(params)),
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
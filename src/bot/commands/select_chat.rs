@@ -0,0 +1,448 @@
+use super::Close;
+use super::Command;
+use super::LinkChat;
+use super::Response;
+use super::UnlinkChat;
+use crate::db::telegram;
+use crate::db::telegram_linked_chats;
+use crate::models::TelegramChat;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use frankenstein::types::ChatType;
+use frankenstein::types::InlineKeyboardButton;
+use frankenstein::types::InlineKeyboardMarkup;
+use frankenstein::types::Message;
+use frankenstein::types::ReplyMarkup;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/select_chat";
+static THIS_CHAT: &str = "this";
+
+#[derive(TypedBuilder)]
+pub struct SelectChat {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl SelectChat {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+
+    pub fn chat_name(chat: &TelegramChat) -> String {
+        match (&chat.title, &chat.username) {
+            (Some(title), _) => title.clone(),
+            (None, Some(username)) => format!("@{username}"),
+            (None, None) => chat.id.to_string(),
+        }
+    }
+
+    fn chats_keyboard_params(
+        &self,
+        db_connection: &mut PgConnection,
+    ) -> Result<SendMessageParams, String> {
+        let chat_id = self.message.chat.id;
+        let linked_chats = telegram_linked_chats::find_chats(db_connection, chat_id)
+            .map_err(|_| "Failed to get your linked chats".to_string())?;
+
+        if linked_chats.is_empty() {
+            return Err(format!(
+                "You don't have linked chats. Link a channel or a group with {}",
+                LinkChat::command()
+            ));
+        }
+
+        let selected_chat_id = self
+            .selected_chat(db_connection, &self.message)
+            .map(|chat| chat.id);
+
+        let mut buttons: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+
+        let button = InlineKeyboardButton::builder()
+            .text(Self::button_text("This chat", selected_chat_id.is_none()))
+            .callback_data(format!("{} {}", Self::command(), THIS_CHAT))
+            .build();
+
+        buttons.push(vec![button]);
+
+        for chat in linked_chats {
+            let select_button = InlineKeyboardButton::builder()
+                .text(Self::button_text(
+                    &Self::chat_name(&chat),
+                    selected_chat_id == Some(chat.id),
+                ))
+                .callback_data(format!("{} {}", Self::command(), chat.id))
+                .build();
+
+            let unlink_button = InlineKeyboardButton::builder()
+                .text("✖ Unlink")
+                .callback_data(format!("{} {}", UnlinkChat::command(), chat.id))
+                .build();
+
+            buttons.push(vec![select_button, unlink_button]);
+        }
+
+        buttons.push(Close::button_row());
+
+        let keyboard = InlineKeyboardMarkup::builder()
+            .inline_keyboard(buttons)
+            .build();
+
+        let params = SendMessageParams::builder()
+            .chat_id(chat_id)
+            .text("Select the chat managed by /subscribe, /set_filter, /set_template and /list_subscriptions:")
+            .reply_markup(ReplyMarkup::InlineKeyboardMarkup(keyboard))
+            .build();
+
+        Ok(params)
+    }
+
+    fn button_text(name: &str, selected: bool) -> String {
+        if selected {
+            format!("✅ {name}")
+        } else {
+            name.to_string()
+        }
+    }
+
+    fn select_chat(&self, db_connection: &mut PgConnection) -> String {
+        let chat = match telegram::find_chat(db_connection, self.message.chat.id) {
+            Some(chat) => chat,
+            None => return "You don't have linked chats".to_string(),
+        };
+
+        if self.args == THIS_CHAT {
+            return match telegram::set_selected_chat(db_connection, &chat, None) {
+                Ok(_) => "Commands manage subscriptions of this chat".to_string(),
+                Err(_) => "Failed to select the chat".to_string(),
+            };
+        }
+
+        let linked_chat_id = match self.args.parse::<i64>() {
+            Ok(id) => id,
+            Err(_) => return "The id is not a number".to_string(),
+        };
+
+        let linked_chat =
+            match telegram_linked_chats::exists(db_connection, chat.id, linked_chat_id) {
+                Ok(true) => telegram::find_chat(db_connection, linked_chat_id),
+                _ => None,
+            };
+
+        let linked_chat = match linked_chat {
+            Some(linked_chat) => linked_chat,
+            None => return "The chat is not linked".to_string(),
+        };
+
+        // the user could have lost admin rights since the chat was linked
+        if !self.is_chat_admin(linked_chat.id, chat.id as u64) {
+            return "You should be an administrator of the chat".to_string();
+        }
+
+        if telegram_linked_chats::mark_admin_checked(db_connection, chat.id, linked_chat.id)
+            .is_err()
+        {
+            return "Failed to select the chat".to_string();
+        }
+
+        match telegram::set_selected_chat(db_connection, &chat, Some(linked_chat.id)) {
+            Ok(_) => format!(
+                "Commands manage subscriptions of {}",
+                Self::chat_name(&linked_chat)
+            ),
+            Err(_) => "Failed to select the chat".to_string(),
+        }
+    }
+}
+
+impl Command for SelectChat {
+    fn response(&self) -> Response {
+        if !matches!(self.message.chat.type_field, ChatType::Private) {
+            return Response::Simple(
+                "Chats can be selected only in the private chat with the bot".to_string(),
+            );
+        }
+
+        let mut connection = match self.fetch_db_connection() {
+            Ok(connection) => connection,
+            Err(error_message) => return Response::Simple(error_message),
+        };
+
+        if self.args.is_empty() {
+            return match self.chats_keyboard_params(&mut connection) {
+                Ok(params) => Response::Params(Box::new(params)),
+                Err(error_message) => Response::Simple(error_message),
+            };
+        }
+
+        let response = self.select_chat(&mut connection);
+
+        if self.callback {
+            self.simple_keyboard(response, Self::command().to_string(), &self.message)
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
+
+#[cfg(test)]
+mod select_chat_tests {
+    use super::SelectChat;
+    use crate::bot::commands::Command;
+    use crate::bot::commands::SetFilter;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_linked_chats;
+    use crate::db::telegram_linked_chats::NewTelegramLinkedChat;
+    use crate::schema::telegram_linked_chats as linked_chats;
+    use chrono::Duration;
+    use diesel::PgConnection;
+    use diesel::connection::Connection;
+    use diesel::prelude::*;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::InlineKeyboardButton;
+    use frankenstein::types::Message;
+    use frankenstein::types::ReplyMarkup;
+
+    fn message() -> Message {
+        let chat = Chat::builder().id(44).type_field(ChatType::Private).build();
+
+        Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(chat)
+            .build()
+    }
+
+    fn link_chats(connection: &mut PgConnection) {
+        for (id, kind, title) in [(44, "private", None), (-45, "channel", Some("News"))] {
+            telegram::create_chat(
+                connection,
+                NewTelegramChat {
+                    id,
+                    kind: kind.to_string(),
+                    username: None,
+                    first_name: None,
+                    last_name: None,
+                    title: title.map(|title| title.to_string()),
+                },
+            )
+            .unwrap();
+        }
+
+        telegram_linked_chats::create(
+            connection,
+            NewTelegramLinkedChat {
+                chat_id: 44,
+                linked_chat_id: -45,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn commands_manage_subscriptions_of_the_selected_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            link_chats(connection);
+
+            let feed = feeds::create(connection, "Link90", "rss".to_string()).unwrap();
+            let new_subscription = NewTelegramSubscription::builder()
+                .chat_id(-45)
+                .feed_id(feed.id)
+                .build();
+            telegram::create_subscription(connection, new_subscription).unwrap();
+
+            let set_filter = |connection: &mut diesel::PgConnection| {
+                SetFilter::builder()
+                    .message(message())
+                    .args("Link90 rust".to_string())
+                    .build()
+                    .set_filter(connection)
+            };
+
+            assert_eq!("Subscription does not exist", set_filter(connection));
+
+            let chat = telegram::find_chat(connection, 44).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-45)).unwrap();
+
+            assert_eq!("The filter was updated:\n\nrust", set_filter(connection));
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(Some(vec!["rust".to_string()]), subscription.filter_words);
+
+            let select_chat = |connection: &mut diesel::PgConnection, args: &str| {
+                SelectChat::builder()
+                    .message(message())
+                    .args(args.to_string())
+                    .callback(true)
+                    .build()
+                    .select_chat(connection)
+            };
+
+            assert_eq!("The chat is not linked", select_chat(connection, "-46"));
+            assert_eq!(
+                "Commands manage subscriptions of this chat",
+                select_chat(connection, "this")
+            );
+            assert_eq!(
+                None,
+                telegram::find_chat(connection, 44)
+                    .unwrap()
+                    .selected_chat_id
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn shows_linked_chats_with_the_selected_one() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let select_chat = SelectChat::builder()
+                .message(message())
+                .args("".to_string())
+                .callback(false)
+                .build();
+
+            assert_eq!(
+                "You don't have linked chats. Link a channel or a group with /link_chat",
+                select_chat.chats_keyboard_params(connection).unwrap_err()
+            );
+
+            link_chats(connection);
+
+            let chat = telegram::find_chat(connection, 44).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-45)).unwrap();
+
+            let params = select_chat.chats_keyboard_params(connection).unwrap();
+
+            let Some(ReplyMarkup::InlineKeyboardMarkup(keyboard)) = params.reply_markup else {
+                panic!("expected an inline keyboard");
+            };
+
+            let buttons: Vec<Vec<(String, Option<String>)>> = keyboard
+                .inline_keyboard
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|button: &InlineKeyboardButton| {
+                            (button.text.clone(), button.callback_data.clone())
+                        })
+                        .collect()
+                })
+                .collect();
+
+            assert_eq!(3, buttons.len());
+            assert_eq!(
+                vec![(
+                    "This chat".to_string(),
+                    Some("/select_chat this".to_string())
+                )],
+                buttons[0]
+            );
+            assert_eq!(
+                vec![
+                    ("✅ News".to_string(), Some("/select_chat -45".to_string())),
+                    ("✖ Unlink".to_string(), Some("/unlink_chat -45".to_string()))
+                ],
+                buttons[1]
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn clears_the_selection_if_admin_rights_can_not_be_confirmed() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            link_chats(connection);
+
+            let chat = telegram::find_chat(connection, 44).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-45)).unwrap();
+
+            let select_chat = SelectChat::builder()
+                .message(message())
+                .args("".to_string())
+                .callback(false)
+                .build();
+
+            assert_eq!(
+                Some(-45),
+                select_chat
+                    .selected_chat(connection, &message())
+                    .map(|chat| chat.id)
+            );
+
+            diesel::update(linked_chats::table)
+                .filter(linked_chats::chat_id.eq(44))
+                .set(linked_chats::admin_checked_at.eq(db::current_time() - Duration::hours(1)))
+                .execute(connection)
+                .unwrap();
+
+            // admin rights can not be confirmed without a valid bot token
+            assert!(select_chat.selected_chat(connection, &message()).is_none());
+            assert_eq!(
+                None,
+                telegram::find_chat(connection, 44)
+                    .unwrap()
+                    .selected_chat_id
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rejects_not_linked_and_invalid_chats() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let select_chat = |connection: &mut PgConnection, args: &str| {
+                SelectChat::builder()
+                    .message(message())
+                    .args(args.to_string())
+                    .callback(false)
+                    .build()
+                    .select_chat(connection)
+            };
+
+            assert_eq!(
+                "You don't have linked chats",
+                select_chat(connection, "-45")
+            );
+
+            link_chats(connection);
+
+            assert_eq!("The id is not a number", select_chat(connection, "news"));
+            assert_eq!("The chat is not linked", select_chat(connection, "-46"));
+            assert_eq!(
+                None,
+                telegram::find_chat(connection, 44)
+                    .unwrap()
+                    .selected_chat_id
+            );
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,109 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use crate::deliver::date_format;
+use crate::deliver::timezone;
+use crate::models::TelegramChat;
+use chrono::Utc;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_date_format";
+
+#[derive(TypedBuilder)]
+pub struct SetDateFormat {
+    message: Message,
+    args: String,
+}
+
+impl SetDateFormat {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_date_format(&self, db_connection: &mut PgConnection) -> String {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let chat = match telegram::find_chat(db_connection, chat_id) {
+            Some(chat) => chat,
+            None => return "You don't have any subscriptions".to_string(),
+        };
+
+        if self.args.is_empty() {
+            return Self::describe(&chat);
+        }
+
+        let date_format = match Self::parse_format(&self.args) {
+            Ok(date_format) => date_format,
+            Err(error) => return error,
+        };
+
+        match telegram::set_date_format(db_connection, &chat, date_format) {
+            Ok(chat) => Self::describe(&chat),
+            Err(_) => "Failed to update the chat".to_string(),
+        }
+    }
+
+    fn parse_format(value: &str) -> Result<Option<String>, String> {
+        if value == "default" {
+            return Ok(None);
+        }
+
+        date_format::validate(value)?;
+
+        Ok(Some(value.to_string()))
+    }
+
+    fn describe(chat: &TelegramChat) -> String {
+        let now = Utc::now();
+        let date = now.with_timezone(&timezone::offset_at(
+            chat.timezone.as_deref(),
+            chat.utc_offset_minutes,
+            now,
+        ));
+
+        match &chat.date_format {
+            Some(pattern) => {
+                match date_format::format(&date, pattern, chat.date_language.as_deref()) {
+                    Ok(example) => {
+                        format!("The date format is {pattern}. Dates look like: {example}")
+                    }
+                    Err(error) => error,
+                }
+            }
+            None => format!("The default date format is used. Dates look like: {date}"),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetDateFormat {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_date_format(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SetDateFormat;
+
+    #[test]
+    fn parse_format_accepts_default_and_strftime_patterns() {
+        assert_eq!(Ok(None), SetDateFormat::parse_format("default"));
+        assert_eq!(
+            Ok(Some("%d %B %Y, %H:%M".to_string())),
+            SetDateFormat::parse_format("%d %B %Y, %H:%M")
+        );
+        assert!(SetDateFormat::parse_format("%Q").is_err());
+        assert!(SetDateFormat::parse_format(&"%d".repeat(51)).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use crate::deliver::date_format;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_date_language";
+
+#[derive(TypedBuilder)]
+pub struct SetDateLanguage {
+    message: Message,
+    args: String,
+}
+
+impl SetDateLanguage {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_date_language(&self, db_connection: &mut PgConnection) -> String {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let chat = match telegram::find_chat(db_connection, chat_id) {
+            Some(chat) => chat,
+            None => return "You don't have any subscriptions".to_string(),
+        };
+
+        if self.args.is_empty() {
+            return Self::describe(chat.date_language);
+        }
+
+        let date_language = match Self::parse_language(&self.args) {
+            Ok(date_language) => date_language,
+            Err(error) => return error,
+        };
+
+        match telegram::set_date_language(db_connection, &chat, date_language) {
+            Ok(chat) => Self::describe(chat.date_language),
+            Err(_) => "Failed to update the chat".to_string(),
+        }
+    }
+
+    fn parse_language(value: &str) -> Result<Option<String>, String> {
+        if value == "default" {
+            return Ok(None);
+        }
+
+        let language = value.to_lowercase();
+
+        if date_format::is_supported_language(&language) {
+            Ok(Some(language))
+        } else {
+            Err(format!(
+                "Unknown language {value}. Supported languages: {}",
+                date_format::languages().join(", ")
+            ))
+        }
+    }
+
+    fn describe(date_language: Option<String>) -> String {
+        match date_language {
+            Some(language) => format!("Month and weekday names in dates are in {language}"),
+            None => "Month and weekday names in dates are in en".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetDateLanguage {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_date_language(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
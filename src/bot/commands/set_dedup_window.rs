@@ -0,0 +1,103 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use crate::db::telegram_dedup_fingerprints;
+use crate::deliver::dedup::MAX_DEDUP_DAYS;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_dedup_window";
+
+#[derive(TypedBuilder)]
+pub struct SetDedupWindow {
+    message: Message,
+    args: String,
+}
+
+impl SetDedupWindow {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_dedup_window(&self, db_connection: &mut PgConnection) -> String {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let chat = match telegram::find_chat(db_connection, chat_id) {
+            Some(chat) => chat,
+            None => return "You don't have any subscriptions".to_string(),
+        };
+
+        if self.args.is_empty() {
+            return Self::describe(chat.dedup_days);
+        }
+
+        let dedup_days = match Self::parse_days(&self.args) {
+            Ok(dedup_days) => dedup_days,
+            Err(error) => return error,
+        };
+
+        if dedup_days.is_none() {
+            if let Err(error) = telegram_dedup_fingerprints::delete_for_chat(db_connection, chat.id)
+            {
+                log::error!("Failed to remove dedup fingerprints of chat {chat_id}: {error:?}");
+            }
+        }
+
+        match telegram::set_dedup_days(db_connection, &chat, dedup_days) {
+            Ok(chat) => Self::describe(chat.dedup_days),
+            Err(_) => "Failed to update the chat".to_string(),
+        }
+    }
+
+    fn parse_days(value: &str) -> Result<Option<i32>, String> {
+        if value == "off" {
+            return Ok(None);
+        }
+
+        match value.parse::<i32>() {
+            Ok(days) if (1..=MAX_DEDUP_DAYS).contains(&days) => Ok(Some(days)),
+            _ => Err(format!(
+                "The window should be a number of days from 1 to {MAX_DEDUP_DAYS} or off"
+            )),
+        }
+    }
+
+    fn describe(dedup_days: Option<i32>) -> String {
+        match dedup_days {
+            Some(days) => format!(
+                "Items with the same link, guid or title as items delivered in the last {days} days are skipped"
+            ),
+            None => "Duplicate items are not skipped".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetDedupWindow {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_dedup_window(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SetDedupWindow;
+
+    #[test]
+    fn parse_days_accepts_off_and_days_in_range() {
+        assert_eq!(Ok(None), SetDedupWindow::parse_days("off"));
+        assert_eq!(Ok(Some(7)), SetDedupWindow::parse_days("7"));
+        assert!(SetDedupWindow::parse_days("0").is_err());
+        assert!(SetDedupWindow::parse_days("31").is_err());
+        assert!(SetDedupWindow::parse_days("week").is_err());
+    }
+}
@@ -0,0 +1,123 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::feeds;
+use crate::db::telegram;
+use crate::models::TelegramSubscription;
+use crate::sync::SyncFeedJob;
+use crate::sync::fetch_options::FetchOptions;
+use crate::sync::reader;
+use diesel::Connection;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_fetch_options";
+
+#[derive(TypedBuilder)]
+pub struct SetFetchOptions {
+    message: Message,
+    args: String,
+}
+
+impl SetFetchOptions {
+    pub fn run(&self) {
+        let feed_url = self.args.split_whitespace().next().unwrap_or_default();
+
+        // options contain secrets so they're not logged
+        self.execute(&self.message, &format!("{} {feed_url}", Self::command()));
+    }
+
+    fn set_fetch_options(&self, db_connection: &mut PgConnection) -> String {
+        let vec: Vec<&str> = self.args.splitn(2, ' ').collect();
+
+        if vec.len() != 2 {
+            return "Wrong number of parameters".to_string();
+        }
+
+        // the message with secrets shouldn't stay in the chat
+        self.remove_message(&self.message);
+
+        let options = match FetchOptions::parse(vec[1]) {
+            Ok(options) => options,
+            Err(error) => return error.to_string(),
+        };
+
+        if options.is_empty() {
+            return "Fetch options can not be empty".to_string();
+        }
+
+        let subscription = match self.find_subscription(db_connection, self.message.chat.id, vec[0])
+        {
+            Err(message) => return message,
+            Ok(subscription) => subscription,
+        };
+
+        match update_fetch_options(db_connection, &subscription, &options) {
+            Ok(_) => "The fetch options were updated".to_string(),
+            Err(message) => message,
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+// Subscriptions with different fetch options point to different feeds
+// so private and public variants of the same url are synced separately
+pub fn update_fetch_options(
+    db_connection: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    options: &FetchOptions,
+) -> Result<(), String> {
+    let feed = match feeds::find(db_connection, subscription.feed_id) {
+        Some(feed) => feed,
+        None => return Err("Feed does not exist".to_string()),
+    };
+
+    let data = reader::read_url_with_options(&feed.link, options).map_err(|error| error.msg)?;
+
+    let feed_type =
+        reader::detect_feed_type(&feed.link, &data).map_err(|_| "Url is not a feed".to_string())?;
+
+    let encrypted_options = options.encrypt().map_err(|error| error.to_string())?;
+
+    db_connection
+        .transaction::<(), diesel::result::Error, _>(|db_connection| {
+            let new_feed = feeds::create_with_fetch_options(
+                db_connection,
+                &feed.link,
+                feed_type,
+                encrypted_options,
+            )?;
+
+            if new_feed.id == feed.id {
+                return Ok(());
+            }
+
+            telegram::set_subscription_feed(
+                db_connection,
+                subscription,
+                new_feed.id,
+                subscription.last_delivered_at,
+            )?;
+
+            if let Err(error) = SyncFeedJob::new(new_feed.id).sync_feed(db_connection) {
+                log::error!("Failed to sync feed {}: {error:?}", new_feed.id);
+            }
+
+            Ok(())
+        })
+        .map_err(|_| "Failed to update the fetch options".to_string())
+}
+
+impl Command for SetFetchOptions {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_fetch_options(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
@@ -34,8 +34,9 @@ impl SetFilter {
             Ok(words) => words,
         };
 
-        let subscription = match self.find_subscription(db_connection, self.message.chat.id, vec[0])
-        {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription = match self.find_subscription(db_connection, chat_id, vec[0]) {
             Err(message) => return message,
             Ok(subscription) => subscription,
         };
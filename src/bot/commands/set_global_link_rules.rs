@@ -0,0 +1,77 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use crate::deliver::links;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_global_link_rules";
+
+#[derive(TypedBuilder)]
+pub struct SetGlobalLinkRules {
+    message: Message,
+    args: String,
+}
+
+impl SetGlobalLinkRules {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_global_link_rules(&self, db_connection: &mut PgConnection) -> String {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let chat = match telegram::find_chat(db_connection, chat_id) {
+            Some(chat) => chat,
+            None => return "You don't have any subcriptions".to_string(),
+        };
+
+        if self.args.is_empty() {
+            return Self::describe(chat.link_rules);
+        }
+
+        // an empty list turns off the default rules
+        let link_rules = if self.args == "none" {
+            Some(vec![])
+        } else if self.args == "default" {
+            None
+        } else {
+            match self.parse_link_rules(&self.args) {
+                Ok(link_rules) => Some(link_rules),
+                Err(message) => return message,
+            }
+        };
+
+        match telegram::set_global_link_rules(db_connection, &chat, link_rules) {
+            Ok(chat) => Self::describe(chat.link_rules),
+            Err(_) => "Failed to update link rules".to_string(),
+        }
+    }
+
+    fn describe(link_rules: Option<Vec<String>>) -> String {
+        match link_rules {
+            Some(link_rules) if link_rules.is_empty() => "Links are not rewritten".to_string(),
+            Some(link_rules) => format!("Global link rules: {}", link_rules.join(" ")),
+            None => format!(
+                "Global link rules: {} (default)",
+                links::default_rules().join(" ")
+            ),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetGlobalLinkRules {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_global_link_rules(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
@@ -3,7 +3,9 @@ use super::Message;
 use super::Response;
 use crate::bot::SimpleMessageParams;
 use crate::db::telegram;
+use crate::deliver::render_message;
 use crate::deliver::render_template_example;
+use crate::deliver::telegram_html;
 use diesel::PgConnection;
 use typed_builder::TypedBuilder;
 
@@ -30,15 +32,23 @@ impl SetGlobalTemplate {
             None => return "You don't have any subcriptions".to_string(),
         };
 
-        let example = match render_template_example(&self.args) {
+        let parse_mode = render_message::parse_mode(&chat.parse_mode);
+
+        let example = match render_template_example(&self.args, parse_mode) {
             Ok(example) => format!("Your messages will look like:\n\n{example}"),
-            Err(_) => return "The template is invalid".to_string(),
+            Err(error) => {
+                return format!(
+                    "The template is invalid: {}",
+                    telegram_html::escape_text(&error)
+                );
+            }
         };
 
         let message_params = SimpleMessageParams::builder()
             .message(example)
             .chat_id(self.message.chat.id)
             .preview_enabled(chat.preview_enabled)
+            .parse_mode(parse_mode)
             .build();
 
         if self.api().reply_with_text_message(&message_params).is_err() {
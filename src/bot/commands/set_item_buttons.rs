@@ -0,0 +1,202 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::item_action::ItemButton;
+use crate::db::telegram;
+use diesel::PgConnection;
+use std::str::FromStr;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_item_buttons";
+
+#[derive(TypedBuilder)]
+pub struct SetItemButtons {
+    message: Message,
+    args: String,
+}
+
+impl SetItemButtons {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_item_buttons(&self, db_connection: &mut PgConnection) -> String {
+        let vec: Vec<&str> = self.args.splitn(2, ' ').collect();
+
+        if vec.len() != 2 {
+            return "Wrong number of parameters".to_string();
+        }
+
+        let item_buttons = match self.parse_buttons(vec[1]) {
+            Ok(item_buttons) => item_buttons,
+            Err(error) => return error,
+        };
+
+        let subscription = match self.find_subscription(db_connection, self.message.chat.id, vec[0])
+        {
+            Err(message) => return message,
+            Ok(subscription) => subscription,
+        };
+
+        match telegram::set_item_buttons(db_connection, &subscription, item_buttons) {
+            Ok(subscription) => match subscription.item_buttons {
+                Some(buttons) => {
+                    format!("Delivered items will have buttons: {}", buttons.join(", "))
+                }
+                None => "Delivered items won't have buttons".to_string(),
+            },
+            Err(_) => "Failed to update the subscription".to_string(),
+        }
+    }
+
+    fn parse_buttons(&self, params: &str) -> Result<Option<Vec<String>>, String> {
+        if params.trim() == "none" {
+            return Ok(None);
+        }
+
+        let mut buttons: Vec<String> = vec![];
+
+        for name in params.split(',').map(|name| name.trim().to_lowercase()) {
+            if ItemButton::from_str(&name).is_err() {
+                let names: Vec<&str> = ItemButton::ALL.iter().map(|button| button.name()).collect();
+
+                return Err(format!(
+                    "Unknown button {name}. Available buttons: {}",
+                    names.join(", ")
+                ));
+            }
+
+            if !buttons.contains(&name) {
+                buttons.push(name);
+            }
+        }
+
+        Ok(Some(buttons))
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetItemButtons {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_item_buttons(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
+
+#[cfg(test)]
+mod set_item_buttons_tests {
+    use super::SetItemButtons;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    fn set_item_buttons(connection: &mut diesel::PgConnection, args: &str) -> String {
+        let message = Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(Chat::builder().id(65).type_field(ChatType::Private).build())
+            .build();
+
+        SetItemButtons::builder()
+            .message(message)
+            .args(args.to_string())
+            .build()
+            .set_item_buttons(connection)
+    }
+
+    fn create_subscription(connection: &mut diesel::PgConnection) -> NewTelegramSubscription {
+        let chat = telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 65,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap();
+        let feed = feeds::create(connection, "Link99", "rss".to_string()).unwrap();
+        let new_subscription = NewTelegramSubscription::builder()
+            .chat_id(chat.id)
+            .feed_id(feed.id)
+            .build();
+
+        telegram::create_subscription(connection, new_subscription).unwrap();
+
+        new_subscription
+    }
+
+    #[test]
+    fn sets_and_removes_item_buttons() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_subscription(connection);
+
+            assert_eq!(
+                "Delivered items will have buttons: mute, author",
+                set_item_buttons(connection, "Link99 Mute, author,mute")
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(
+                Some(vec!["mute".to_string(), "author".to_string()]),
+                subscription.item_buttons
+            );
+            assert_eq!(
+                "Delivered items won't have buttons",
+                set_item_buttons(connection, "Link99 none")
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(None, subscription.item_buttons);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_subscription(connection);
+
+            assert_eq!(
+                "Wrong number of parameters",
+                set_item_buttons(connection, "Link99")
+            );
+            assert!(
+                set_item_buttons(connection, "Link99 mute,share")
+                    .starts_with("Unknown button share. Available buttons: mute, unsubscribe")
+            );
+            assert_eq!(
+                "Feed does not exist",
+                set_item_buttons(connection, "Link100 mute")
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(None, subscription.item_buttons);
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,254 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use crate::deliver::deliver_chat_updates_job::MESSAGES_LIMIT;
+use crate::models::TelegramSubscription;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_limits";
+const MAX_MESSAGES_LIMIT: i32 = 50;
+const MAX_HOURLY_LIMIT: i32 = 1000;
+
+#[derive(TypedBuilder)]
+pub struct SetLimits {
+    message: Message,
+    args: String,
+}
+
+impl SetLimits {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_limits(&self, db_connection: &mut PgConnection) -> String {
+        let mut args = self.args.split_whitespace();
+        let feed_url_or_external_id = args.next().unwrap_or_default();
+        let options: Vec<&str> = args.collect();
+
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription =
+            match self.find_subscription(db_connection, chat_id, feed_url_or_external_id) {
+                Err(message) => return message,
+                Ok(subscription) => subscription,
+            };
+
+        if options.is_empty() {
+            return Self::describe(&subscription);
+        }
+
+        let (messages_limit, hourly_limit) = match Self::parse_options(&subscription, &options) {
+            Ok(limits) => limits,
+            Err(error) => return error,
+        };
+
+        match telegram::set_limits(db_connection, &subscription, messages_limit, hourly_limit) {
+            Ok(subscription) => Self::describe(&subscription),
+            Err(_) => "Failed to update the subscription".to_string(),
+        }
+    }
+
+    // Options that aren't passed keep their current values
+    fn parse_options(
+        subscription: &TelegramSubscription,
+        options: &[&str],
+    ) -> Result<(Option<i32>, Option<i32>), String> {
+        let mut messages_limit = subscription.messages_limit;
+        let mut hourly_limit = subscription.hourly_limit;
+
+        for option in options {
+            match option.split_once('=') {
+                Some(("per_run", "default")) => messages_limit = None,
+                Some(("per_run", value)) => {
+                    messages_limit = Some(Self::parse_limit(value, MAX_MESSAGES_LIMIT)?)
+                }
+                Some(("per_hour", "none")) => hourly_limit = None,
+                Some(("per_hour", value)) => {
+                    hourly_limit = Some(Self::parse_limit(value, MAX_HOURLY_LIMIT)?)
+                }
+                _ => {
+                    return Err(format!(
+                        "Unknown option {option}. Use per_run=N or per_hour=N"
+                    ));
+                }
+            }
+        }
+
+        Ok((messages_limit, hourly_limit))
+    }
+
+    fn parse_limit(value: &str, max: i32) -> Result<i32, String> {
+        match value.parse::<i32>() {
+            Ok(limit) if (1..=max).contains(&limit) => Ok(limit),
+            _ => Err(format!("The limit should be a number from 1 to {max}")),
+        }
+    }
+
+    fn describe(subscription: &TelegramSubscription) -> String {
+        let messages_limit = subscription
+            .messages_limit
+            .map(|limit| limit as usize)
+            .unwrap_or(MESSAGES_LIMIT);
+
+        let hourly_limit = match subscription.hourly_limit {
+            Some(limit) => format!("at most {limit} items per hour"),
+            None => "no hourly limit".to_string(),
+        };
+
+        format!("Up to {messages_limit} items are delivered at once, {hourly_limit}")
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetLimits {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_limits(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
+
+#[cfg(test)]
+mod set_limits_tests {
+    use super::SetLimits;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    fn set_limits(connection: &mut diesel::PgConnection, args: &str) -> String {
+        let message = Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(Chat::builder().id(66).type_field(ChatType::Private).build())
+            .build();
+
+        SetLimits::builder()
+            .message(message)
+            .args(args.to_string())
+            .build()
+            .set_limits(connection)
+    }
+
+    fn create_subscription(connection: &mut diesel::PgConnection) -> NewTelegramSubscription {
+        let chat = telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 66,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap();
+        let feed = feeds::create(connection, "Link101", "rss".to_string()).unwrap();
+        let new_subscription = NewTelegramSubscription::builder()
+            .chat_id(chat.id)
+            .feed_id(feed.id)
+            .build();
+
+        telegram::create_subscription(connection, new_subscription).unwrap();
+
+        new_subscription
+    }
+
+    #[test]
+    fn sets_and_resets_limits() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_subscription(connection);
+
+            assert_eq!(
+                "Up to 10 items are delivered at once, no hourly limit",
+                set_limits(connection, "Link101")
+            );
+            assert_eq!(
+                "Up to 50 items are delivered at once, at most 1 items per hour",
+                set_limits(connection, "Link101 per_run=50 per_hour=1")
+            );
+            // options that aren't passed keep their values
+            assert_eq!(
+                "Up to 3 items are delivered at once, at most 1 items per hour",
+                set_limits(connection, "Link101 per_run=3")
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(Some(3), subscription.messages_limit);
+            assert_eq!(Some(1), subscription.hourly_limit);
+            assert_eq!(
+                "Up to 10 items are delivered at once, no hourly limit",
+                set_limits(connection, "Link101 per_run=default per_hour=none")
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(None, subscription.messages_limit);
+            assert_eq!(None, subscription.hourly_limit);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn rejects_invalid_limits() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_subscription(connection);
+
+            for (args, error) in [
+                (
+                    "Link101 per_run=0",
+                    "The limit should be a number from 1 to 50",
+                ),
+                (
+                    "Link101 per_run=51",
+                    "The limit should be a number from 1 to 50",
+                ),
+                (
+                    "Link101 per_hour=1001",
+                    "The limit should be a number from 1 to 1000",
+                ),
+                (
+                    "Link101 per_hour=ten",
+                    "The limit should be a number from 1 to 1000",
+                ),
+                (
+                    "Link101 per_day=5",
+                    "Unknown option per_day=5. Use per_run=N or per_hour=N",
+                ),
+                (
+                    "Link101 per_run=5 per_hour",
+                    "Unknown option per_hour. Use per_run=N or per_hour=N",
+                ),
+                ("Link102 per_run=5", "Feed does not exist"),
+            ] {
+                assert_eq!(error, set_limits(connection, args), "{args}");
+            }
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(None, subscription.messages_limit);
+            assert_eq!(None, subscription.hourly_limit);
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,83 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_link_rules";
+
+#[derive(TypedBuilder)]
+pub struct SetLinkRules {
+    message: Message,
+    args: String,
+}
+
+impl SetLinkRules {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_link_rules(&self, db_connection: &mut PgConnection) -> String {
+        let (feed_url_or_external_id, rules) = match self.args.split_once(' ') {
+            Some((feed_url_or_external_id, rules)) => (feed_url_or_external_id, rules.trim()),
+            None => (self.args.as_str(), ""),
+        };
+
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription =
+            match self.find_subscription(db_connection, chat_id, feed_url_or_external_id) {
+                Err(message) => return message,
+                Ok(subscription) => subscription,
+            };
+
+        if rules.is_empty() {
+            return Self::describe(subscription.link_rules);
+        }
+
+        // an empty list turns off rules of the chat for the subscription
+        let link_rules = if rules == "none" {
+            Some(vec![])
+        } else if rules == "default" {
+            None
+        } else {
+            match self.parse_link_rules(rules) {
+                Ok(link_rules) => Some(link_rules),
+                Err(message) => return message,
+            }
+        };
+
+        match telegram::set_link_rules(db_connection, &subscription, link_rules) {
+            Ok(subscription) => Self::describe(subscription.link_rules),
+            Err(_) => "Failed to update link rules".to_string(),
+        }
+    }
+
+    fn describe(link_rules: Option<Vec<String>>) -> String {
+        match link_rules {
+            Some(link_rules) if link_rules.is_empty() => {
+                "Links of the subscription are not rewritten".to_string()
+            }
+            Some(link_rules) => format!("Link rules: {}", link_rules.join(" ")),
+            None => {
+                "The subscription doesn't have link rules. Global link rules are used".to_string()
+            }
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetLinkRules {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_link_rules(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
@@ -0,0 +1,64 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use diesel::PgConnection;
+use frankenstein::ParseMode;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_parse_mode";
+
+#[derive(TypedBuilder)]
+pub struct SetParseMode {
+    message: Message,
+    args: String,
+}
+
+impl SetParseMode {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_parse_mode(&self, db_connection: &mut PgConnection) -> String {
+        let parse_mode = match self.validate_parse_mode() {
+            Ok(parse_mode) => parse_mode,
+            Err(error) => return error.to_string(),
+        };
+
+        let chat = match telegram::find_chat(db_connection, self.message.chat.id) {
+            Some(chat) => chat,
+            None => return "You don't have any subcriptions".to_string(),
+        };
+
+        match telegram::set_parse_mode(db_connection, &chat, parse_mode.to_str()) {
+            Ok(_) => format!(
+                "The parse mode was updated to {}. Make sure your templates use its formatting",
+                parse_mode.to_str()
+            ),
+            Err(_) => "Failed to update the parse mode".to_string(),
+        }
+    }
+
+    fn validate_parse_mode(&self) -> Result<ParseMode, &'static str> {
+        match self.args.to_lowercase().as_str() {
+            "html" => Ok(ParseMode::Html),
+            "markdown" | "markdownv2" => Ok(ParseMode::MarkdownV2),
+            _ => Err("The parse mode should be html or markdownv2"),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetParseMode {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_parse_mode(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
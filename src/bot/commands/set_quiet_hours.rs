@@ -0,0 +1,85 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use crate::deliver::quiet_hours;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_quiet_hours";
+
+#[derive(TypedBuilder)]
+pub struct SetQuietHours {
+    message: Message,
+    args: String,
+}
+
+impl SetQuietHours {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_quiet_hours(&self, db_connection: &mut PgConnection) -> String {
+        let (window, hold) = match self.parse_args() {
+            Ok(result) => result,
+            Err(error) => return error,
+        };
+
+        let chat = match telegram::find_chat(db_connection, self.message.chat.id) {
+            Some(chat) => chat,
+            None => return "You don't have any subcriptions".to_string(),
+        };
+
+        match telegram::set_quiet_hours(db_connection, &chat, Some(window), hold) {
+            Ok(_) => {
+                let mode = if hold {
+                    "held until they end"
+                } else {
+                    "sent without notifications"
+                };
+
+                format!(
+                    "Quiet hours are set to {}. Items will be {mode}",
+                    quiet_hours::format(window.0, window.1)
+                )
+            }
+            Err(_) => "Failed to set quiet hours".to_string(),
+        }
+    }
+
+    fn parse_args(&self) -> Result<((i32, i32), bool), String> {
+        let mut args = self.args.split_whitespace();
+
+        let window = match args.next() {
+            Some(window) => quiet_hours::parse(window)?,
+            None => return Err("Quiet hours should look like 23:00-07:00".to_string()),
+        };
+
+        let hold = match args.next() {
+            None | Some("silent") => false,
+            Some("hold") => true,
+            Some(mode) => return Err(format!("Unknown mode {mode}. Use hold or silent")),
+        };
+
+        if args.next().is_some() {
+            return Err("Wrong number of parameters".to_string());
+        }
+
+        Ok((window, hold))
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetQuietHours {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_quiet_hours(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
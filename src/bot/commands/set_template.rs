@@ -3,7 +3,9 @@ use super::Message;
 use super::Response;
 use crate::bot::SimpleMessageParams;
 use crate::db::telegram;
+use crate::deliver::render_message;
 use crate::deliver::render_template_example;
+use crate::deliver::telegram_html;
 use diesel::PgConnection;
 use typed_builder::TypedBuilder;
 
@@ -34,26 +36,35 @@ impl SetTemplate {
         let feed_url = vec[0];
         let template = vec[1];
 
-        let chat = match telegram::find_chat(db_connection, self.message.chat.id) {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let chat = match telegram::find_chat(db_connection, chat_id) {
             Some(chat) => chat,
             None => return "You don't have any subcriptions".to_string(),
         };
 
-        let subscription =
-            match self.find_subscription(db_connection, self.message.chat.id, feed_url) {
-                Err(message) => return message,
-                Ok(subscription) => subscription,
-            };
+        let subscription = match self.find_subscription(db_connection, chat_id, feed_url) {
+            Err(message) => return message,
+            Ok(subscription) => subscription,
+        };
+
+        let parse_mode = render_message::parse_mode(&chat.parse_mode);
 
-        let example = match render_template_example(template) {
+        let example = match render_template_example(template, parse_mode) {
             Ok(example) => format!("Your messages will look like:\n\n{example}"),
-            Err(_) => return "The template is invalid".to_string(),
+            Err(error) => {
+                return format!(
+                    "The template is invalid: {}",
+                    telegram_html::escape_text(&error)
+                );
+            }
         };
 
         let message_params = SimpleMessageParams::builder()
             .message(example)
             .chat_id(self.message.chat.id)
             .preview_enabled(chat.preview_enabled)
+            .parse_mode(parse_mode)
             .build();
 
         if self.api().reply_with_text_message(&message_params).is_err() {
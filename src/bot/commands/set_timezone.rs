@@ -1,7 +1,10 @@
 use super::Command;
+use super::CommandsKeyboard;
 use super::Message;
 use super::Response;
 use crate::db::telegram;
+use crate::deliver::timezone;
+use chrono::Utc;
 use diesel::PgConnection;
 use typed_builder::TypedBuilder;
 
@@ -11,6 +14,7 @@ static COMMAND: &str = "/set_timezone";
 pub struct SetTimezone {
     message: Message,
     args: String,
+    callback: bool,
 }
 
 impl SetTimezone {
@@ -18,42 +22,85 @@ impl SetTimezone {
         self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
     }
 
-    fn set_timezone(&self, db_connection: &mut PgConnection) -> String {
-        match self.update_timezone(db_connection) {
-            Ok(_) => "Your timezone was updated".to_string(),
-            Err(err_string) => err_string.to_string(),
-        }
-    }
+    fn set_timezone(&self, db_connection: &mut PgConnection) -> Response {
+        let value = self.args.trim();
 
-    fn update_timezone(&self, db_connection: &mut PgConnection) -> Result<(), &str> {
-        let offset = self.validate_offset()?;
+        // numeric values are offsets in minutes, other values are timezone names or searches
+        let (timezone, offset) = if value.parse::<i32>().is_ok() {
+            match timezone::parse_offset(value) {
+                Ok(offset) => (None, Some(offset)),
+                Err(error) => return self.reply(error),
+            }
+        } else {
+            match timezone::find(value) {
+                Some(timezone) => (Some(timezone.name().to_string()), None),
+                None => return self.timezones_keyboard(value),
+            }
+        };
 
-        match telegram::find_chat(db_connection, self.message.chat.id) {
-            None => Err(
-                "You'll be able to set your timezone only after you'll have at least one subscription",
-            ),
-            Some(chat) => match telegram::set_utc_offset_minutes(db_connection, &chat, offset) {
-                Ok(_) => Ok(()),
-                Err(_) => Err("Failed to set your timezone"),
+        let response = match telegram::find_chat(db_connection, self.message.chat.id) {
+            None => "You'll be able to set your timezone only after you'll have at least one subscription".to_string(),
+            Some(chat) => match telegram::set_timezone(db_connection, &chat, timezone, offset) {
+                Ok(chat) => format!(
+                    "Your timezone was updated. The current offset is UTC{}",
+                    timezone::offset_at(chat.timezone.as_deref(), chat.utc_offset_minutes, Utc::now())
+                ),
+                Err(_) => "Failed to set your timezone".to_string(),
             },
-        }
+        };
+
+        self.reply(response)
     }
 
-    fn validate_offset(&self) -> Result<i32, &'static str> {
-        let offset = match self.args.parse::<i32>() {
-            Ok(result) => result,
-            Err(_) => return Err("The value is not a number"),
+    // Matching timezones are offered for searches. Common timezones are offered if nothing matches
+    fn timezones_keyboard(&self, query: &str) -> Response {
+        let found = timezone::search(query);
+
+        let (text, timezones): (String, Vec<String>) = if found.is_empty() {
+            let text = if query.is_empty() {
+                "Choose your timezone or send a timezone name (Europe/Berlin), a city or an offset in minutes".to_string()
+            } else {
+                format!(
+                    "Timezone {query} was not found. Choose your timezone or send a timezone name (Europe/Berlin), a city or an offset in minutes"
+                )
+            };
+
+            (
+                text,
+                timezone::COMMON_TIMEZONES
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            )
+        } else {
+            (
+                format!("Choose your timezone. Timezones matching {query}:"),
+                found
+                    .iter()
+                    .map(|timezone| timezone.name().to_string())
+                    .collect(),
+            )
         };
 
-        if offset % 30 != 0 {
-            return Err("Offset must be divisible by 30");
-        }
+        let options = timezones
+            .into_iter()
+            .map(|name| (name.clone(), format!("{} {name}", Self::command())))
+            .collect();
 
-        if !(-720..=840).contains(&offset) {
-            return Err("Offset must be >= -720 (UTC -12) and <= 840 (UTC +14)");
-        }
+        self.options_keyboard(
+            text,
+            options,
+            CommandsKeyboard::command().to_string(),
+            &self.message,
+        )
+    }
 
-        Ok(offset)
+    fn reply(&self, text: String) -> Response {
+        if self.callback {
+            self.simple_keyboard(text, CommandsKeyboard::command().to_string(), &self.message)
+        } else {
+            Response::Simple(text)
+        }
     }
 
     pub fn command() -> &'static str {
@@ -63,11 +110,182 @@ impl SetTimezone {
 
 impl Command for SetTimezone {
     fn response(&self) -> Response {
-        let response = match self.fetch_db_connection() {
+        match self.fetch_db_connection() {
             Ok(mut connection) => self.set_timezone(&mut connection),
-            Err(error_message) => error_message,
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SetTimezone;
+    use crate::bot::commands::Response;
+    use crate::db;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+    use frankenstein::types::ReplyMarkup;
+
+    fn set_timezone(connection: &mut diesel::PgConnection, args: &str, callback: bool) -> Response {
+        let message = Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(Chat::builder().id(48).type_field(ChatType::Private).build())
+            .build();
+
+        SetTimezone::builder()
+            .message(message)
+            .args(args.to_string())
+            .callback(callback)
+            .build()
+            .set_timezone(connection)
+    }
+
+    fn create_chat(connection: &mut diesel::PgConnection) {
+        telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 48,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap();
+    }
+
+    fn simple_text(response: Response) -> String {
+        match response {
+            Response::Simple(text) => text,
+            Response::Params(_) => panic!("unexpected keyboard"),
+        }
+    }
+
+    // button texts and callbacks of the keyboard without the back and close rows
+    fn keyboard_options(response: &Response) -> Vec<(String, Option<String>)> {
+        let params = match response {
+            Response::Params(params) => params,
+            Response::Simple(text) => panic!("unexpected text {text}"),
         };
 
-        Response::Simple(response)
+        match &params.reply_markup {
+            Some(ReplyMarkup::InlineKeyboardMarkup(keyboard)) => keyboard.inline_keyboard
+                [..keyboard.inline_keyboard.len() - 2]
+                .iter()
+                .flatten()
+                .map(|button| (button.text.clone(), button.callback_data.clone()))
+                .collect(),
+            _ => panic!("keyboard is missing"),
+        }
+    }
+
+    #[test]
+    fn sets_offset_in_minutes() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            create_chat(connection);
+
+            assert_eq!(
+                "Your timezone was updated. The current offset is UTC+03:00",
+                simple_text(set_timezone(connection, "180", false))
+            );
+
+            let chat = telegram::find_chat(connection, 48).unwrap();
+
+            assert_eq!(Some(180), chat.utc_offset_minutes);
+            assert_eq!(None, chat.timezone);
+            assert_eq!(
+                "Offset must be divisible by 30",
+                simple_text(set_timezone(connection, "100", false))
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn sets_timezone_by_name() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            create_chat(connection);
+            set_timezone(connection, "180", false);
+
+            let text = simple_text(set_timezone(connection, "asia/tokyo", false));
+
+            assert_eq!(
+                "Your timezone was updated. The current offset is UTC+09:00",
+                text
+            );
+
+            let chat = telegram::find_chat(connection, 48).unwrap();
+
+            assert_eq!(Some("Asia/Tokyo".to_string()), chat.timezone);
+            assert_eq!(None, chat.utc_offset_minutes);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn offers_matching_timezones() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            create_chat(connection);
+
+            let response = set_timezone(connection, "new york", false);
+
+            assert_eq!(
+                vec![(
+                    "America/New_York".to_string(),
+                    Some("/set_timezone America/New_York".to_string())
+                )],
+                keyboard_options(&response)
+            );
+
+            let response = set_timezone(connection, "", false);
+
+            assert_eq!(12, keyboard_options(&response).len());
+
+            let chat = telegram::find_chat(connection, 48).unwrap();
+
+            assert_eq!(None, chat.timezone);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn sets_timezone_from_keyboard_callbacks() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            create_chat(connection);
+
+            let response = set_timezone(connection, "America/New_York", true);
+
+            assert!(keyboard_options(&response).is_empty());
+
+            match response {
+                Response::Params(params) => {
+                    assert!(params.text.starts_with("Your timezone was updated"))
+                }
+                Response::Simple(_) => panic!("keyboard is missing"),
+            }
+
+            let chat = telegram::find_chat(connection, 48).unwrap();
+
+            assert_eq!(Some("America/New_York".to_string()), chat.timezone);
+
+            Ok(())
+        });
     }
 }
@@ -0,0 +1,260 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::ShowFeedKeyboard;
+use crate::db::feeds;
+use crate::db::telegram;
+use crate::db::telegram_outbox;
+use crate::models::TelegramSubscription;
+use diesel::Connection;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/set_topic";
+
+#[derive(TypedBuilder)]
+pub struct SetTopic {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Topic {
+    // the topic where the command was sent
+    Current,
+    General,
+    New,
+    Id(i32),
+}
+
+impl Topic {
+    fn parse(topic: Option<&str>) -> Result<Self, String> {
+        match topic {
+            None => Ok(Topic::Current),
+            Some("general") => Ok(Topic::General),
+            Some("new") => Ok(Topic::New),
+            Some(id) => match id.parse::<i32>() {
+                Ok(id) if id > 0 => Ok(Topic::Id(id)),
+                _ => Err("The topic should be a topic id, general or new".to_string()),
+            },
+        }
+    }
+}
+
+impl SetTopic {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn set_topic(&self, db_connection: &mut PgConnection) -> Response {
+        let mut args = self.args.split_whitespace();
+        let feed_url_or_external_id = args.next().unwrap_or_default();
+
+        let topic = match Topic::parse(args.next()) {
+            Ok(topic) => topic,
+            Err(error) => return Response::Simple(error),
+        };
+
+        if args.next().is_some() {
+            return Response::Simple("Wrong number of parameters".to_string());
+        }
+
+        // the topic of a private chat means nothing in the selected chat
+        if topic == Topic::Current && self.selected_chat(db_connection, &self.message).is_some() {
+            return Response::Simple(
+                "The topic should be set for a selected chat: a topic id, general or new"
+                    .to_string(),
+            );
+        }
+
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription =
+            match self.find_subscription(db_connection, chat_id, feed_url_or_external_id) {
+                Ok(subscription) => subscription,
+                Err(error) => return Response::Simple(error),
+            };
+
+        let response = match self.thread_id(db_connection, &subscription, topic) {
+            Ok(thread_id) => self.move_subscription(db_connection, &subscription, thread_id),
+            Err(error) => error,
+        };
+
+        if self.callback {
+            self.simple_keyboard(
+                response,
+                format!(
+                    "{} {}",
+                    ShowFeedKeyboard::command(),
+                    subscription.external_id
+                ),
+                &self.message,
+            )
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    fn thread_id(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+        topic: Topic,
+    ) -> Result<Option<i32>, String> {
+        match topic {
+            Topic::Current => Ok(self.message.message_thread_id),
+            Topic::General => Ok(None),
+            Topic::Id(id) => Ok(Some(id)),
+            Topic::New => match feeds::find(db_connection, subscription.feed_id) {
+                Some(feed) => self
+                    .create_forum_topic(subscription.chat_id, &feed)
+                    .map(Some),
+                None => Err("Feed does not exist".to_string()),
+            },
+        }
+    }
+
+    fn move_subscription(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+        thread_id: Option<i32>,
+    ) -> String {
+        let result = db_connection.transaction::<_, diesel::result::Error, _>(|db_connection| {
+            telegram_outbox::set_pending_thread_id(db_connection, subscription, thread_id)?;
+            telegram::set_thread_id(db_connection, subscription, thread_id)
+        });
+
+        match result {
+            Ok(updated_subscription) => match updated_subscription.thread_id {
+                Some(thread_id) => format!("Items will be sent to the topic {thread_id}"),
+                None => "Items will be sent to the general topic".to_string(),
+            },
+            Err(_) => "Failed to update the subscription".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for SetTopic {
+    fn response(&self) -> Response {
+        match self.fetch_db_connection() {
+            Ok(mut connection) => self.set_topic(&mut connection),
+
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
+
+#[cfg(test)]
+mod set_topic_tests {
+    use super::SetTopic;
+    use super::Topic;
+    use crate::bot::commands::Response;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_linked_chats;
+    use crate::db::telegram_linked_chats::NewTelegramLinkedChat;
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+
+    #[test]
+    fn parses_topics() {
+        assert_eq!(Ok(Topic::Current), Topic::parse(None));
+        assert_eq!(Ok(Topic::General), Topic::parse(Some("general")));
+        assert_eq!(Ok(Topic::New), Topic::parse(Some("new")));
+        assert_eq!(Ok(Topic::Id(42)), Topic::parse(Some("42")));
+        assert!(Topic::parse(Some("-1")).is_err());
+        assert!(Topic::parse(Some("topic")).is_err());
+    }
+
+    #[test]
+    fn requires_a_topic_for_the_selected_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            for (id, kind) in [(46, "private"), (-47, "supergroup")] {
+                telegram::create_chat(
+                    connection,
+                    NewTelegramChat {
+                        id,
+                        kind: kind.to_string(),
+                        username: None,
+                        first_name: None,
+                        last_name: None,
+                        title: None,
+                    },
+                )
+                .unwrap();
+            }
+
+            telegram_linked_chats::create(
+                connection,
+                NewTelegramLinkedChat {
+                    chat_id: 46,
+                    linked_chat_id: -47,
+                },
+            )
+            .unwrap();
+
+            let feed = feeds::create(connection, "Link91", "rss".to_string()).unwrap();
+            let new_subscription = NewTelegramSubscription::builder()
+                .chat_id(-47)
+                .feed_id(feed.id)
+                .thread_id(Some(5))
+                .build();
+            telegram::create_subscription(connection, new_subscription).unwrap();
+
+            let chat = telegram::find_chat(connection, 46).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-47)).unwrap();
+
+            let set_topic = |connection: &mut diesel::PgConnection, args: &str| {
+                let message = Message::builder()
+                    .message_id(1)
+                    .date(1_u64)
+                    .chat(Chat::builder().id(46).type_field(ChatType::Private).build())
+                    .build();
+
+                match SetTopic::builder()
+                    .message(message)
+                    .args(args.to_string())
+                    .callback(false)
+                    .build()
+                    .set_topic(connection)
+                {
+                    Response::Simple(text) => text,
+                    Response::Params(_) => panic!("unexpected keyboard"),
+                }
+            };
+
+            assert_eq!(
+                "The topic should be set for a selected chat: a topic id, general or new",
+                set_topic(connection, "Link91")
+            );
+            assert_eq!(
+                "Items will be sent to the general topic",
+                set_topic(connection, "Link91 general")
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(None, subscription.thread_id);
+
+            Ok(())
+        });
+    }
+}
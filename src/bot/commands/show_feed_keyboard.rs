@@ -1,14 +1,26 @@
 use super::Close;
 use super::Command;
+use super::GetFetchOptions;
 use super::GetFilter;
 use super::GetTemplate;
 use super::ListSubscriptionsKeyboard;
+use super::MarkAllRead;
+use super::Pause;
+use super::RemoveFetchOptions;
 use super::RemoveFilter;
 use super::RemoveTemplate;
 use super::Response;
+use super::Resume;
+use super::SetFetchOptions;
 use super::SetFilter;
+use super::SetLimits;
 use super::SetTemplate;
+use super::SetTopic;
+use super::Snooze;
+use super::ToggleSilent;
+use super::ToggleSplitMessages;
 use super::Unsubscribe;
+use super::pause;
 use crate::db::feeds;
 use diesel::PgConnection;
 use frankenstein::methods::SendMessageParams;
@@ -70,7 +82,25 @@ impl ShowFeedKeyboard {
                     ("Set Template", SetTemplate::command()),
                     ("Remove Template", RemoveTemplate::command()),
                 ],
-                vec![("Unsubscribe", Unsubscribe::command())],
+                vec![
+                    ("Show Fetch Options", GetFetchOptions::command()),
+                    ("Set Fetch Options", SetFetchOptions::command()),
+                    ("Remove Fetch Options", RemoveFetchOptions::command()),
+                ],
+                vec![
+                    ("Toggle Splitting", ToggleSplitMessages::command()),
+                    ("Toggle Silent", ToggleSilent::command()),
+                    ("Set Limits", SetLimits::command()),
+                ],
+                vec![
+                    ("Pause", Pause::command()),
+                    ("Snooze", Snooze::command()),
+                    ("Resume", Resume::command()),
+                ],
+                vec![
+                    ("Mark All Read", MarkAllRead::command()),
+                    ("Unsubscribe", Unsubscribe::command()),
+                ],
             ]
         } else {
             [
@@ -82,7 +112,24 @@ impl ShowFeedKeyboard {
                     ("Show Template", GetTemplate::command()),
                     ("Remove Template", RemoveTemplate::command()),
                 ],
-                vec![("Unsubscribe", Unsubscribe::command())],
+                vec![
+                    ("Show Fetch Options", GetFetchOptions::command()),
+                    ("Remove Fetch Options", RemoveFetchOptions::command()),
+                ],
+                vec![
+                    ("Toggle Splitting", ToggleSplitMessages::command()),
+                    ("Toggle Silent", ToggleSilent::command()),
+                    ("Show Limits", SetLimits::command()),
+                ],
+                vec![
+                    ("Pause", Pause::command()),
+                    ("Snooze", Snooze::command()),
+                    ("Resume", Resume::command()),
+                ],
+                vec![
+                    ("Mark All Read", MarkAllRead::command()),
+                    ("Unsubscribe", Unsubscribe::command()),
+                ],
             ]
         };
 
@@ -100,6 +147,24 @@ impl ShowFeedKeyboard {
 
             buttons.push(row);
         }
+
+        // topics can be changed only from the forum itself
+        if self.message.chat.is_forum == Some(true) {
+            let command = format!("{} {}", SetTopic::command(), subscription.external_id);
+
+            let move_button = InlineKeyboardButton::builder()
+                .text("Move To This Topic")
+                .callback_data(command.clone())
+                .build();
+
+            let new_topic_button = InlineKeyboardButton::builder()
+                .text("Move To New Topic")
+                .callback_data(format!("{command} new"))
+                .build();
+
+            buttons.push(vec![move_button, new_topic_button]);
+        }
+
         let mut row: Vec<InlineKeyboardButton> = Vec::new();
 
         let button = InlineKeyboardButton::builder()
@@ -117,9 +182,14 @@ impl ShowFeedKeyboard {
             .inline_keyboard(buttons)
             .build();
 
+        let text = match pause::paused_status(&subscription) {
+            Some(status) => format!("{}\n\n{status}", feed.link),
+            None => feed.link,
+        };
+
         let mut params = SendMessageParams::builder()
             .chat_id(self.message.chat.id)
-            .text(feed.link)
+            .text(text)
             .reply_markup(ReplyMarkup::InlineKeyboardMarkup(keyboard))
             .build();
 
@@ -0,0 +1,284 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::ShowFeedKeyboard;
+use crate::db::telegram;
+use chrono::Duration;
+use chrono::Utc;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/snooze";
+static DURATIONS: [&str; 5] = ["1h", "8h", "1d", "3d", "1w"];
+const MAX_SNOOZE_DAYS: i64 = 365;
+
+#[derive(TypedBuilder)]
+pub struct Snooze {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl Snooze {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn snooze(&self, db_connection: &mut PgConnection) -> Response {
+        let mut args = self.args.split_whitespace();
+        let feed_url_or_external_id = args.next().unwrap_or_default();
+        let duration = args.next();
+
+        if args.next().is_some() {
+            return Response::Simple("Wrong number of parameters".to_string());
+        }
+
+        let subscription = match self.find_subscription(
+            db_connection,
+            self.message.chat.id,
+            feed_url_or_external_id,
+        ) {
+            Ok(subscription) => subscription,
+            Err(error) => return Response::Simple(error),
+        };
+
+        let back_command = format!(
+            "{} {}",
+            ShowFeedKeyboard::command(),
+            subscription.external_id
+        );
+
+        let duration = match duration {
+            Some(duration) => duration,
+            None => {
+                let options = DURATIONS
+                    .iter()
+                    .map(|duration| {
+                        (
+                            format!("Snooze for {duration}"),
+                            format!(
+                                "{} {} {duration}",
+                                Self::command(),
+                                subscription.external_id
+                            ),
+                        )
+                    })
+                    .collect();
+
+                return self.options_keyboard(
+                    "How long should the subscription be snoozed?".to_string(),
+                    options,
+                    back_command,
+                    &self.message,
+                );
+            }
+        };
+
+        let response = match parse_duration(duration) {
+            Ok(duration) => {
+                let until = Utc::now() + duration;
+
+                match telegram::pause_subscription(db_connection, &subscription, Some(until)) {
+                    Ok(_) => format!(
+                        "The subscription is snoozed until {}",
+                        until.format("%Y-%m-%d %H:%M UTC")
+                    ),
+                    Err(_) => "Failed to update the subscription".to_string(),
+                }
+            }
+            Err(error) => error,
+        };
+
+        if self.callback {
+            self.simple_keyboard(response, back_command, &self.message)
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+// Durations look like 30m, 8h, 3d or 2w. Longer durations are cut to a year
+fn parse_duration(duration: &str) -> Result<Duration, String> {
+    let error = "The duration should look like 30m, 8h, 3d or 2w".to_string();
+
+    if duration.len() < 2 {
+        return Err(error);
+    }
+
+    let (amount, unit) = duration.split_at(duration.len() - 1);
+
+    let amount = match amount.parse::<i64>() {
+        Ok(amount) if amount > 0 => amount,
+        _ => return Err(error),
+    };
+
+    let duration = match unit {
+        "m" => Duration::minutes(amount.min(MAX_SNOOZE_DAYS * 24 * 60)),
+        "h" => Duration::hours(amount.min(MAX_SNOOZE_DAYS * 24)),
+        "d" => Duration::days(amount.min(MAX_SNOOZE_DAYS)),
+        "w" => Duration::weeks(amount.min(MAX_SNOOZE_DAYS / 7)),
+        _ => return Err(error),
+    };
+
+    Ok(duration)
+}
+
+impl Command for Snooze {
+    fn response(&self) -> Response {
+        match self.fetch_db_connection() {
+            Ok(mut connection) => self.snooze(&mut connection),
+
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
+
+#[cfg(test)]
+mod snooze_tests {
+    use super::Snooze;
+    use super::parse_duration;
+    use crate::bot::commands::Response;
+    use crate::bot::commands::pause;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use chrono::{Duration, Utc};
+    use diesel::connection::Connection;
+    use frankenstein::types::Chat;
+    use frankenstein::types::ChatType;
+    use frankenstein::types::Message;
+    use frankenstein::types::ReplyMarkup;
+
+    fn snooze(connection: &mut diesel::PgConnection, args: &str) -> Response {
+        let message = Message::builder()
+            .message_id(1)
+            .date(1_u64)
+            .chat(Chat::builder().id(62).type_field(ChatType::Private).build())
+            .build();
+
+        Snooze::builder()
+            .message(message)
+            .args(args.to_string())
+            .callback(false)
+            .build()
+            .snooze(connection)
+    }
+
+    fn simple_text(response: Response) -> String {
+        match response {
+            Response::Simple(text) => text,
+            Response::Params(_) => panic!("unexpected keyboard"),
+        }
+    }
+
+    fn create_subscription(connection: &mut diesel::PgConnection) -> NewTelegramSubscription {
+        let chat = telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 62,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap();
+        let feed = feeds::create(connection, "Link92", "rss".to_string()).unwrap();
+        let new_subscription = NewTelegramSubscription::builder()
+            .chat_id(chat.id)
+            .feed_id(feed.id)
+            .build();
+
+        telegram::create_subscription(connection, new_subscription).unwrap();
+
+        new_subscription
+    }
+
+    #[test]
+    fn snoozes_subscription_until_the_time() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_subscription(connection);
+            let text = simple_text(snooze(connection, "Link92 8h"));
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+            let paused_until = subscription.paused_until.unwrap();
+
+            assert_eq!(
+                format!(
+                    "The subscription is snoozed until {}",
+                    paused_until.format("%Y-%m-%d %H:%M UTC")
+                ),
+                text
+            );
+            assert!(subscription.paused_at.is_some());
+            assert!(paused_until > Utc::now() + Duration::hours(7));
+            assert!(paused_until <= Utc::now() + Duration::hours(8));
+            assert!(pause::is_paused(&subscription));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn offers_durations_and_rejects_invalid_ones() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let new_subscription = create_subscription(connection);
+
+            let response = snooze(connection, "Link92");
+            let Response::Params(params) = response else {
+                panic!("keyboard is missing");
+            };
+            let Some(ReplyMarkup::InlineKeyboardMarkup(keyboard)) = params.reply_markup else {
+                panic!("keyboard is missing");
+            };
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(
+                Some(format!("/snooze {} 1h", subscription.external_id)),
+                keyboard.inline_keyboard[0][0].callback_data
+            );
+            assert_eq!(
+                "The duration should look like 30m, 8h, 3d or 2w",
+                simple_text(snooze(connection, "Link92 8y"))
+            );
+            assert_eq!(
+                "Wrong number of parameters",
+                simple_text(snooze(connection, "Link92 8h 1d"))
+            );
+
+            let subscription = telegram::find_subscription(connection, new_subscription).unwrap();
+
+            assert_eq!(None, subscription.paused_at);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(Ok(Duration::minutes(30)), parse_duration("30m"));
+        assert_eq!(Ok(Duration::hours(8)), parse_duration("8h"));
+        assert_eq!(Ok(Duration::days(3)), parse_duration("3d"));
+        assert_eq!(Ok(Duration::weeks(2)), parse_duration("2w"));
+        assert_eq!(Ok(Duration::days(365)), parse_duration("1000d"));
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("0d").is_err());
+        assert!(parse_duration("3y").is_err());
+    }
+}
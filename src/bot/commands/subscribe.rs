@@ -1,20 +1,32 @@
 use super::Command;
 use super::Message;
 use super::Response;
+use super::SelectChat;
+use super::mark_all_read;
+use crate::bot::telegram_client;
 use crate::config::Config;
+use crate::db::feed_items;
 use crate::db::feeds;
 use crate::db::telegram;
 use crate::db::telegram::NewTelegramSubscription;
+use crate::db::telegram_outbox;
 use crate::deliver::DeliverChatUpdatesJob;
+use crate::deliver::deliver_chat_updates_job::DeliverChatUpdates;
+use crate::models::Feed;
+use crate::models::TelegramChat;
 use crate::models::telegram_subscription::TelegramSubscription;
 use crate::sync::SyncFeedJob;
+use crate::sync::fetch_options::FetchOptions;
 use crate::sync::reader;
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::Connection;
 use diesel::PgConnection;
+use fang::Queue;
 use typed_builder::TypedBuilder;
 use url::Url;
 
 static COMMAND: &str = "/subscribe";
+const MAX_BACKLOG_ITEMS: i64 = 50;
 
 #[derive(TypedBuilder)]
 pub struct Subscribe {
@@ -22,14 +34,52 @@ pub struct Subscribe {
     args: String,
 }
 
+// how many past items are sent right after subscribing.
+// At most MAX_BACKLOG_ITEMS latest items are queued
+#[derive(Debug, PartialEq)]
+enum Backlog {
+    New,
+    Last(i64),
+    Since(DateTime<Utc>),
+    All,
+}
+
+impl Backlog {
+    fn parse(option: &str) -> Option<Result<Self, SubscriptionError>> {
+        let backlog = match option.split_once('=') {
+            None if option == "new" => Ok(Backlog::New),
+            None if option == "all" => Ok(Backlog::All),
+            Some(("last", count)) => match count.parse::<i64>() {
+                Ok(count) if count >= 0 => Ok(Backlog::Last(count)),
+                _ => Err(SubscriptionError::InvalidBacklog(format!(
+                    "Invalid number of items: {count}"
+                ))),
+            },
+            Some(("since", date)) => match NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                Ok(date) => Ok(Backlog::Since(date.and_hms_opt(0, 0, 0).unwrap().and_utc())),
+                Err(_) => Err(SubscriptionError::InvalidBacklog(format!(
+                    "Invalid date: {date}. The date format is YYYY-MM-DD"
+                ))),
+            },
+            _ => return None,
+        };
+
+        Some(backlog)
+    }
+}
+
 #[derive(Debug, PartialEq)]
 enum SubscriptionError {
     DbError(diesel::result::Error),
     InvalidUrl,
     UrlIsNotFeed,
+    FetchError(String),
+    InvalidFetchOptions(String),
+    InvalidBacklog(String),
     SubscriptionAlreadyExists,
     SubscriptionCountLimit,
     SyncError,
+    BacklogError,
 }
 
 impl From<diesel::result::Error> for SubscriptionError {
@@ -40,17 +90,65 @@ impl From<diesel::result::Error> for SubscriptionError {
 
 impl Subscribe {
     pub fn run(&self) {
-        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+        // fetch options contain secrets so they're not logged
+        self.execute(
+            &self.message,
+            &format!("{} {}", Self::command(), self.feed_url()),
+        );
+    }
+
+    fn feed_url(&self) -> &str {
+        self.args.split_whitespace().next().unwrap_or_default()
+    }
+
+    // backlog options are picked from the arguments, the rest are fetch options
+    fn split_options(&self) -> Result<(Option<Backlog>, String), SubscriptionError> {
+        let mut backlog = None;
+        let mut fetch_options = vec![];
+
+        for option in self.args.split_whitespace().skip(1) {
+            match Backlog::parse(option) {
+                Some(_) if backlog.is_some() => {
+                    return Err(SubscriptionError::InvalidBacklog(
+                        "Only one of new, last=N, since=YYYY-MM-DD or all can be set".to_string(),
+                    ));
+                }
+                Some(parsed_backlog) => backlog = Some(parsed_backlog?),
+                None => fetch_options.push(option),
+            }
+        }
+
+        Ok((backlog, fetch_options.join(" ")))
     }
 
     fn subscribe(&self, db_connection: &mut PgConnection) -> String {
-        match self.create_subscription(db_connection) {
-            Ok(_subscription) => format!("Successfully subscribed to {}", self.args),
+        let selected_chat = self.selected_chat(db_connection, &self.message);
+
+        match self.create_subscription(db_connection, selected_chat.clone()) {
+            Ok((_subscription, truncated)) => {
+                let response = match selected_chat {
+                    Some(chat) => format!(
+                        "Successfully subscribed {} to {}",
+                        SelectChat::chat_name(&chat),
+                        self.feed_url()
+                    ),
+                    None => format!("Successfully subscribed to {}", self.feed_url()),
+                };
+
+                if truncated {
+                    format!("{response}. Only {MAX_BACKLOG_ITEMS} latest items will be sent")
+                } else {
+                    response
+                }
+            }
             Err(SubscriptionError::DbError(_)) => {
                 "Something went wrong with the bot's storage".to_string()
             }
             Err(SubscriptionError::InvalidUrl) => "Invalid url".to_string(),
             Err(SubscriptionError::UrlIsNotFeed) => "Url is not a feed".to_string(),
+            Err(SubscriptionError::FetchError(message)) => message,
+            Err(SubscriptionError::InvalidFetchOptions(message)) => message,
+            Err(SubscriptionError::InvalidBacklog(message)) => message,
             Err(SubscriptionError::SubscriptionAlreadyExists) => {
                 "The subscription already exists".to_string()
             }
@@ -58,60 +156,231 @@ impl Subscribe {
                 "You exceeded the number of subscriptions".to_string()
             }
             Err(SubscriptionError::SyncError) => "Failed to sync your feed".to_string(),
+            Err(SubscriptionError::BacklogError) => {
+                "Failed to queue past items of your feed".to_string()
+            }
         }
     }
 
     fn create_subscription(
         &self,
         db_connection: &mut PgConnection,
-    ) -> Result<TelegramSubscription, SubscriptionError> {
-        let feed_type = self.validate_rss_url()?;
-
-        db_connection.transaction::<TelegramSubscription, SubscriptionError, _>(|db_connection| {
-            let chat =
-                telegram::create_chat(db_connection, (*self.message.chat.clone()).into()).unwrap();
-            let feed = feeds::create(db_connection, &self.args, feed_type).unwrap();
+        selected_chat: Option<TelegramChat>,
+    ) -> Result<(TelegramSubscription, bool), SubscriptionError> {
+        let (backlog, fetch_options) = self.split_options()?;
 
-            let new_telegram_subscription = NewTelegramSubscription::builder()
-                .chat_id(chat.id)
-                .feed_id(feed.id)
-                .thread_id(self.message.message_thread_id)
-                .build();
+        if !fetch_options.is_empty() {
+            // the message with secrets shouldn't stay in the chat
+            self.remove_message(&self.message);
+        }
 
-            self.check_if_subscription_exists(db_connection, new_telegram_subscription)?;
-            self.check_number_of_subscriptions(db_connection, chat.id)?;
+        let options = FetchOptions::parse(&fetch_options)
+            .map_err(|error| SubscriptionError::InvalidFetchOptions(error.to_string()))?;
+
+        let feed_type = self.validate_rss_url(&options)?;
+        let encrypted_options = options
+            .encrypt()
+            .map_err(|error| SubscriptionError::InvalidFetchOptions(error.to_string()))?;
+
+        let deliver_now = backlog.is_none();
+
+        let (chat, feed, subscription, truncated) = db_connection.transaction::<(
+            TelegramChat,
+            Feed,
+            TelegramSubscription,
+            bool,
+        ), SubscriptionError, _>(
+            |db_connection| {
+                // topics belong to the chat where the command was sent
+                let (chat, thread_id) = match selected_chat {
+                    Some(chat) => (chat, None),
+                    None => (
+                        telegram::create_chat(db_connection, (*self.message.chat.clone()).into())
+                            .unwrap(),
+                        self.message.message_thread_id,
+                    ),
+                };
+                let feed = feeds::create_with_fetch_options(
+                    db_connection,
+                    self.feed_url(),
+                    feed_type,
+                    encrypted_options,
+                )
+                .unwrap();
 
-            let subscription =
-                telegram::create_subscription(db_connection, new_telegram_subscription).unwrap();
+                let new_telegram_subscription = NewTelegramSubscription::builder()
+                    .chat_id(chat.id)
+                    .feed_id(feed.id)
+                    .thread_id(thread_id)
+                    .build();
+
+                self.check_if_subscription_exists(db_connection, chat.id)?;
+                self.check_number_of_subscriptions(db_connection, chat.id)?;
+
+                let subscription =
+                    telegram::create_subscription(db_connection, new_telegram_subscription)
+                        .unwrap();
+
+                if let Err(_err) = SyncFeedJob::new(feed.id).sync_feed(db_connection) {
+                    return Err(SubscriptionError::SyncError);
+                }
+
+                // the title is known only after the sync
+                let feed = feeds::find(db_connection, feed.id).unwrap_or(feed);
+
+                let truncated = match backlog {
+                    None => false,
+                    Some(backlog) => self.enqueue_backlog(
+                        db_connection,
+                        chat.clone(),
+                        feed.clone(),
+                        &subscription,
+                        backlog,
+                    )?,
+                };
+
+                Ok((chat, feed, subscription, truncated))
+            },
+        )?;
 
-            if let Err(_err) = SyncFeedJob::new(feed.id).sync_feed(db_connection) {
-                return Err(SubscriptionError::SyncError);
-            }
+        // the topic is created after the subscription is saved so a failed subscription doesn't leave it behind
+        let subscription = if chat.topic_per_feed {
+            self.move_to_feed_topic(db_connection, &feed, subscription)
+        } else {
+            subscription
+        };
 
+        if deliver_now {
+            // a rate limited chat receives the rest of the items with the next delivery
             DeliverChatUpdatesJob::new(chat.id)
                 .deliver(db_connection)
                 .unwrap();
+        } else if let Err(error) = Queue::insert_query(
+            db_connection,
+            &DeliverChatUpdatesJob::new(chat.id),
+            Utc::now(),
+        ) {
+            // the backlog is still sent by the periodic delivery
+            log::error!(
+                "Failed to schedule backlog delivery for chat {}: {error:?}",
+                chat.id
+            );
+        }
+
+        Ok((subscription, truncated))
+    }
+
+    fn move_to_feed_topic(
+        &self,
+        db_connection: &mut PgConnection,
+        feed: &Feed,
+        subscription: TelegramSubscription,
+    ) -> TelegramSubscription {
+        let thread_id = match self.create_forum_topic(subscription.chat_id, feed) {
+            Ok(thread_id) => Some(thread_id),
+            Err(_) => return subscription,
+        };
+
+        let result = db_connection.transaction::<_, diesel::result::Error, _>(|db_connection| {
+            telegram_outbox::set_pending_thread_id(db_connection, &subscription, thread_id)?;
+            telegram::set_thread_id(db_connection, &subscription, thread_id)
+        });
+
+        match result {
+            Ok(subscription) => subscription,
+            Err(error) => {
+                log::error!("Failed to move subscription to topic {thread_id:?}: {error:?}");
+
+                subscription
+            }
+        }
+    }
+
+    // Backlog items are queued in the outbox and sent by the delivery job like any other items.
+    // Returns true if there were more items than MAX_BACKLOG_ITEMS
+    fn enqueue_backlog(
+        &self,
+        db_connection: &mut PgConnection,
+        chat: TelegramChat,
+        feed: Feed,
+        subscription: &TelegramSubscription,
+        backlog: Backlog,
+    ) -> Result<bool, SubscriptionError> {
+        let feed_id = feed.id;
+
+        // one extra item shows if the backlog was truncated
+        let (published_since, count) = match backlog {
+            Backlog::New => (None, 0),
+            Backlog::Last(count) => (None, count.min(MAX_BACKLOG_ITEMS + 1)),
+            Backlog::Since(date) => (Some(date), MAX_BACKLOG_ITEMS + 1),
+            Backlog::All => (None, MAX_BACKLOG_ITEMS + 1),
+        };
+
+        let mut items = if count > 0 {
+            feed_items::find_latest_items(db_connection, feed_id, published_since, count)?
+        } else {
+            vec![]
+        };
+
+        let truncated = items.len() as i64 > MAX_BACKLOG_ITEMS;
+        items.truncate(MAX_BACKLOG_ITEMS as usize);
+
+        let queued = !items.is_empty();
+
+        if queued {
+            DeliverChatUpdates::builder()
+                .chat(chat)
+                .feed(feed)
+                .subscription(subscription.clone())
+                .db_connection(db_connection)
+                .api(telegram_client::api())
+                .build()
+                .enqueue_items(items)
+                .map_err(|error| {
+                    log::error!(
+                        "Failed to queue backlog for subscription: {subscription:?} {error:?}"
+                    );
+
+                    SubscriptionError::BacklogError
+                })?;
+        }
+
+        // older items are skipped
+        let subscription = mark_all_read::mark_subscription_read(db_connection, subscription)?;
+
+        if queued {
+            // queued items are sent by the delivery job
+            telegram::mark_subscription_has_updates(db_connection, &subscription)?;
+        }
 
-            Ok(subscription)
-        })
+        Ok(truncated)
     }
 
     fn check_if_subscription_exists(
         &self,
         connection: &mut PgConnection,
-        subscription: NewTelegramSubscription,
+        chat_id: i64,
     ) -> Result<(), SubscriptionError> {
-        match telegram::find_subscription(connection, subscription) {
+        match telegram::find_subscription_by_chat_id_and_feed_link(
+            connection,
+            chat_id,
+            self.feed_url(),
+        ) {
             None => Ok(()),
             Some(_) => Err(SubscriptionError::SubscriptionAlreadyExists),
         }
     }
-    fn validate_rss_url(&self) -> Result<String, SubscriptionError> {
-        match Url::parse(&self.args) {
-            Ok(_) => match reader::validate_rss_url(&self.args) {
-                Ok(feed_type) => Ok(feed_type),
-                _ => Err(SubscriptionError::UrlIsNotFeed),
-            },
+    fn validate_rss_url(&self, options: &FetchOptions) -> Result<String, SubscriptionError> {
+        let feed_url = self.feed_url();
+
+        match Url::parse(feed_url) {
+            Ok(_) => {
+                let data = reader::read_url_with_options(feed_url, options)
+                    .map_err(|error| SubscriptionError::FetchError(error.msg))?;
+
+                reader::detect_feed_type(feed_url, &data)
+                    .map_err(|_| SubscriptionError::UrlIsNotFeed)
+            }
             _ => Err(SubscriptionError::InvalidUrl),
         }
     }
@@ -156,12 +425,23 @@ mod subscribe_tests {
     use crate::db;
     use crate::db::feeds;
     use crate::db::telegram;
+    use crate::db::telegram_outbox;
+    use crate::deliver::DeliverChatUpdatesJob;
     use diesel::connection::Connection;
+    use diesel::prelude::*;
+    use fang::Runnable;
+    use fang::fang_tasks;
     use frankenstein::types::Chat;
     use frankenstein::types::ChatType;
     use frankenstein::types::Message;
     use mockito::Mock;
 
+    fn allow_local_feeds() {
+        unsafe {
+            std::env::set_var("FETCH_ALLOWED_HOSTS", "127.0.0.1");
+        }
+    }
+
     fn set_deliver_server_response(server: &mut mockito::Server) -> Mock {
         let response_string = "{\"ok\":true,\"result\":{\"message_id\":2746,\"from\":{\"id\":1276618370,\"is_bot\":true,\"first_name\":\"test_el_bot\",\"username\":\"el_mon_test_bot\"},\"date\":1618207352,\"chat\":{\"id\":275808073,\"type\":\"private\",\"username\":\"Ayrat555\",\"first_name\":\"Ayrat\",\"last_name\":\"Badykov\"},\"text\":\"Hello!\"}}";
 
@@ -181,6 +461,7 @@ mod subscribe_tests {
         let mut db_connection = db::establish_test_connection();
         let message = create_message();
         let mut server = mockito::Server::new();
+        allow_local_feeds();
 
         let path = "/feed";
         let response = feed_example();
@@ -212,6 +493,181 @@ mod subscribe_tests {
         });
     }
 
+    #[test]
+    fn creates_new_subscription_with_only_new_items() {
+        let mut db_connection = db::establish_test_connection();
+        let message = create_message();
+        let mut server = mockito::Server::new();
+        allow_local_feeds();
+
+        let path = "/feed";
+        let response = feed_example();
+        let _m = server
+            .mock("GET", path)
+            .with_status(200)
+            .with_body(response)
+            .create();
+        let feed_url = format!("{}{}", server.url(), path);
+
+        db_connection.test_transaction::<(), (), _>(|db_connection| {
+            let result = Subscribe::builder()
+                .message(message.clone())
+                .args(format!("{feed_url} new"))
+                .build()
+                .subscribe(db_connection);
+
+            assert_eq!(result, format!("Successfully subscribed to {feed_url}"));
+
+            let subscriptions = telegram::fetch_subscriptions(db_connection, 1, 1000).unwrap();
+
+            assert_eq!(1, subscriptions.len());
+            assert!(
+                telegram::find_undelivered_feed_items(db_connection, &subscriptions[0], 10)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn creates_new_subscription_with_queued_backlog() {
+        let mut db_connection = db::establish_test_connection();
+        let message = create_message();
+        let mut server = mockito::Server::new();
+        allow_local_feeds();
+
+        let path = "/feed";
+        let _m = server
+            .mock("GET", path)
+            .with_status(200)
+            .with_body(feed_example())
+            .create();
+        let feed_url = format!("{}{}", server.url(), path);
+
+        // the backlog is sent by the delivery job, not by the command
+        let deliver_mock = set_deliver_server_response(&mut server).expect(0);
+
+        db_connection.test_transaction::<(), (), _>(|db_connection| {
+            let result = Subscribe::builder()
+                .message(message.clone())
+                .args(format!("{feed_url} last=3"))
+                .build()
+                .subscribe(db_connection);
+
+            assert_eq!(result, format!("Successfully subscribed to {feed_url}"));
+
+            let subscription = telegram::fetch_subscriptions(db_connection, 1, 1000)
+                .unwrap()
+                .remove(0);
+
+            assert!(subscription.has_updates);
+            assert_eq!(
+                3,
+                telegram_outbox::find_for_subscription(db_connection, &subscription)
+                    .unwrap()
+                    .len()
+            );
+            assert!(
+                telegram::find_undelivered_feed_items(db_connection, &subscription, 10)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            let job: &dyn Runnable = &DeliverChatUpdatesJob::new(message.chat.id);
+            let job = serde_json::to_value(job).unwrap();
+            let jobs = fang_tasks::table
+                .filter(fang_tasks::metadata.eq(job))
+                .count()
+                .get_result::<i64>(db_connection)
+                .unwrap();
+
+            assert_eq!(1, jobs);
+
+            Ok(())
+        });
+
+        deliver_mock.assert();
+    }
+
+    #[test]
+    fn creates_new_subscription_with_truncated_backlog() {
+        let mut db_connection = db::establish_test_connection();
+        let message = create_message();
+        let mut server = mockito::Server::new();
+        allow_local_feeds();
+
+        let path = "/feed";
+        let _m = server
+            .mock("GET", path)
+            .with_status(200)
+            .with_body(feed_with_items(super::MAX_BACKLOG_ITEMS + 5))
+            .create();
+        let feed_url = format!("{}{}", server.url(), path);
+
+        db_connection.test_transaction::<(), (), _>(|db_connection| {
+            let result = Subscribe::builder()
+                .message(message.clone())
+                .args(format!("{feed_url} all"))
+                .build()
+                .subscribe(db_connection);
+
+            assert_eq!(
+                result,
+                format!(
+                    "Successfully subscribed to {feed_url}. Only {} latest items will be sent",
+                    super::MAX_BACKLOG_ITEMS
+                )
+            );
+
+            let subscription = telegram::fetch_subscriptions(db_connection, 1, 1000)
+                .unwrap()
+                .remove(0);
+
+            assert_eq!(
+                super::MAX_BACKLOG_ITEMS as usize,
+                telegram_outbox::find_for_subscription(db_connection, &subscription)
+                    .unwrap()
+                    .len()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn create_subscription_fails_when_backlog_option_is_invalid() {
+        let mut db_connection = db::establish_test_connection();
+        let message = create_message();
+
+        db_connection.test_transaction::<(), (), _>(|db_connection| {
+            let result = Subscribe::builder()
+                .message(message)
+                .args("https://www.badykov.com/feed.xml since=yesterday".to_string())
+                .build()
+                .subscribe(db_connection);
+
+            assert_eq!(
+                result,
+                "Invalid date: yesterday. The date format is YYYY-MM-DD".to_string()
+            );
+
+            let result = Subscribe::builder()
+                .message(create_message())
+                .args("https://www.badykov.com/feed.xml new all".to_string())
+                .build()
+                .subscribe(db_connection);
+
+            assert_eq!(
+                result,
+                "Only one of new, last=N, since=YYYY-MM-DD or all can be set".to_string()
+            );
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn create_subscription_fails_to_create_chat_when_rss_url_is_invalid() {
         let mut db_connection = db::establish_test_connection();
@@ -238,6 +694,7 @@ mod subscribe_tests {
         let mut db_connection = db::establish_test_connection();
         let message = create_message();
         let mut server = mockito::Server::new();
+        allow_local_feeds();
 
         let path = "/not_feed";
         let _m = server
@@ -268,6 +725,7 @@ mod subscribe_tests {
         let mut db_connection = db::establish_test_connection();
         let message = create_message();
         let mut server = mockito::Server::new();
+        allow_local_feeds();
 
         let path = "/feed";
         let response = feed_example();
@@ -309,6 +767,7 @@ mod subscribe_tests {
 
         let response = feed_example();
         let mut server = mockito::Server::new();
+        allow_local_feeds();
 
         let path1 = "/feed1";
         let _m1 = server
@@ -364,6 +823,78 @@ mod subscribe_tests {
         });
     }
 
+    #[test]
+    fn create_subscription_fails_when_url_points_to_private_network() {
+        let mut db_connection = db::establish_test_connection();
+        let message = create_message();
+        let mut server = mockito::Server::new();
+        allow_local_feeds();
+
+        let path = "/feed";
+        let _m = server
+            .mock("GET", path)
+            .with_status(200)
+            .with_body(feed_example())
+            .create();
+        let feed_url = format!(
+            "http://localhost:{}{}",
+            server.socket_address().port(),
+            path
+        );
+
+        db_connection.test_transaction::<(), (), _>(|db_connection| {
+            let result = Subscribe::builder()
+                .message(message)
+                .args(feed_url)
+                .build()
+                .subscribe(db_connection);
+
+            assert_eq!(
+                result,
+                "localhost points to a private network address".to_string()
+            );
+
+            let subscriptions = telegram::fetch_subscriptions(db_connection, 1, 1000).unwrap();
+            assert_eq!(0, subscriptions.len());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn create_subscription_fails_when_redirected_to_private_network() {
+        let mut db_connection = db::establish_test_connection();
+        let message = create_message();
+        let mut server = mockito::Server::new();
+        allow_local_feeds();
+
+        let path = "/redirect";
+        let _m = server
+            .mock("GET", path)
+            .with_status(302)
+            .with_header(
+                "Location",
+                &format!("http://localhost:{}/feed", server.socket_address().port()),
+            )
+            .create();
+        let feed_url = format!("{}{}", server.url(), path);
+
+        db_connection.test_transaction::<(), (), _>(|db_connection| {
+            let result = Subscribe::builder()
+                .message(message)
+                .args(feed_url)
+                .build()
+                .subscribe(db_connection);
+
+            assert_eq!(
+                result,
+                "localhost points to a private network address".to_string()
+            );
+
+            Ok(())
+        });
+    }
+
     fn create_message() -> Message {
         let chat = Chat::builder().id(1).type_field(ChatType::Private).build();
         Message::builder()
@@ -376,4 +907,19 @@ mod subscribe_tests {
     fn feed_example() -> String {
         std::fs::read_to_string("./tests/support/rss_feed_example.xml").unwrap()
     }
+
+    fn feed_with_items(count: i64) -> String {
+        let items = (1..=count)
+            .map(|number| {
+                format!(
+                    "<item><title>Item {number}</title><link>https://example.com/{number}</link><pubDate>Sat, 13 Jan 2024 10:{:02}:00 +0000</pubDate></item>",
+                    number % 60
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            "<?xml version=\"1.0\"?><rss version=\"2.0\"><channel><title>Feed</title><link>https://example.com</link><description>Feed</description>{items}</channel></rss>"
+        )
+    }
 }
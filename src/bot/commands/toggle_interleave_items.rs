@@ -0,0 +1,56 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/toggle_interleave_items";
+
+#[derive(TypedBuilder)]
+pub struct ToggleInterleaveItems {
+    message: Message,
+}
+
+impl ToggleInterleaveItems {
+    pub fn run(&self) {
+        self.execute(&self.message, Self::command());
+    }
+
+    fn toggle_interleave_items(&self, db_connection: &mut PgConnection) -> String {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let chat = match telegram::find_chat(db_connection, chat_id) {
+            Some(chat) => chat,
+            None => return "You don't have any subscriptions".to_string(),
+        };
+
+        match telegram::set_interleave_items(db_connection, &chat, !chat.interleave_items) {
+            Ok(updated_chat) => {
+                if updated_chat.interleave_items {
+                    "Items of all subscriptions will be sent together in publication order"
+                        .to_string()
+                } else {
+                    "Items will be sent feed by feed".to_string()
+                }
+            }
+
+            Err(_) => "Failed to update the chat".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for ToggleInterleaveItems {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.toggle_interleave_items(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
@@ -0,0 +1,76 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::ShowFeedKeyboard;
+use crate::db::telegram;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/toggle_silent";
+
+#[derive(TypedBuilder)]
+pub struct ToggleSilent {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl ToggleSilent {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn toggle_silent(&self, db_connection: &mut PgConnection) -> Response {
+        let subscription =
+            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
+                Ok(subscription) => subscription,
+                Err(error) => return Response::Simple(error),
+            };
+
+        let response =
+            match telegram::set_silent(db_connection, &subscription, !subscription.silent) {
+                Ok(updated_subscription) => {
+                    if updated_subscription.silent {
+                        "Items of the subscription will be sent without notifications".to_string()
+                    } else {
+                        "Items of the subscription will be sent with notifications".to_string()
+                    }
+                }
+
+                Err(_) => "Failed to update the subscription".to_string(),
+            };
+
+        if self.callback {
+            self.simple_keyboard(
+                response,
+                format!(
+                    "{} {}",
+                    ShowFeedKeyboard::command(),
+                    subscription.external_id
+                ),
+                &self.message,
+            )
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for ToggleSilent {
+    fn response(&self) -> Response {
+        match self.fetch_db_connection() {
+            Ok(mut connection) => self.toggle_silent(&mut connection),
+
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
@@ -0,0 +1,79 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::ShowFeedKeyboard;
+use crate::db::telegram;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/toggle_split_messages";
+
+#[derive(TypedBuilder)]
+pub struct ToggleSplitMessages {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl ToggleSplitMessages {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn toggle_split_messages(&self, db_connection: &mut PgConnection) -> Response {
+        let subscription =
+            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
+                Ok(subscription) => subscription,
+                Err(error) => return Response::Simple(error),
+            };
+
+        let response = match telegram::set_split_messages(
+            db_connection,
+            &subscription,
+            !subscription.split_messages,
+        ) {
+            Ok(updated_subscription) => {
+                if updated_subscription.split_messages {
+                    "Long items will be split into several messages".to_string()
+                } else {
+                    "Long items will be truncated".to_string()
+                }
+            }
+
+            Err(_) => "Failed to update the subscription".to_string(),
+        };
+
+        if self.callback {
+            self.simple_keyboard(
+                response,
+                format!(
+                    "{} {}",
+                    ShowFeedKeyboard::command(),
+                    subscription.external_id
+                ),
+                &self.message,
+            )
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for ToggleSplitMessages {
+    fn response(&self) -> Response {
+        match self.fetch_db_connection() {
+            Ok(mut connection) => self.toggle_split_messages(&mut connection),
+
+            Err(error_message) => Response::Simple(error_message),
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
@@ -0,0 +1,63 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use crate::db::telegram;
+use diesel::PgConnection;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/toggle_topic_per_feed";
+
+#[derive(TypedBuilder)]
+pub struct ToggleTopicPerFeed {
+    message: Message,
+}
+
+impl ToggleTopicPerFeed {
+    pub fn run(&self) {
+        self.execute(&self.message, Self::command());
+    }
+
+    fn toggle_topic_per_feed(&self, db_connection: &mut PgConnection) -> String {
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        // the option is usually set before the first subscription
+        let chat = if chat_id == self.message.chat.id {
+            telegram::create_chat(db_connection, (*self.message.chat.clone()).into()).ok()
+        } else {
+            telegram::find_chat(db_connection, chat_id)
+        };
+
+        let chat = match chat {
+            Some(chat) => chat,
+            None => return "Failed to update the chat".to_string(),
+        };
+
+        match telegram::set_topic_per_feed(db_connection, &chat, !chat.topic_per_feed) {
+            Ok(updated_chat) => {
+                if updated_chat.topic_per_feed {
+                    "A new topic will be created for every new subscription".to_string()
+                } else {
+                    "New subscriptions will be sent to the topic where /subscribe was sent"
+                        .to_string()
+                }
+            }
+
+            Err(_) => "Failed to update the chat".to_string(),
+        }
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for ToggleTopicPerFeed {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.toggle_topic_per_feed(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        Response::Simple(response)
+    }
+}
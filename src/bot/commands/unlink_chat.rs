@@ -0,0 +1,70 @@
+use super::Command;
+use super::Message;
+use super::Response;
+use super::SelectChat;
+use crate::db::telegram;
+use crate::db::telegram_linked_chats;
+use diesel::PgConnection;
+use frankenstein::methods::SendMessageParams;
+use typed_builder::TypedBuilder;
+
+static COMMAND: &str = "/unlink_chat";
+
+#[derive(TypedBuilder)]
+pub struct UnlinkChat {
+    message: Message,
+    args: String,
+    callback: bool,
+}
+
+impl UnlinkChat {
+    pub fn run(&self) {
+        self.execute(&self.message, &format!("{} {}", Self::command(), self.args));
+    }
+
+    fn unlink_chat(&self, db_connection: &mut PgConnection) -> String {
+        let linked_chat_id = match self.args.trim().parse::<i64>() {
+            Ok(id) => id,
+            Err(_) => return "The id is not a number".to_string(),
+        };
+
+        match telegram_linked_chats::delete(db_connection, self.message.chat.id, linked_chat_id) {
+            Ok(1) => (),
+            Ok(_) => return "The chat is not linked".to_string(),
+            Err(_) => return "Failed to unlink the chat".to_string(),
+        }
+
+        if let Some(chat) = telegram::find_chat(db_connection, self.message.chat.id) {
+            if chat.selected_chat_id == Some(linked_chat_id)
+                && telegram::set_selected_chat(db_connection, &chat, None).is_err()
+            {
+                return "Failed to unlink the chat".to_string();
+            }
+        }
+
+        "The chat was unlinked".to_string()
+    }
+
+    pub fn command() -> &'static str {
+        COMMAND
+    }
+}
+
+impl Command for UnlinkChat {
+    fn response(&self) -> Response {
+        let response = match self.fetch_db_connection() {
+            Ok(mut connection) => self.unlink_chat(&mut connection),
+            Err(error_message) => error_message,
+        };
+
+        if self.callback {
+            self.simple_keyboard(response, SelectChat::command().to_string(), &self.message)
+        } else {
+            Response::Simple(response)
+        }
+    }
+
+    fn send_message(&self, send_message_params: SendMessageParams) {
+        self.send_message_and_remove(send_message_params, &self.message);
+    }
+}
@@ -43,20 +43,22 @@ impl Unsubscribe {
         &self,
         db_connection: &mut PgConnection,
     ) -> Result<String, DeleteSubscriptionError> {
-        let subscription =
-            match self.find_subscription(db_connection, self.message.chat.id, &self.args) {
-                Ok(subscription) => subscription,
-                Err(_) => return Err(DeleteSubscriptionError::FeedNotFound),
-            };
+        let chat_id = self.target_chat_id(db_connection, &self.message);
+
+        let subscription = match self.find_subscription(db_connection, chat_id, &self.args) {
+            Ok(subscription) => subscription,
+            Err(_) => return Err(DeleteSubscriptionError::FeedNotFound),
+        };
 
         let feed = feeds::find(db_connection, subscription.feed_id).unwrap();
 
         let telegram_subscription = NewTelegramSubscription::builder()
-            .chat_id(self.message.chat.id)
+            .chat_id(subscription.chat_id)
             .feed_id(feed.id)
             .build();
 
         match telegram::remove_subscription(db_connection, telegram_subscription) {
+            Ok(0) => Err(DeleteSubscriptionError::FeedNotFound),
             Ok(_) => Ok(feed.link),
             _ => Err(DeleteSubscriptionError::DbError),
         }
@@ -98,6 +100,8 @@ mod unsubscribe_tests {
     use crate::db::telegram;
     use crate::db::telegram::NewTelegramChat;
     use crate::db::telegram::NewTelegramSubscription;
+    use crate::db::telegram_linked_chats;
+    use crate::db::telegram_linked_chats::NewTelegramLinkedChat;
     use diesel::connection::Connection;
     use frankenstein::types::Chat;
     use frankenstein::types::ChatType;
@@ -183,4 +187,87 @@ mod unsubscribe_tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn removes_subscription_of_the_selected_chat() {
+        let mut connection = db::establish_test_connection();
+        let link = "Link88".to_string();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            for (id, kind) in [(42, "private"), (-43, "channel")] {
+                telegram::create_chat(
+                    connection,
+                    NewTelegramChat {
+                        id,
+                        kind: kind.to_string(),
+                        username: None,
+                        first_name: None,
+                        last_name: None,
+                        title: None,
+                    },
+                )
+                .unwrap();
+            }
+
+            telegram_linked_chats::create(
+                connection,
+                NewTelegramLinkedChat {
+                    chat_id: 42,
+                    linked_chat_id: -43,
+                },
+            )
+            .unwrap();
+
+            let chat = telegram::find_chat(connection, 42).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-43)).unwrap();
+
+            let feed = feeds::create(connection, &link, "rss".to_string()).unwrap();
+
+            for chat_id in [42, -43] {
+                let new_subscription = NewTelegramSubscription::builder()
+                    .chat_id(chat_id)
+                    .feed_id(feed.id)
+                    .build();
+
+                telegram::create_subscription(connection, new_subscription).unwrap();
+            }
+
+            let channel_subscription = telegram::find_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(-43)
+                    .feed_id(feed.id)
+                    .build(),
+            )
+            .unwrap();
+
+            let chat = Chat::builder().id(42).type_field(ChatType::Private).build();
+            let message = Message::builder()
+                .message_id(1)
+                .date(1_u64)
+                .chat(chat)
+                .build();
+
+            let unsubscribe = |connection: &mut diesel::PgConnection, args: String| {
+                Unsubscribe::builder()
+                    .message(message.clone())
+                    .args(args)
+                    .build()
+                    .unsubscribe(connection)
+            };
+
+            let result = unsubscribe(connection, channel_subscription.external_id.to_string());
+
+            assert_eq!(format!("Successfully unsubscribed from {link}"), result);
+
+            let result = unsubscribe(connection, link.clone());
+
+            assert_eq!("The subscription does not exist", result);
+
+            assert_eq!(1, telegram::count_subscriptions_for_chat(connection, 42));
+            assert_eq!(0, telegram::count_subscriptions_for_chat(connection, -43));
+
+            Ok(())
+        });
+    }
 }
@@ -0,0 +1,175 @@
+use crate::config::Config;
+use crate::db::telegram_rate_limits;
+use crate::db::telegram_rate_limits::SendSlot;
+use chrono::{DateTime, Duration, Utc};
+use diesel::PgConnection;
+
+// Messages are sent by the command bot and all deliver workers.
+// The state is stored in postgres so limits are shared between processes
+#[derive(Clone, Debug)]
+pub struct RateLimiter {
+    global_interval: Duration,
+    chat_interval: Duration,
+    group_interval: Duration,
+    max_wait: Duration,
+    enabled: bool,
+}
+
+const MAX_WAIT_SECONDS: i64 = 5;
+
+impl RateLimiter {
+    pub fn new(
+        global_interval: Duration,
+        chat_interval: Duration,
+        group_interval: Duration,
+    ) -> Self {
+        Self {
+            global_interval,
+            chat_interval,
+            group_interval,
+            max_wait: Duration::seconds(MAX_WAIT_SECONDS),
+            enabled: true,
+        }
+    }
+
+    // Doesn't wait and doesn't store anything. Used by tests with a fake telegram server
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::new(Duration::zero(), Duration::zero(), Duration::zero())
+        }
+    }
+
+    pub fn from_config() -> Self {
+        let messages_per_second = Config::telegram_messages_per_second().max(1) as i64;
+
+        Self::new(
+            Duration::microseconds(1_000_000 / messages_per_second),
+            Duration::milliseconds(Config::telegram_chat_interval_milliseconds().max(0)),
+            Duration::milliseconds(Config::telegram_group_interval_milliseconds().max(0)),
+        )
+    }
+
+    // Blocks until a message can be sent to the chat.
+    // Returns the time to retry at if the chat has to wait too long.
+    // If the limits can't be checked, messages wait instead of skipping them
+    pub fn wait(&self, chat_id: i64) -> Result<(), DateTime<Utc>> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        match crate::db::pool().get() {
+            Ok(mut connection) => self.reserve(&mut connection, chat_id),
+            Err(error) => {
+                log::error!("Failed to get a connection for the rate limiter: {error:?}");
+
+                Err(self.retry_at())
+            }
+        }
+    }
+
+    fn reserve(&self, connection: &mut PgConnection, chat_id: i64) -> Result<(), DateTime<Utc>> {
+        let slot = telegram_rate_limits::reserve_slot(
+            connection,
+            chat_id,
+            self.chat_interval(chat_id),
+            self.global_interval,
+            self.max_wait,
+        );
+
+        match slot {
+            Ok(SendSlot::Ready(slot)) => {
+                if let Ok(delay) = (slot - Utc::now()).to_std() {
+                    std::thread::sleep(delay);
+                }
+
+                Ok(())
+            }
+            Ok(SendSlot::Wait(until)) => Err(until),
+            Err(error) => {
+                log::error!("Failed to reserve a slot for chat {chat_id}: {error:?}");
+
+                Err(self.retry_at())
+            }
+        }
+    }
+
+    fn retry_at(&self) -> DateTime<Utc> {
+        Utc::now() + self.max_wait
+    }
+
+    pub fn back_off(&self, chat_id: i64, retry_after_seconds: u64) {
+        if !self.enabled {
+            return;
+        }
+
+        let until = Utc::now() + Duration::seconds(retry_after_seconds as i64);
+
+        let result = crate::db::pool()
+            .get()
+            .map_err(|error| format!("{error:?}"))
+            .and_then(|mut connection| {
+                telegram_rate_limits::delay(&mut connection, chat_id, until)
+                    .map_err(|error| format!("{error:?}"))
+            });
+
+        match result {
+            Ok(until) => log::warn!("Chat {chat_id} is rate limited until {until}"),
+            Err(error) => log::error!("Failed to delay chat {chat_id}: {error}"),
+        }
+    }
+
+    // Group and channel ids are negative
+    fn chat_interval(&self, chat_id: i64) -> Duration {
+        if chat_id < 0 {
+            self.group_interval
+        } else {
+            self.chat_interval
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RateLimiter;
+    use crate::db;
+    use crate::db::telegram_rate_limits;
+    use chrono::{Duration, Utc};
+    use diesel::RunQueryDsl;
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    #[test]
+    fn reserve_returns_the_retry_time_of_limited_chats() {
+        let mut connection = db::establish_test_connection();
+        let rate_limiter = RateLimiter::new(Duration::zero(), Duration::zero(), Duration::zero());
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            assert_eq!(Ok(()), rate_limiter.reserve(connection, 9301));
+
+            let until =
+                telegram_rate_limits::delay(connection, 9301, Utc::now() + Duration::minutes(1))?;
+
+            assert_eq!(Err(until), rate_limiter.reserve(connection, 9301));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn reserve_fails_closed_on_database_errors() {
+        let mut connection = db::establish_test_connection();
+        let rate_limiter = RateLimiter::new(Duration::zero(), Duration::zero(), Duration::zero());
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            // the transaction is aborted, so the slot can't be reserved
+            assert!(diesel::sql_query("SELECT 1/0").execute(connection).is_err());
+
+            let retry_at = rate_limiter.reserve(connection, 9302).unwrap_err();
+
+            assert!(retry_at > Utc::now());
+
+            Ok(())
+        });
+    }
+}
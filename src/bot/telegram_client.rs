@@ -1,15 +1,22 @@
+use super::rate_limiter::RateLimiter;
 use crate::config::Config;
 use crate::http_client;
+use crate::models::Feed;
+use chrono::Utc;
 use fang::FangError;
 use frankenstein::ParseMode;
 use frankenstein::TelegramApi;
+use frankenstein::methods::CreateForumTopicParams;
 use frankenstein::methods::DeleteMessageParams;
 use frankenstein::methods::GetUpdatesParams;
 use frankenstein::methods::SendMessageParams;
 use frankenstein::response::ErrorResponse;
 use frankenstein::types::AllowedUpdate;
+use frankenstein::types::ChatId;
+use frankenstein::types::InlineKeyboardMarkup;
 use frankenstein::types::LinkPreviewOptions;
 use frankenstein::types::Message;
+use frankenstein::types::ReplyMarkup;
 use frankenstein::types::ReplyParameters;
 use frankenstein::updates::Update;
 use isahc::HttpClient;
@@ -28,12 +35,52 @@ pub struct Api {
     pub update_params: GetUpdatesParams,
     pub buffer: VecDeque<Update>,
     pub http_client: HttpClient,
+    pub rate_limiter: RateLimiter,
 }
 
 #[derive(Debug)]
 pub enum Error {
     Http { code: u16, message: String },
     Api(ErrorResponse),
+    RateLimited { retry_after: u64 },
+}
+
+impl Error {
+    // Seconds to wait before sending messages to the chat again
+    pub fn retry_after(&self) -> Option<u64> {
+        match self {
+            Error::Api(ErrorResponse {
+                error_code: 429,
+                parameters: Some(parameters),
+                ..
+            }) => parameters.retry_after.map(u64::from),
+            Error::RateLimited { retry_after } => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    // The message has invalid html or markdown
+    pub fn is_parse_error(&self) -> bool {
+        match self {
+            Error::Api(ErrorResponse {
+                error_code: 400,
+                description,
+                ..
+            }) => description.contains("can't parse entities"),
+            _ => false,
+        }
+    }
+
+    // The new id of a group that was upgraded to a supergroup
+    pub fn migrate_to_chat_id(&self) -> Option<i64> {
+        match self {
+            Error::Api(ErrorResponse {
+                parameters: Some(parameters),
+                ..
+            }) => parameters.migrate_to_chat_id,
+            _ => None,
+        }
+    }
 }
 
 impl Default for Api {
@@ -60,6 +107,12 @@ pub struct SimpleMessageParams {
     preview_enabled: bool,
     #[builder(default, setter(into))]
     message_thread_id: Option<i32>,
+    #[builder(default = ParseMode::Html)]
+    parse_mode: ParseMode,
+    #[builder(default, setter(into))]
+    reply_markup: Option<InlineKeyboardMarkup>,
+    #[builder(default = false)]
+    disable_notification: bool,
 }
 
 impl Api {
@@ -82,6 +135,7 @@ impl Api {
             update_params,
             http_client,
             buffer: VecDeque::new(),
+            rate_limiter: RateLimiter::from_config(),
         }
     }
 
@@ -113,7 +167,7 @@ impl Api {
     pub fn reply_with_text_message(
         &self,
         simple_params: &SimpleMessageParams,
-    ) -> Result<(), Error> {
+    ) -> Result<Message, Error> {
         let preview_params = LinkPreviewOptions::builder()
             .is_disabled(!simple_params.preview_enabled)
             .build();
@@ -122,10 +176,18 @@ impl Api {
             .chat_id(simple_params.chat_id)
             .text(simple_params.message.clone())
             .link_preview_options(preview_params)
-            .parse_mode(ParseMode::Html)
+            .parse_mode(simple_params.parse_mode)
             .build();
 
         message_params.message_thread_id = simple_params.message_thread_id;
+        if simple_params.disable_notification {
+            message_params.disable_notification = Some(true);
+        }
+
+        message_params.reply_markup = simple_params
+            .reply_markup
+            .clone()
+            .map(ReplyMarkup::InlineKeyboardMarkup);
 
         if let Some(message_id) = simple_params.reply_message_id {
             let reply_params = ReplyParameters::builder().message_id(message_id).build();
@@ -139,16 +201,50 @@ impl Api {
     pub fn send_message_with_params(
         &self,
         send_message_params: &SendMessageParams,
-    ) -> Result<(), Error> {
+    ) -> Result<Message, Error> {
+        let chat_id = match send_message_params.chat_id {
+            ChatId::Integer(chat_id) => Some(chat_id),
+            ChatId::String(_) => None,
+        };
+
+        if let Some(chat_id) = chat_id {
+            if let Err(until) = self.rate_limiter.wait(chat_id) {
+                let retry_after = (until - Utc::now()).num_seconds().max(1) as u64;
+
+                return Err(Error::RateLimited { retry_after });
+            }
+        }
+
         match self.send_message(send_message_params) {
-            Ok(_) => Ok(()),
+            Ok(response) => Ok(response.result),
             Err(err) => {
+                if let (Some(chat_id), Some(retry_after)) = (chat_id, err.retry_after()) {
+                    self.rate_limiter.back_off(chat_id, retry_after);
+                }
+
                 error!("Failed to send message {err:?}: {send_message_params:?}");
                 Err(err)
             }
         }
     }
 
+    // Topics are named after the feed. Telegram limits names to 128 characters
+    pub fn create_feed_topic(&self, chat_id: i64, feed: &Feed) -> Result<i32, Error> {
+        let name = match &feed.title {
+            Some(title) if !title.trim().is_empty() => title.trim(),
+            _ => &feed.link,
+        };
+
+        let params = CreateForumTopicParams::builder()
+            .chat_id(chat_id)
+            .name(name.chars().take(128).collect::<String>())
+            .build();
+
+        let response = self.create_forum_topic(&params)?;
+
+        Ok(response.result.message_thread_id)
+    }
+
     pub fn remove_message(&self, message: &Message) {
         let params = DeleteMessageParams::builder()
             .chat_id(message.chat.id)
@@ -254,3 +350,43 @@ impl TelegramApi for Api {
 pub fn api() -> &'static Api {
     API.get_or_init(Api::new)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Error;
+    use frankenstein::response::ErrorResponse;
+
+    #[test]
+    fn retry_after_is_parsed_from_too_many_requests_response() {
+        let response = "{\"ok\":false,\"error_code\":429,\"description\":\"Too Many Requests: retry after 35\",\"parameters\":{\"retry_after\":35}}";
+        let error = Error::Api(serde_json::from_str::<ErrorResponse>(response).unwrap());
+
+        assert_eq!(Some(35), error.retry_after());
+
+        let response =
+            "{\"ok\":false,\"error_code\":400,\"description\":\"Bad Request: chat not found\"}";
+        let error = Error::Api(serde_json::from_str::<ErrorResponse>(response).unwrap());
+
+        assert_eq!(None, error.retry_after());
+        assert_eq!(None, error.migrate_to_chat_id());
+        assert_eq!(Some(3), Error::RateLimited { retry_after: 3 }.retry_after());
+    }
+
+    #[test]
+    fn migrate_to_chat_id_is_parsed_from_upgraded_group_response() {
+        let response = "{\"ok\":false,\"error_code\":400,\"description\":\"Bad Request: group chat was upgraded to a supergroup chat\",\"parameters\":{\"migrate_to_chat_id\":-1001234567890}}";
+        let error = Error::Api(serde_json::from_str::<ErrorResponse>(response).unwrap());
+
+        assert_eq!(Some(-1001234567890), error.migrate_to_chat_id());
+        assert_eq!(None, error.retry_after());
+    }
+
+    #[test]
+    fn html_errors_are_detected() {
+        let response = "{\"ok\":false,\"error_code\":400,\"description\":\"Bad Request: can't parse entities: Unsupported start tag \\\"div\\\" at byte offset 0\"}";
+        let error = Error::Api(serde_json::from_str::<ErrorResponse>(response).unwrap());
+
+        assert!(error.is_parse_error());
+        assert!(!Error::RateLimited { retry_after: 3 }.is_parse_error());
+    }
+}
@@ -1,6 +1,7 @@
 use super::commands::CommandProcessor;
 use crate::bot::telegram_client;
 use crate::config::Config;
+use crate::db::telegram;
 use frankenstein::types::MaybeInaccessibleMessage;
 use frankenstein::updates::Update;
 use frankenstein::updates::UpdateContent;
@@ -44,6 +45,14 @@ impl UpdateHandler {
             _ => return,
         };
 
+        if let Some(new_chat_id) = message.migrate_to_chat_id {
+            return Self::migrate_chat(message.chat.id, new_chat_id);
+        }
+
+        if let Some(old_chat_id) = message.migrate_from_chat_id {
+            return Self::migrate_chat(old_chat_id, message.chat.id);
+        }
+
         if let Some(owner_id) = Self::owner_telegram_id() {
             if message.from.is_none() {
                 return;
@@ -85,11 +94,29 @@ impl UpdateHandler {
                 .message(*message)
                 .text(text.unwrap())
                 .callback(true)
+                .user_id(Some(query.from.id))
                 .build()
                 .process();
         }
     }
 
+    // both the old group and the new supergroup receive a service message
+    fn migrate_chat(old_chat_id: i64, new_chat_id: i64) {
+        let mut connection = match crate::db::pool().get() {
+            Ok(connection) => connection,
+            Err(error) => {
+                log::error!("Failed to get a connection to migrate chat {old_chat_id}: {error:?}");
+                return;
+            }
+        };
+
+        match telegram::migrate_chat(&mut connection, old_chat_id, new_chat_id) {
+            Ok(Some(_)) => log::info!("Migrated chat {old_chat_id} to {new_chat_id}"),
+            Ok(None) => (),
+            Err(error) => log::error!("Failed to migrate chat {old_chat_id}: {error:?}"),
+        }
+    }
+
     fn owner_telegram_id() -> Option<i64> {
         Config::owner_telegram_id()
     }
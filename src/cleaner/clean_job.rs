@@ -1,6 +1,12 @@
 use super::RemoveOldItemsJob;
 use crate::Config;
+use crate::db;
 use crate::db::feeds;
+use crate::db::telegram_dedup_fingerprints;
+use crate::db::telegram_outbox;
+use crate::db::telegram_rate_limits;
+use crate::deliver::dedup::MAX_DEDUP_DAYS;
+use chrono::Duration;
 use fang::FangError;
 use fang::PgConnection;
 use fang::Queueable;
@@ -10,6 +16,7 @@ use fang::typetag;
 use serde::{Deserialize, Serialize};
 
 const FEEDS_PER_PAGE: i64 = 500;
+const OUTBOX_RETENTION_DAYS: i64 = 7;
 
 #[derive(Serialize, Deserialize)]
 pub struct CleanJob {}
@@ -29,6 +36,9 @@ impl CleanJob {
         let mut conn = crate::db::pool().get()?;
 
         self.delete_feeds_without_subscriptions(&mut conn);
+        self.delete_expired_rate_limits(&mut conn);
+        self.delete_processed_outbox_messages(&mut conn);
+        self.delete_expired_dedup_fingerprints(&mut conn);
 
         let mut current_feed_ids: Vec<i64>;
         let mut page = 1;
@@ -71,6 +81,35 @@ impl CleanJob {
             Err(error) => log::error!("Failed to remove feeds without subscriptions {error:?}"),
         };
     }
+
+    // Delivered items are kept separately so /save and item buttons work for old messages
+    fn delete_processed_outbox_messages(&self, conn: &mut PgConnection) {
+        let before = db::current_time() - Duration::days(OUTBOX_RETENTION_DAYS);
+
+        match telegram_outbox::delete_processed(conn, before) {
+            Ok(count) => log::info!("Removed {count} processed outbox messages"),
+            Err(error) => log::error!("Failed to remove processed outbox messages {error:?}"),
+        };
+    }
+
+    // fingerprints can't be needed after the longest dedup window
+    fn delete_expired_dedup_fingerprints(&self, conn: &mut PgConnection) {
+        let before = db::current_time() - Duration::days(MAX_DEDUP_DAYS as i64);
+
+        match telegram_dedup_fingerprints::delete_expired(conn, before) {
+            Ok(count) => log::info!("Removed {count} expired dedup fingerprints"),
+            Err(error) => log::error!("Failed to remove expired dedup fingerprints {error:?}"),
+        };
+    }
+
+    fn delete_expired_rate_limits(&self, conn: &mut PgConnection) {
+        let before = db::current_time() - Duration::days(1);
+
+        match telegram_rate_limits::delete_expired(conn, before) {
+            Ok(count) => log::info!("Removed {count} expired telegram rate limits"),
+            Err(error) => log::error!("Failed to remove expired telegram rate limits {error:?}"),
+        };
+    }
 }
 
 #[typetag::serde]
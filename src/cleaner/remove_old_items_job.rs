@@ -5,7 +5,7 @@ use fang::Runnable;
 use fang::typetag;
 use serde::{Deserialize, Serialize};
 
-const MESSAGES_LIMIT_PER_FEED: i64 = 1000;
+pub const MESSAGES_LIMIT_PER_FEED: i64 = 1000;
 
 #[derive(Serialize, Deserialize)]
 pub struct RemoveOldItemsJob {
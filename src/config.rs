@@ -23,6 +23,62 @@ impl Config {
         Self::read_var_with_default("REQUEST_TIMEOUT", "5")
     }
 
+    pub fn fetch_allowed_hosts() -> Vec<String> {
+        Self::read_list_var("FETCH_ALLOWED_HOSTS")
+    }
+
+    pub fn fetch_blocked_hosts() -> Vec<String> {
+        Self::read_list_var("FETCH_BLOCKED_HOSTS")
+    }
+
+    pub fn fetch_max_body_bytes() -> u64 {
+        Self::read_var_with_default("FETCH_MAX_BODY_BYTES", "5242880")
+    }
+
+    pub fn fetch_options_key() -> Option<String> {
+        Self::read_var_option("FETCH_OPTIONS_KEY")
+    }
+
+    pub fn tor_proxy() -> Option<String> {
+        Self::read_var_option("TOR_PROXY")
+    }
+
+    pub fn sync_host_concurrency() -> i64 {
+        Self::read_var_with_default("SYNC_HOST_CONCURRENCY", "2")
+    }
+
+    pub fn sync_host_interval_milliseconds() -> i64 {
+        Self::read_var_with_default("SYNC_HOST_INTERVAL_MILLISECONDS", "1000")
+    }
+
+    pub fn sync_host_backoff_seconds() -> i64 {
+        Self::read_var_with_default("SYNC_HOST_BACKOFF_SECONDS", "60")
+    }
+
+    pub fn sync_host_max_backoff_seconds() -> i64 {
+        Self::read_var_with_default("SYNC_HOST_MAX_BACKOFF_SECONDS", "3600")
+    }
+
+    pub fn sync_jitter_seconds() -> u64 {
+        Self::read_var_with_default("SYNC_JITTER_SECONDS", "30")
+    }
+
+    pub fn sync_archive_depth() -> u32 {
+        Self::read_var_with_default("SYNC_ARCHIVE_DEPTH", "3")
+    }
+
+    pub fn telegram_messages_per_second() -> u32 {
+        Self::read_var_with_default("TELEGRAM_MESSAGES_PER_SECOND", "30")
+    }
+
+    pub fn telegram_chat_interval_milliseconds() -> i64 {
+        Self::read_var_with_default("TELEGRAM_CHAT_INTERVAL_MILLISECONDS", "1000")
+    }
+
+    pub fn telegram_group_interval_milliseconds() -> i64 {
+        Self::read_var_with_default("TELEGRAM_GROUP_INTERVAL_MILLISECONDS", "3000")
+    }
+
     pub fn owner_telegram_id() -> Option<i64> {
         Self::read_var_option("OWNER_TELEGRAM_ID")
     }
@@ -55,6 +111,10 @@ impl Config {
         Self::read_var_with_default("FILTER_LIMIT", "20")
     }
 
+    pub fn rewrite_limit() -> usize {
+        Self::read_var_with_default("REWRITE_LIMIT", "10")
+    }
+
     pub fn commands_db_pool_number() -> u32 {
         Self::read_var_with_default("DATABASE_POOL_SIZE", "5")
     }
@@ -66,7 +126,7 @@ impl Config {
     }
 
     pub fn deliver_interval_in_seconds() -> u32 {
-        let interval: u32 = Self::read_var_with_default("DELIVER_INTERVAL_SECONDS", "60");
+        let interval: u32 = Self::read_var_with_default("DELIVER_INTERVAL_SECONDS", "600");
 
         Self::check_interval(&interval);
         interval
@@ -129,6 +189,17 @@ impl Config {
             .unwrap_or_else(|_| panic!("{name} can not be parsed"))
     }
 
+    fn read_list_var(name: &str) -> Vec<String> {
+        match env::var(name) {
+            Ok(val) => val
+                .split(',')
+                .map(|value| value.trim().to_lowercase())
+                .filter(|value| !value.is_empty())
+                .collect(),
+            Err(_error) => vec![],
+        }
+    }
+
     fn read_var_option<T: FromStr + Debug>(name: &str) -> Option<T>
     where
         <T as FromStr>::Err: std::fmt::Debug,
@@ -13,7 +13,15 @@ use dotenvy::dotenv;
 
 pub mod feed_items;
 pub mod feeds;
+pub mod sync_hosts;
 pub mod telegram;
+pub mod telegram_bookmarks;
+pub mod telegram_dedup_fingerprints;
+pub mod telegram_delivered_items;
+pub mod telegram_linked_chats;
+pub mod telegram_outbox;
+pub mod telegram_rate_limits;
+pub mod telegram_rewrite_rules;
 
 static POOL: OnceLock<r2d2::Pool<r2d2::ConnectionManager<PgConnection>>> = OnceLock::new();
 
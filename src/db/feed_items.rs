@@ -1,8 +1,9 @@
+use crate::db;
 use crate::models::Feed;
 use crate::models::FeedItem;
 use crate::schema::feed_items;
 use crate::sync::FetchedFeedItem;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use diesel::result::Error;
 use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
 use sha2::{Digest, Sha256};
@@ -18,6 +19,7 @@ pub struct NewFeedItem {
     pub guid: Option<String>,
     pub publication_date: DateTime<Utc>,
     pub content_hash: String,
+    pub created_at: Option<DateTime<Utc>>,
 }
 
 pub trait ContentHashable {
@@ -90,6 +92,39 @@ pub fn create(
     conn: &mut PgConnection,
     feed: &Feed,
     fetched_items: Vec<FetchedFeedItem>,
+) -> Result<Vec<FeedItem>, Error> {
+    insert(conn, feed, fetched_items, None)
+}
+
+// Archived items are backdated so they're not delivered as new items
+// and they're the first to be removed by the cleaner
+pub fn create_archived(
+    conn: &mut PgConnection,
+    feed: &Feed,
+    fetched_items: Vec<FetchedFeedItem>,
+) -> Result<Vec<FeedItem>, Error> {
+    let oldest_created_at = feed_items::table
+        .filter(feed_items::feed_id.eq(feed.id))
+        .select(diesel::dsl::min(feed_items::created_at))
+        .first::<Option<DateTime<Utc>>>(conn)?;
+
+    let created_at = oldest_created_at.unwrap_or_else(db::current_time) - Duration::seconds(1);
+
+    insert(conn, feed, fetched_items, Some(created_at))
+}
+
+pub fn count(conn: &mut PgConnection, feed_id: i64) -> Result<i64, Error> {
+    feed_items::table
+        .filter(feed_items::feed_id.eq(feed_id))
+        .count()
+        .get_result::<i64>(conn)
+}
+
+fn insert(
+    conn: &mut PgConnection,
+    feed: &Feed,
+    fetched_items: Vec<FetchedFeedItem>,
+    created_at: Option<DateTime<Utc>>,
 ) -> Result<Vec<FeedItem>, Error> {
     let new_feed_items = fetched_items
         .into_iter()
@@ -105,6 +140,7 @@ pub fn create(
                 guid: fetched_feed_item.guid,
                 publication_date: fetched_feed_item.publication_date,
                 content_hash: hash,
+                created_at,
             }
         })
         .collect::<Vec<NewFeedItem>>();
@@ -123,6 +159,18 @@ pub fn find(conn: &mut PgConnection, feed_id: i64) -> Option<Vec<FeedItem>> {
         .ok()
 }
 
+pub fn find_by_content_hash(
+    conn: &mut PgConnection,
+    feed_id: i64,
+    content_hash: &str,
+) -> Option<FeedItem> {
+    feed_items::table
+        .filter(feed_items::feed_id.eq(feed_id))
+        .filter(feed_items::content_hash.eq(content_hash))
+        .first::<FeedItem>(conn)
+        .ok()
+}
+
 pub fn delete_old_feed_items(
     conn: &mut PgConnection,
     feed_id: i64,
@@ -166,11 +214,35 @@ pub fn get_latest_item(conn: &mut PgConnection, feed_id: i64) -> Option<FeedItem
         .ok()
 }
 
+pub fn find_latest_items(
+    conn: &mut PgConnection,
+    feed_id: i64,
+    published_since: Option<DateTime<Utc>>,
+    count: i64,
+) -> Result<Vec<FeedItem>, Error> {
+    let mut query = feed_items::table
+        .filter(feed_items::feed_id.eq(feed_id))
+        .into_boxed();
+
+    if let Some(published_since) = published_since {
+        query = query.filter(feed_items::publication_date.ge(published_since));
+    }
+
+    query
+        .order((
+            feed_items::publication_date.desc(),
+            feed_items::created_at.desc(),
+        ))
+        .limit(count)
+        .get_results::<FeedItem>(conn)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::db;
     use crate::db::feeds;
     use crate::sync::FetchedFeedItem;
+    use chrono::Duration;
     use diesel::connection::Connection;
     use diesel::result::Error;
     use sha2::{Digest, Sha256};
@@ -418,6 +490,85 @@ mod tests {
         });
     }
 
+    #[test]
+    fn find_latest_items_returns_items_published_since_date() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let feed = feeds::create(connection, "Link", "rss".to_string()).unwrap();
+            let now = db::current_time();
+            let feed_items = (1..=3)
+                .map(|number| FetchedFeedItem {
+                    title: format!("FeedItem{number}"),
+                    description: None,
+                    link: format!("Link{number}"),
+                    author: None,
+                    guid: None,
+                    publication_date: now - Duration::days(number),
+                })
+                .collect();
+
+            super::create(connection, &feed, feed_items).unwrap();
+
+            let latest = super::find_latest_items(connection, feed.id, None, 2).unwrap();
+
+            assert_eq!(2, latest.len());
+            assert_eq!("FeedItem1", latest[0].title);
+            assert_eq!("FeedItem2", latest[1].title);
+
+            let since = now - Duration::days(2);
+            let published_since =
+                super::find_latest_items(connection, feed.id, Some(since), 10).unwrap();
+
+            assert_eq!(2, published_since.len());
+            assert_eq!("FeedItem2", published_since[1].title);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn create_archived_backdates_items() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let feed = feeds::create(connection, "Link", "rss".to_string()).unwrap();
+            let now = db::current_time();
+            let fetched_item = |number: i64| FetchedFeedItem {
+                title: format!("FeedItem{number}"),
+                description: None,
+                link: format!("Link{number}"),
+                author: None,
+                guid: None,
+                publication_date: now - Duration::days(number),
+            };
+
+            super::create(connection, &feed, vec![fetched_item(1)]).unwrap();
+
+            let latest_item = super::get_latest_item(connection, feed.id).unwrap();
+            let archived_items =
+                super::create_archived(connection, &feed, vec![fetched_item(2), fetched_item(3)])
+                    .unwrap();
+
+            assert_eq!(2, archived_items.len());
+
+            for item in archived_items {
+                assert_eq!(
+                    item.created_at,
+                    latest_item.created_at - Duration::seconds(1)
+                );
+            }
+
+            assert_eq!(3, super::count(connection, feed.id).unwrap());
+            assert_eq!(
+                "FeedItem1",
+                super::get_latest_item(connection, feed.id).unwrap().title
+            );
+
+            Ok(())
+        });
+    }
+
     fn calculate_hash(data: &str) -> String {
         let mut hasher = Sha256::new();
         hasher.update(data.as_bytes());
@@ -1,6 +1,7 @@
 use crate::db;
 use crate::models::feed::Feed;
 use crate::schema::{feeds, telegram_subscriptions};
+use crate::sync::fetch_options::EncryptedFetchOptions;
 use chrono::{DateTime, Utc};
 use diesel::dsl::sql;
 use diesel::prelude::*;
@@ -16,21 +17,39 @@ const MAX_RETRIES: i32 = 5;
 struct NewFeed {
     link: String,
     feed_type: String,
+    fetch_options: Option<String>,
+    fetch_options_hash: String,
 }
 
 pub fn create(conn: &mut PgConnection, link: &str, feed_type: String) -> Result<Feed, Error> {
+    create_with_fetch_options(conn, link, feed_type, None)
+}
+
+pub fn create_with_fetch_options(
+    conn: &mut PgConnection,
+    link: &str,
+    feed_type: String,
+    fetch_options: Option<EncryptedFetchOptions>,
+) -> Result<Feed, Error> {
     if feed_type != *"atom" && feed_type != *"rss" && feed_type != *"json" {
         unimplemented!()
     }
 
+    let (fetch_options, fetch_options_hash) = match fetch_options {
+        Some(options) => (Some(options.ciphertext), options.hash),
+        None => (None, "".to_string()),
+    };
+
     let new_feed = NewFeed {
         link: link.trim().to_string(),
         feed_type,
+        fetch_options,
+        fetch_options_hash,
     };
 
     let feed = diesel::insert_into(feeds::table)
         .values(new_feed)
-        .on_conflict(feeds::link)
+        .on_conflict((feeds::link, feeds::fetch_options_hash))
         .do_update()
         .set(feeds::updated_at.eq(db::current_time()))
         .get_result::<Feed>(conn)?;
@@ -84,6 +103,7 @@ pub fn find(conn: &mut PgConnection, id: i64) -> Option<Feed> {
 pub fn find_by_link(conn: &mut PgConnection, link: &str) -> Option<Feed> {
     feeds::table
         .filter(feeds::link.eq(link))
+        .order(feeds::fetch_options_hash)
         .first::<Feed>(conn)
         .ok()
 }
@@ -112,6 +132,12 @@ pub fn find_unsynced_feeds(
         .filter(feeds::sync_retries.eq(0).or(sql::<Bool>(
             "\"feeds\".\"sync_skips\" = pow(2, \"feeds\".\"sync_retries\" - 1)",
         )))
+        // feeds are synced only for active subscriptions
+        .filter(
+            telegram_subscriptions::paused_at
+                .is_null()
+                .or(telegram_subscriptions::paused_until.le(Utc::now())),
+        )
         .select(feeds::id)
         .order(feeds::id)
         .distinct()
@@ -195,6 +221,7 @@ mod tests {
     use crate::models::feed::Feed;
     use crate::models::telegram_subscription::TelegramSubscription;
     use crate::schema::feeds;
+    use crate::sync::fetch_options::EncryptedFetchOptions;
     use chrono::{Duration, Utc};
     use diesel::connection::Connection;
     use diesel::result::Error;
@@ -214,6 +241,46 @@ mod tests {
         assert_eq!(result.description, None);
     }
 
+    #[test]
+    fn create_with_fetch_options_creates_separate_feeds_for_the_same_link() {
+        let link = "Link";
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let options = EncryptedFetchOptions {
+                ciphertext: "ciphertext".to_string(),
+                hash: "hash".to_string(),
+            };
+
+            let feed = super::create(connection, link, "rss".to_string()).unwrap();
+            let private_feed = super::create_with_fetch_options(
+                connection,
+                link,
+                "rss".to_string(),
+                Some(options.clone()),
+            )
+            .unwrap();
+            let same_private_feed = super::create_with_fetch_options(
+                connection,
+                link,
+                "rss".to_string(),
+                Some(options),
+            )
+            .unwrap();
+
+            assert_ne!(feed.id, private_feed.id);
+            assert_eq!(private_feed.id, same_private_feed.id);
+            assert_eq!(private_feed.fetch_options, Some("ciphertext".to_string()));
+            assert_eq!(private_feed.fetch_options_hash, "hash");
+
+            let found_feed = super::find_by_link(connection, link).unwrap();
+
+            assert_eq!(feed.id, found_feed.id);
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn create_fails_to_create_feed_without_link() {
         let link = "";
@@ -631,6 +698,40 @@ mod tests {
         })
     }
 
+    #[test]
+    fn find_unsynced_feeds_skips_feeds_with_only_paused_subscriptions() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let link = "Link".to_string();
+            let feed = super::create(connection, &link, "atom".to_string()).unwrap();
+
+            let subscription = create_telegram_subscription(connection, &feed);
+            let subscription =
+                telegram::pause_subscription(connection, &subscription, None).unwrap();
+
+            let found_unsynced_feeds =
+                super::find_unsynced_feeds(connection, Utc::now(), 1, 1).unwrap();
+
+            assert!(found_unsynced_feeds.is_empty());
+
+            // a snoozed subscription is active again after the snooze ends
+            telegram::pause_subscription(
+                connection,
+                &subscription,
+                Some(Utc::now() - Duration::minutes(1)),
+            )
+            .unwrap();
+
+            let found_unsynced_feeds =
+                super::find_unsynced_feeds(connection, Utc::now(), 1, 1).unwrap();
+
+            assert_eq!(vec![feed.id], found_unsynced_feeds);
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn find_unsynced_feeds_skips_based_on_retries() {
         let mut connection = db::establish_test_connection();
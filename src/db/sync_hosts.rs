@@ -0,0 +1,272 @@
+use crate::db;
+use crate::models::SyncHost;
+use crate::schema::{sync_host_tokens, sync_hosts};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum HostToken {
+    Acquired(i64),
+    Wait(DateTime<Utc>),
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = sync_hosts)]
+struct NewSyncHost {
+    host: String,
+    next_request_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = sync_host_tokens)]
+struct NewSyncHostToken {
+    host: String,
+    expires_at: DateTime<Utc>,
+}
+
+// The host row is locked so workers acquire tokens for the same host one by one.
+// Tokens expire so a crashed worker doesn't block the host forever
+pub fn acquire_token(
+    conn: &mut PgConnection,
+    host: &str,
+    max_concurrency: i64,
+    interval: Duration,
+    lease: Duration,
+) -> Result<HostToken, Error> {
+    conn.transaction::<HostToken, Error, _>(|conn| {
+        let sync_host = find_or_create_for_update(conn, host)?;
+        let now = db::current_time();
+
+        diesel::delete(
+            sync_host_tokens::table
+                .filter(sync_host_tokens::host.eq(host))
+                .filter(sync_host_tokens::expires_at.lt(now)),
+        )
+        .execute(conn)?;
+
+        if let Some(backoff_until) = sync_host.backoff_until {
+            if backoff_until > now {
+                return Ok(HostToken::Wait(backoff_until));
+            }
+        }
+
+        if sync_host.next_request_at > now {
+            return Ok(HostToken::Wait(sync_host.next_request_at));
+        }
+
+        let active_tokens = sync_host_tokens::table
+            .filter(sync_host_tokens::host.eq(host))
+            .count()
+            .get_result::<i64>(conn)?;
+
+        if active_tokens >= max_concurrency {
+            return Ok(HostToken::Wait(now + interval.max(Duration::seconds(1))));
+        }
+
+        let new_token = NewSyncHostToken {
+            host: host.to_string(),
+            expires_at: now + lease,
+        };
+
+        let token_id = diesel::insert_into(sync_host_tokens::table)
+            .values(new_token)
+            .returning(sync_host_tokens::id)
+            .get_result::<i64>(conn)?;
+
+        diesel::update(&sync_host)
+            .set((
+                sync_hosts::next_request_at.eq(now + interval),
+                sync_hosts::updated_at.eq(now),
+            ))
+            .execute(conn)?;
+
+        Ok(HostToken::Acquired(token_id))
+    })
+}
+
+pub fn release_token(conn: &mut PgConnection, token_id: i64) -> Result<usize, Error> {
+    diesel::delete(sync_host_tokens::table.filter(sync_host_tokens::id.eq(token_id))).execute(conn)
+}
+
+// Exponential backoff. `Retry-After` is respected if it's longer
+pub fn set_backoff(
+    conn: &mut PgConnection,
+    host: &str,
+    retry_after: Duration,
+    base: Duration,
+    max: Duration,
+) -> Result<SyncHost, Error> {
+    conn.transaction::<SyncHost, Error, _>(|conn| {
+        let sync_host = find_or_create_for_update(conn, host)?;
+        let now = db::current_time();
+
+        let exponent = sync_host.backoff_count.clamp(0, 16) as u32;
+        let exponential_delay = base * 2_i32.pow(exponent);
+        let delay = exponential_delay.min(max).max(retry_after);
+
+        diesel::update(&sync_host)
+            .set((
+                sync_hosts::backoff_until.eq(now + delay),
+                sync_hosts::backoff_count.eq(sync_host.backoff_count + 1),
+                sync_hosts::updated_at.eq(now),
+            ))
+            .get_result::<SyncHost>(conn)
+    })
+}
+
+pub fn reset_backoff(conn: &mut PgConnection, host: &str) -> Result<usize, Error> {
+    let backoff_until: Option<DateTime<Utc>> = None;
+
+    diesel::update(
+        sync_hosts::table
+            .filter(sync_hosts::host.eq(host))
+            .filter(sync_hosts::backoff_count.gt(0)),
+    )
+    .set((
+        sync_hosts::backoff_until.eq(backoff_until),
+        sync_hosts::backoff_count.eq(0),
+        sync_hosts::updated_at.eq(db::current_time()),
+    ))
+    .execute(conn)
+}
+
+pub fn find(conn: &mut PgConnection, host: &str) -> Option<SyncHost> {
+    sync_hosts::table
+        .filter(sync_hosts::host.eq(host))
+        .first::<SyncHost>(conn)
+        .ok()
+}
+
+fn find_or_create_for_update(conn: &mut PgConnection, host: &str) -> Result<SyncHost, Error> {
+    diesel::insert_into(sync_hosts::table)
+        .values(NewSyncHost {
+            host: host.to_string(),
+            next_request_at: db::current_time(),
+        })
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    sync_hosts::table
+        .filter(sync_hosts::host.eq(host))
+        .for_update()
+        .first::<SyncHost>(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HostToken;
+    use crate::db;
+    use chrono::Duration;
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    #[test]
+    fn acquire_token_limits_concurrency() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let host = "example.com";
+            let interval = Duration::zero();
+            let lease = Duration::seconds(60);
+
+            let first = super::acquire_token(connection, host, 2, interval, lease).unwrap();
+            let second = super::acquire_token(connection, host, 2, interval, lease).unwrap();
+            let third = super::acquire_token(connection, host, 2, interval, lease).unwrap();
+
+            assert!(matches!(first, HostToken::Acquired(_)));
+            assert!(matches!(second, HostToken::Acquired(_)));
+            assert!(matches!(third, HostToken::Wait(_)));
+
+            if let HostToken::Acquired(token_id) = first {
+                super::release_token(connection, token_id).unwrap();
+            }
+
+            let fourth = super::acquire_token(connection, host, 2, interval, lease).unwrap();
+
+            assert!(matches!(fourth, HostToken::Acquired(_)));
+
+            let other_host =
+                super::acquire_token(connection, "example.org", 2, interval, lease).unwrap();
+
+            assert!(matches!(other_host, HostToken::Acquired(_)));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn acquire_token_respects_interval() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let host = "example.com";
+            let interval = Duration::seconds(10);
+            let lease = Duration::seconds(60);
+
+            let first = super::acquire_token(connection, host, 5, interval, lease).unwrap();
+            let second = super::acquire_token(connection, host, 5, interval, lease).unwrap();
+
+            assert!(matches!(first, HostToken::Acquired(_)));
+
+            let sync_host = super::find(connection, host).unwrap();
+
+            assert_eq!(HostToken::Wait(sync_host.next_request_at), second);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn set_backoff_blocks_host_until_reset() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let host = "example.com";
+            let base = Duration::seconds(60);
+            let max = Duration::seconds(3600);
+
+            let sync_host =
+                super::set_backoff(connection, host, Duration::zero(), base, max).unwrap();
+
+            assert_eq!(1, sync_host.backoff_count);
+            assert_eq!(
+                sync_host.backoff_until.unwrap(),
+                sync_host.updated_at + Duration::seconds(60)
+            );
+
+            let sync_host =
+                super::set_backoff(connection, host, Duration::zero(), base, max).unwrap();
+
+            assert_eq!(2, sync_host.backoff_count);
+            assert_eq!(
+                sync_host.backoff_until.unwrap(),
+                sync_host.updated_at + Duration::seconds(120)
+            );
+
+            let sync_host =
+                super::set_backoff(connection, host, Duration::seconds(7200), base, max).unwrap();
+
+            assert_eq!(
+                sync_host.backoff_until.unwrap(),
+                sync_host.updated_at + Duration::seconds(7200)
+            );
+
+            let token =
+                super::acquire_token(connection, host, 5, Duration::zero(), Duration::seconds(60))
+                    .unwrap();
+
+            assert_eq!(HostToken::Wait(sync_host.backoff_until.unwrap()), token);
+
+            super::reset_backoff(connection, host).unwrap();
+
+            let sync_host = super::find(connection, host).unwrap();
+
+            assert_eq!(0, sync_host.backoff_count);
+            assert_eq!(None, sync_host.backoff_until);
+
+            Ok(())
+        });
+    }
+}
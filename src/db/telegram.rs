@@ -4,7 +4,10 @@ use crate::models::feed_item::FeedItem;
 use crate::models::telegram_chat::TelegramChat;
 use crate::models::telegram_subscription::TelegramSubscription;
 use crate::schema::feed_items;
-use crate::schema::{feeds, telegram_chats, telegram_subscriptions};
+use crate::schema::{
+    feeds, telegram_bookmarks, telegram_chats, telegram_dedup_fingerprints, telegram_linked_chats,
+    telegram_outbox, telegram_subscriptions,
+};
 use chrono::{DateTime, Duration, Utc};
 use diesel::dsl::*;
 use diesel::pg::upsert::excluded;
@@ -72,6 +75,20 @@ pub fn set_utc_offset_minutes(
         .get_result::<TelegramChat>(conn)
 }
 
+pub fn set_timezone(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    timezone: Option<String>,
+    offset: Option<i32>,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set((
+            telegram_chats::timezone.eq(timezone),
+            telegram_chats::utc_offset_minutes.eq(offset),
+        ))
+        .get_result::<TelegramChat>(conn)
+}
+
 pub fn set_command(
     conn: &mut PgConnection,
     chat: &TelegramChat,
@@ -102,6 +119,16 @@ pub fn set_global_filter(
         .get_result::<TelegramChat>(conn)
 }
 
+pub fn set_global_link_rules(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    link_rules: Option<Vec<String>>,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set(telegram_chats::link_rules.eq(link_rules))
+        .get_result::<TelegramChat>(conn)
+}
+
 pub fn set_template(
     conn: &mut PgConnection,
     chat: &TelegramSubscription,
@@ -122,6 +149,141 @@ pub fn set_filter(
         .get_result::<TelegramSubscription>(conn)
 }
 
+pub fn set_link_rules(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    link_rules: Option<Vec<String>>,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set(telegram_subscriptions::link_rules.eq(link_rules))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn set_split_messages(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    split_messages: bool,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set(telegram_subscriptions::split_messages.eq(split_messages))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+// Without `until` the subscription is paused until it's resumed
+pub fn pause_subscription(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    until: Option<DateTime<Utc>>,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set((
+            telegram_subscriptions::paused_at.eq(db::current_time()),
+            telegram_subscriptions::paused_until.eq(until),
+        ))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn resume_subscription(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set((
+            telegram_subscriptions::paused_at.eq(None::<DateTime<Utc>>),
+            telegram_subscriptions::paused_until.eq(None::<DateTime<Utc>>),
+        ))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn set_limits(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    messages_limit: Option<i32>,
+    hourly_limit: Option<i32>,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set((
+            telegram_subscriptions::messages_limit.eq(messages_limit),
+            telegram_subscriptions::hourly_limit.eq(hourly_limit),
+        ))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn set_thread_id(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    thread_id: Option<i32>,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set(telegram_subscriptions::thread_id.eq(thread_id))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn set_item_buttons(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    item_buttons: Option<Vec<String>>,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set(telegram_subscriptions::item_buttons.eq(item_buttons))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn set_muted(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    muted: bool,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set(telegram_subscriptions::muted.eq(muted))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn set_excluded_authors(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    excluded_authors: Option<Vec<String>>,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set(telegram_subscriptions::excluded_authors.eq(excluded_authors))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn set_silent(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    silent: bool,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set(telegram_subscriptions::silent.eq(silent))
+        .get_result::<TelegramSubscription>(conn)
+}
+
+pub fn set_quiet_hours(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    quiet_hours: Option<(i32, i32)>,
+    hold: bool,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set((
+            telegram_chats::quiet_hours_start.eq(quiet_hours.map(|(start, _)| start)),
+            telegram_chats::quiet_hours_end.eq(quiet_hours.map(|(_, end)| end)),
+            telegram_chats::quiet_hours_hold.eq(hold),
+        ))
+        .get_result::<TelegramChat>(conn)
+}
+
+pub fn set_selected_chat(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    selected_chat_id: Option<i64>,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set(telegram_chats::selected_chat_id.eq(selected_chat_id))
+        .get_result::<TelegramChat>(conn)
+}
+
 pub fn set_preview_enabled(
     conn: &mut PgConnection,
     chat: &TelegramChat,
@@ -132,6 +294,66 @@ pub fn set_preview_enabled(
         .get_result::<TelegramChat>(conn)
 }
 
+pub fn set_topic_per_feed(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    topic_per_feed: bool,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set(telegram_chats::topic_per_feed.eq(topic_per_feed))
+        .get_result::<TelegramChat>(conn)
+}
+
+pub fn set_interleave_items(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    interleave_items: bool,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set(telegram_chats::interleave_items.eq(interleave_items))
+        .get_result::<TelegramChat>(conn)
+}
+
+pub fn set_dedup_days(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    dedup_days: Option<i32>,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set(telegram_chats::dedup_days.eq(dedup_days))
+        .get_result::<TelegramChat>(conn)
+}
+
+pub fn set_date_format(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    date_format: Option<String>,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set(telegram_chats::date_format.eq(date_format))
+        .get_result::<TelegramChat>(conn)
+}
+
+pub fn set_date_language(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    date_language: Option<String>,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set(telegram_chats::date_language.eq(date_language))
+        .get_result::<TelegramChat>(conn)
+}
+
+pub fn set_parse_mode(
+    conn: &mut PgConnection,
+    chat: &TelegramChat,
+    parse_mode: &str,
+) -> Result<TelegramChat, Error> {
+    diesel::update(chat)
+        .set(telegram_chats::parse_mode.eq(parse_mode))
+        .get_result::<TelegramChat>(conn)
+}
+
 pub fn create_subscription(
     conn: &mut PgConnection,
     subscription: NewTelegramSubscription,
@@ -152,6 +374,20 @@ pub fn find_subscription(
         .ok()
 }
 
+pub fn find_subscription_by_chat_id_and_feed_link(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    feed_link: &str,
+) -> Option<TelegramSubscription> {
+    telegram_subscriptions::table
+        .inner_join(feeds::table)
+        .filter(telegram_subscriptions::chat_id.eq(chat_id))
+        .filter(feeds::link.eq(feed_link))
+        .select(telegram_subscriptions::all_columns)
+        .first::<TelegramSubscription>(conn)
+        .ok()
+}
+
 pub fn find_subscription_by_external_id(
     conn: &mut PgConnection,
     external_id: Uuid,
@@ -179,6 +415,157 @@ pub fn remove_chat(conn: &mut PgConnection, chat_id: i64) -> Result<usize, Error
     diesel::delete(record_query).execute(conn)
 }
 
+// Telegram changes the chat id when a group is upgraded to a supergroup.
+// Settings of the new chat are kept if it already exists
+pub fn migrate_chat(
+    conn: &mut PgConnection,
+    old_chat_id: i64,
+    new_chat_id: i64,
+) -> Result<Option<TelegramChat>, Error> {
+    conn.transaction::<Option<TelegramChat>, Error, _>(|conn| {
+        let old_chat = match find_chat(conn, old_chat_id) {
+            Some(chat) => chat,
+            None => return Ok(None),
+        };
+
+        let existing_chat = find_chat(conn, new_chat_id);
+
+        let new_chat = create_chat(
+            conn,
+            NewTelegramChat {
+                id: new_chat_id,
+                kind: "supergroup".to_string(),
+                title: old_chat.title.clone(),
+                username: old_chat.username.clone(),
+                first_name: old_chat.first_name.clone(),
+                last_name: old_chat.last_name.clone(),
+            },
+        )?;
+
+        let settings_chat = existing_chat.unwrap_or_else(|| old_chat.clone());
+
+        let (timezone, utc_offset_minutes) =
+            if settings_chat.timezone.is_some() || settings_chat.utc_offset_minutes.is_some() {
+                (
+                    settings_chat.timezone.clone(),
+                    settings_chat.utc_offset_minutes,
+                )
+            } else {
+                (old_chat.timezone.clone(), old_chat.utc_offset_minutes)
+            };
+        let template = settings_chat.template.or(old_chat.template);
+        let filter_words = settings_chat.filter_words.or(old_chat.filter_words);
+        let link_rules = settings_chat.link_rules.or(old_chat.link_rules);
+        let date_format = settings_chat.date_format.or(old_chat.date_format);
+        let date_language = settings_chat.date_language.or(old_chat.date_language);
+
+        let (quiet_hours_start, quiet_hours_end, quiet_hours_hold) =
+            match settings_chat.quiet_hours_start {
+                Some(_) => (
+                    settings_chat.quiet_hours_start,
+                    settings_chat.quiet_hours_end,
+                    settings_chat.quiet_hours_hold,
+                ),
+                None => (
+                    old_chat.quiet_hours_start,
+                    old_chat.quiet_hours_end,
+                    old_chat.quiet_hours_hold,
+                ),
+            };
+
+        let new_chat = diesel::update(&new_chat)
+            .set((
+                telegram_chats::utc_offset_minutes.eq(utc_offset_minutes),
+                telegram_chats::timezone.eq(timezone),
+                telegram_chats::template.eq(template),
+                telegram_chats::filter_words.eq(filter_words),
+                telegram_chats::preview_enabled.eq(settings_chat.preview_enabled),
+                telegram_chats::parse_mode.eq(settings_chat.parse_mode),
+                telegram_chats::quiet_hours_start.eq(quiet_hours_start),
+                telegram_chats::quiet_hours_end.eq(quiet_hours_end),
+                telegram_chats::quiet_hours_hold.eq(quiet_hours_hold),
+                telegram_chats::topic_per_feed.eq(settings_chat.topic_per_feed),
+                telegram_chats::interleave_items.eq(settings_chat.interleave_items),
+                telegram_chats::dedup_days.eq(settings_chat.dedup_days),
+                telegram_chats::link_rules.eq(link_rules),
+                telegram_chats::date_format.eq(date_format),
+                telegram_chats::date_language.eq(date_language),
+            ))
+            .get_result::<TelegramChat>(conn)?;
+
+        let new_chat_feed_ids = telegram_subscriptions::table
+            .filter(telegram_subscriptions::chat_id.eq(new_chat_id))
+            .select(telegram_subscriptions::feed_id)
+            .load::<i64>(conn)?;
+
+        diesel::update(
+            telegram_subscriptions::table
+                .filter(telegram_subscriptions::chat_id.eq(old_chat_id))
+                .filter(telegram_subscriptions::feed_id.ne_all(new_chat_feed_ids)),
+        )
+        .set((
+            telegram_subscriptions::chat_id.eq(new_chat_id),
+            telegram_subscriptions::updated_at.eq(db::current_time()),
+        ))
+        .execute(conn)?;
+
+        let new_chat_links = telegram_bookmarks::table
+            .filter(telegram_bookmarks::chat_id.eq(new_chat_id))
+            .select(telegram_bookmarks::link)
+            .load::<String>(conn)?;
+
+        diesel::update(
+            telegram_bookmarks::table
+                .filter(telegram_bookmarks::chat_id.eq(old_chat_id))
+                .filter(telegram_bookmarks::link.ne_all(new_chat_links)),
+        )
+        .set((
+            telegram_bookmarks::chat_id.eq(new_chat_id),
+            telegram_bookmarks::updated_at.eq(db::current_time()),
+        ))
+        .execute(conn)?;
+
+        let new_chat_linked_by = telegram_linked_chats::table
+            .filter(telegram_linked_chats::linked_chat_id.eq(new_chat_id))
+            .select(telegram_linked_chats::chat_id)
+            .load::<i64>(conn)?;
+
+        diesel::update(
+            telegram_linked_chats::table
+                .filter(telegram_linked_chats::linked_chat_id.eq(old_chat_id))
+                .filter(telegram_linked_chats::chat_id.ne_all(new_chat_linked_by)),
+        )
+        .set((
+            telegram_linked_chats::linked_chat_id.eq(new_chat_id),
+            telegram_linked_chats::updated_at.eq(db::current_time()),
+        ))
+        .execute(conn)?;
+
+        let new_chat_fingerprints = telegram_dedup_fingerprints::table
+            .filter(telegram_dedup_fingerprints::chat_id.eq(new_chat_id))
+            .select(telegram_dedup_fingerprints::fingerprint)
+            .load::<String>(conn)?;
+
+        diesel::update(
+            telegram_dedup_fingerprints::table
+                .filter(telegram_dedup_fingerprints::chat_id.eq(old_chat_id))
+                .filter(telegram_dedup_fingerprints::fingerprint.ne_all(new_chat_fingerprints)),
+        )
+        .set(telegram_dedup_fingerprints::chat_id.eq(new_chat_id))
+        .execute(conn)?;
+
+        diesel::update(
+            telegram_chats::table.filter(telegram_chats::selected_chat_id.eq(old_chat_id)),
+        )
+        .set(telegram_chats::selected_chat_id.eq(new_chat_id))
+        .execute(conn)?;
+
+        remove_chat(conn, old_chat_id)?;
+
+        Ok(Some(new_chat))
+    })
+}
+
 pub fn count_subscriptions_for_chat(conn: &mut PgConnection, chat_id: i64) -> i64 {
     telegram_subscriptions::table
         .filter(telegram_subscriptions::chat_id.eq(chat_id))
@@ -194,6 +581,12 @@ pub fn find_unread_subscriptions_for_chat(
     telegram_subscriptions::table
         .filter(telegram_subscriptions::chat_id.eq(chat_id))
         .filter(telegram_subscriptions::has_updates.eq(true))
+        .filter(telegram_subscriptions::muted.eq(false))
+        .filter(
+            telegram_subscriptions::paused_at
+                .is_null()
+                .or(telegram_subscriptions::paused_until.le(Utc::now())),
+        )
         .get_results::<TelegramSubscription>(conn)
 }
 
@@ -229,6 +622,27 @@ pub fn find_chats_by_feed_id(
         .get_results::<TelegramChat>(conn)
 }
 
+// Chats that have undelivered items of the feed.
+// Subscriptions with pending messages are skipped, their messages are held
+// (quiet hours, backoff) and the delivery job is already scheduled to retry
+pub fn find_chat_ids_with_updates_by_feed_id(
+    conn: &mut PgConnection,
+    feed_id: i64,
+) -> Result<Vec<i64>, Error> {
+    let pending_messages = telegram_outbox::table
+        .filter(telegram_outbox::chat_id.eq(telegram_subscriptions::chat_id))
+        .filter(telegram_outbox::feed_id.eq(telegram_subscriptions::feed_id))
+        .filter(telegram_outbox::status.eq(db::telegram_outbox::PENDING));
+
+    telegram_subscriptions::table
+        .filter(telegram_subscriptions::feed_id.eq(feed_id))
+        .filter(telegram_subscriptions::has_updates.eq(true))
+        .filter(not(exists(pending_messages)))
+        .order(telegram_subscriptions::chat_id)
+        .select(telegram_subscriptions::chat_id)
+        .get_results(conn)
+}
+
 pub fn fetch_subscriptions(
     conn: &mut PgConnection,
     page: i64,
@@ -253,6 +667,12 @@ pub fn fetch_chats_with_subscriptions(
     telegram_chats::table
         .inner_join(telegram_subscriptions::table)
         .filter(telegram_subscriptions::has_updates.eq(true))
+        .filter(telegram_subscriptions::muted.eq(false))
+        .filter(
+            telegram_subscriptions::paused_at
+                .is_null()
+                .or(telegram_subscriptions::paused_until.le(Utc::now())),
+        )
         .order(telegram_chats::id)
         .select(telegram_chats::id)
         .distinct()
@@ -336,6 +756,21 @@ pub fn set_subscription_last_delivered_at(
         .get_result::<TelegramSubscription>(conn)
 }
 
+pub fn set_subscription_feed(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    feed_id: i64,
+    last_delivered_at: Option<DateTime<Utc>>,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set((
+            telegram_subscriptions::feed_id.eq(feed_id),
+            telegram_subscriptions::last_delivered_at.eq(last_delivered_at),
+            telegram_subscriptions::updated_at.eq(db::current_time()),
+        ))
+        .get_result::<TelegramSubscription>(conn)
+}
+
 pub fn mark_subscription_delivered(
     conn: &mut PgConnection,
     subscription: &TelegramSubscription,
@@ -345,6 +780,15 @@ pub fn mark_subscription_delivered(
         .get_result::<TelegramSubscription>(conn)
 }
 
+pub fn mark_subscription_has_updates(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+) -> Result<TelegramSubscription, Error> {
+    diesel::update(subscription)
+        .set(telegram_subscriptions::has_updates.eq(true))
+        .get_result::<TelegramSubscription>(conn)
+}
+
 pub fn set_subscriptions_has_updates(
     conn: &mut PgConnection,
     feed_id: i64,
@@ -369,7 +813,9 @@ mod tests {
     use super::NewTelegramSubscription;
     use crate::db;
     use crate::db::feeds;
+    use crate::db::telegram_dedup_fingerprints;
     use crate::models::telegram_chat::TelegramChat;
+    use chrono::{Duration, Utc};
     use diesel::connection::Connection;
     use diesel::result::Error;
 
@@ -696,6 +1142,40 @@ mod tests {
         });
     }
 
+    #[test]
+    fn fetch_chats_with_subscriptions_skips_muted_subscriptions() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let feed1 = feeds::create(connection, "Link97", "atom".to_string()).unwrap();
+            let feed2 = feeds::create(connection, "Link96", "atom".to_string()).unwrap();
+            let chat = super::create_chat(connection, build_new_chat()).unwrap();
+
+            let mut subscriptions = Vec::new();
+
+            for feed in [feed1, feed2] {
+                let new_subscription = NewTelegramSubscription::builder()
+                    .chat_id(chat.id)
+                    .feed_id(feed.id)
+                    .build();
+
+                let subscription = super::create_subscription(connection, new_subscription)?;
+                subscriptions.push(super::set_muted(connection, &subscription, true)?);
+            }
+
+            assert!(super::fetch_chats_with_subscriptions(connection, 1, 10)?.is_empty());
+
+            super::set_muted(connection, &subscriptions[0], false)?;
+
+            assert_eq!(
+                vec![chat.id],
+                super::fetch_chats_with_subscriptions(connection, 1, 10)?
+            );
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn count_subscriptions_for_chat_counts_the_number_of_subscriptions() {
         let mut connection = db::establish_test_connection();
@@ -757,6 +1237,54 @@ mod tests {
         });
     }
 
+    #[test]
+    fn find_unread_subscriptions_for_chat_skips_paused_subscriptions() {
+        let mut connection = db::establish_test_connection();
+
+        let new_chat = build_new_chat();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let feed = feeds::create(connection, "Link81", "atom".to_string()).unwrap();
+            let chat = super::create_chat(connection, new_chat).unwrap();
+
+            let subscription = super::create_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(chat.id)
+                    .feed_id(feed.id)
+                    .build(),
+            )
+            .unwrap();
+
+            let subscription = super::pause_subscription(
+                connection,
+                &subscription,
+                Some(Utc::now() + Duration::days(1)),
+            )
+            .unwrap();
+
+            assert!(
+                super::find_unread_subscriptions_for_chat(connection, chat.id)
+                    .unwrap()
+                    .is_empty()
+            );
+            assert!(
+                super::fetch_chats_with_subscriptions(connection, 1, 10)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            super::resume_subscription(connection, &subscription).unwrap();
+
+            let result = super::find_unread_subscriptions_for_chat(connection, chat.id).unwrap();
+
+            assert_eq!(1, result.len());
+            assert_eq!(None, result[0].paused_at);
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn find_unread_subscriptions_for_chat_does_not_return_wrong_chats() {
         let mut connection = db::establish_test_connection();
@@ -880,6 +1408,27 @@ mod tests {
         });
     }
 
+    #[test]
+    fn set_timezone_replaces_offset() {
+        let mut connection = db::establish_test_connection();
+
+        let new_chat = build_new_chat();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let chat = super::create_chat(connection, new_chat).unwrap();
+            let chat = super::set_utc_offset_minutes(connection, &chat, 180).unwrap();
+
+            let result =
+                super::set_timezone(connection, &chat, Some("Europe/Berlin".to_string()), None)
+                    .unwrap();
+
+            assert_eq!(Some("Europe/Berlin".to_string()), result.timezone);
+            assert_eq!(None, result.utc_offset_minutes);
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn set_global_template_sets_template() {
         let mut connection = db::establish_test_connection();
@@ -1063,6 +1612,91 @@ mod tests {
 
             assert!(!found_sub.has_updates);
 
+            let chat_ids =
+                super::find_chat_ids_with_updates_by_feed_id(connection, feed1.id).unwrap();
+
+            assert_eq!(vec![chat1.id, chat2.id], chat_ids);
+            assert!(
+                super::find_chat_ids_with_updates_by_feed_id(connection, feed2.id)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn migrate_chat_moves_settings_and_subscriptions() {
+        let mut connection = db::establish_test_connection();
+
+        let new_chat = NewTelegramChat {
+            kind: "group".to_string(),
+            ..build_new_chat_with_id(-9002)
+        };
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let feed = feeds::create(connection, "Link", "rss".to_string()).unwrap();
+            let chat = super::create_chat(connection, new_chat).unwrap();
+            let chat = super::set_utc_offset_minutes(connection, &chat, 180).unwrap();
+            super::set_global_template(connection, &chat, Some("{{bot_item_name}}".to_string()))
+                .unwrap();
+
+            let telegram_subscription = NewTelegramSubscription::builder()
+                .chat_id(chat.id)
+                .feed_id(feed.id)
+                .build();
+
+            let subscription =
+                super::create_subscription(connection, telegram_subscription).unwrap();
+            super::set_filter(connection, &subscription, Some(vec!["rust".to_string()])).unwrap();
+
+            telegram_dedup_fingerprints::save(connection, chat.id, vec!["fingerprint".to_string()])
+                .unwrap();
+
+            let migrated_chat = super::migrate_chat(connection, chat.id, -1009002)
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(-1009002, migrated_chat.id);
+            assert_eq!("supergroup", migrated_chat.kind);
+            assert_eq!(Some(180), migrated_chat.utc_offset_minutes);
+            assert_eq!(
+                Some("{{bot_item_name}}".to_string()),
+                migrated_chat.template
+            );
+            assert!(super::find_chat(connection, chat.id).is_none());
+
+            let subscription = super::find_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(migrated_chat.id)
+                    .feed_id(feed.id)
+                    .build(),
+            )
+            .unwrap();
+
+            assert_eq!(Some(vec!["rust".to_string()]), subscription.filter_words);
+
+            let fingerprints = vec!["fingerprint".to_string()];
+
+            assert_eq!(
+                fingerprints,
+                telegram_dedup_fingerprints::find_delivered(
+                    connection,
+                    migrated_chat.id,
+                    &fingerprints,
+                    db::current_time() - Duration::days(1)
+                )
+                .unwrap()
+            );
+
+            assert!(
+                super::migrate_chat(connection, chat.id, -1009002)
+                    .unwrap()
+                    .is_none()
+            );
+
             Ok(())
         });
     }
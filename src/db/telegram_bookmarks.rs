@@ -0,0 +1,160 @@
+use crate::models::TelegramBookmark;
+use crate::schema::telegram_bookmarks;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = telegram_bookmarks)]
+pub struct NewTelegramBookmark {
+    pub chat_id: i64,
+    pub feed_link: String,
+    pub title: String,
+    pub link: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub publication_date: DateTime<Utc>,
+}
+
+// Returns None if the item is already saved
+pub fn create(
+    conn: &mut PgConnection,
+    bookmark: NewTelegramBookmark,
+) -> Result<Option<TelegramBookmark>, Error> {
+    diesel::insert_into(telegram_bookmarks::table)
+        .values(bookmark)
+        .on_conflict((telegram_bookmarks::chat_id, telegram_bookmarks::link))
+        .do_nothing()
+        .get_result::<TelegramBookmark>(conn)
+        .optional()
+}
+
+// Bookmarks are listed from the newest to the oldest. Pages start with 1
+pub fn find_page(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    page: i64,
+    per_page: i64,
+) -> Result<Vec<TelegramBookmark>, Error> {
+    telegram_bookmarks::table
+        .filter(telegram_bookmarks::chat_id.eq(chat_id))
+        .order((
+            telegram_bookmarks::created_at.desc(),
+            telegram_bookmarks::id.desc(),
+        ))
+        .offset((page - 1) * per_page)
+        .limit(per_page)
+        .load::<TelegramBookmark>(conn)
+}
+
+pub fn count(conn: &mut PgConnection, chat_id: i64) -> Result<i64, Error> {
+    telegram_bookmarks::table
+        .filter(telegram_bookmarks::chat_id.eq(chat_id))
+        .count()
+        .get_result::<i64>(conn)
+}
+
+pub fn delete(conn: &mut PgConnection, chat_id: i64, id: i64) -> Result<usize, Error> {
+    diesel::delete(
+        telegram_bookmarks::table
+            .filter(telegram_bookmarks::chat_id.eq(chat_id))
+            .filter(telegram_bookmarks::id.eq(id)),
+    )
+    .execute(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewTelegramBookmark;
+    use crate::db;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    #[test]
+    fn create_skips_saved_links() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let chat_id = create_chat(connection);
+
+            assert!(
+                super::create(connection, new_bookmark(chat_id, "1"))
+                    .unwrap()
+                    .is_some()
+            );
+            assert!(
+                super::create(connection, new_bookmark(chat_id, "1"))
+                    .unwrap()
+                    .is_none()
+            );
+            assert_eq!(1, super::count(connection, chat_id).unwrap());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn find_page_paginates_bookmarks() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let chat_id = create_chat(connection);
+
+            for number in 1..=3 {
+                super::create(connection, new_bookmark(chat_id, &number.to_string())).unwrap();
+            }
+
+            let first_page = super::find_page(connection, chat_id, 1, 2).unwrap();
+            let second_page = super::find_page(connection, chat_id, 2, 2).unwrap();
+
+            assert_eq!(vec!["Item 3", "Item 2"], titles(&first_page));
+            assert_eq!(vec!["Item 1"], titles(&second_page));
+
+            assert_eq!(
+                1,
+                super::delete(connection, chat_id, second_page[0].id).unwrap()
+            );
+            assert_eq!(2, super::count(connection, chat_id).unwrap());
+
+            Ok(())
+        });
+    }
+
+    fn titles(bookmarks: &[crate::models::TelegramBookmark]) -> Vec<&str> {
+        bookmarks
+            .iter()
+            .map(|bookmark| bookmark.title.as_str())
+            .collect()
+    }
+
+    fn create_chat(connection: &mut diesel::PgConnection) -> i64 {
+        telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 9301,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap()
+        .id
+    }
+
+    fn new_bookmark(chat_id: i64, number: &str) -> NewTelegramBookmark {
+        NewTelegramBookmark {
+            chat_id,
+            feed_link: "https://example.com/feed.xml".to_string(),
+            title: format!("Item {number}"),
+            link: format!("https://example.com/{number}"),
+            description: None,
+            author: None,
+            publication_date: db::current_time(),
+        }
+    }
+}
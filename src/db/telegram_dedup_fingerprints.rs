@@ -0,0 +1,143 @@
+use crate::db;
+use crate::schema::telegram_dedup_fingerprints;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::upsert::excluded;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+#[derive(Insertable)]
+#[diesel(table_name = telegram_dedup_fingerprints)]
+struct NewTelegramDedupFingerprint {
+    chat_id: i64,
+    fingerprint: String,
+    delivered_at: DateTime<Utc>,
+}
+
+// Returns fingerprints that were delivered to the chat after `since`
+pub fn find_delivered(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    fingerprints: &[String],
+    since: DateTime<Utc>,
+) -> Result<Vec<String>, Error> {
+    telegram_dedup_fingerprints::table
+        .filter(telegram_dedup_fingerprints::chat_id.eq(chat_id))
+        .filter(telegram_dedup_fingerprints::fingerprint.eq_any(fingerprints))
+        .filter(telegram_dedup_fingerprints::delivered_at.ge(since))
+        .select(telegram_dedup_fingerprints::fingerprint)
+        .load::<String>(conn)
+}
+
+// Fingerprints that were already saved get a new delivery time
+pub fn save(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    fingerprints: Vec<String>,
+) -> Result<usize, Error> {
+    let now = db::current_time();
+
+    let values = fingerprints
+        .into_iter()
+        .map(|fingerprint| NewTelegramDedupFingerprint {
+            chat_id,
+            fingerprint,
+            delivered_at: now,
+        })
+        .collect::<Vec<NewTelegramDedupFingerprint>>();
+
+    diesel::insert_into(telegram_dedup_fingerprints::table)
+        .values(values)
+        .on_conflict((
+            telegram_dedup_fingerprints::chat_id,
+            telegram_dedup_fingerprints::fingerprint,
+        ))
+        .do_update()
+        .set(
+            telegram_dedup_fingerprints::delivered_at
+                .eq(excluded(telegram_dedup_fingerprints::delivered_at)),
+        )
+        .execute(conn)
+}
+
+pub fn delete_for_chat(conn: &mut PgConnection, chat_id: i64) -> Result<usize, Error> {
+    diesel::delete(
+        telegram_dedup_fingerprints::table.filter(telegram_dedup_fingerprints::chat_id.eq(chat_id)),
+    )
+    .execute(conn)
+}
+
+pub fn delete_expired(conn: &mut PgConnection, before: DateTime<Utc>) -> Result<usize, Error> {
+    diesel::delete(
+        telegram_dedup_fingerprints::table
+            .filter(telegram_dedup_fingerprints::delivered_at.lt(before)),
+    )
+    .execute(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use chrono::Duration;
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    #[test]
+    fn find_delivered_skips_expired_fingerprints() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let chat = telegram::create_chat(
+                connection,
+                NewTelegramChat {
+                    id: 9301,
+                    kind: "private".to_string(),
+                    username: None,
+                    first_name: None,
+                    last_name: None,
+                    title: None,
+                },
+            )
+            .unwrap();
+            let fingerprints = vec!["link:1".to_string(), "title:1".to_string()];
+
+            assert_eq!(
+                2,
+                super::save(connection, chat.id, fingerprints.clone()).unwrap()
+            );
+            assert_eq!(
+                1,
+                super::save(connection, chat.id, vec!["link:1".to_string()]).unwrap()
+            );
+
+            let now = db::current_time();
+            let delivered = super::find_delivered(
+                connection,
+                chat.id,
+                &["link:1".to_string(), "guid:1".to_string()],
+                now - Duration::days(1),
+            )
+            .unwrap();
+
+            assert_eq!(vec!["link:1".to_string()], delivered);
+
+            assert_eq!(
+                0,
+                super::delete_expired(connection, now - Duration::days(1)).unwrap()
+            );
+            assert_eq!(
+                2,
+                super::delete_expired(connection, now + Duration::days(1)).unwrap()
+            );
+            assert!(
+                super::find_delivered(connection, chat.id, &fingerprints, now - Duration::days(1))
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+    }
+}
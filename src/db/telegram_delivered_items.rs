@@ -0,0 +1,60 @@
+use crate::models::FeedItem;
+use crate::models::TelegramDeliveredItem;
+use crate::schema::telegram_delivered_items;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = telegram_delivered_items)]
+pub struct NewTelegramDeliveredItem {
+    pub chat_id: i64,
+    pub telegram_message_id: i32,
+    pub feed_id: i64,
+    pub title: String,
+    pub link: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub publication_date: DateTime<Utc>,
+}
+
+// A copy of the item is saved because feed items and outbox messages are removed by the cleaner
+pub fn create(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    telegram_message_id: i32,
+    item: FeedItem,
+) -> Result<usize, Error> {
+    let delivered_item = NewTelegramDeliveredItem {
+        chat_id,
+        telegram_message_id,
+        feed_id: item.feed_id,
+        title: item.title,
+        link: item.link,
+        description: item.description,
+        author: item.author,
+        publication_date: item.publication_date,
+    };
+
+    diesel::insert_into(telegram_delivered_items::table)
+        .values(delivered_item)
+        .on_conflict((
+            telegram_delivered_items::chat_id,
+            telegram_delivered_items::telegram_message_id,
+        ))
+        .do_nothing()
+        .execute(conn)
+}
+
+pub fn find(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    telegram_message_id: i32,
+) -> Result<Option<TelegramDeliveredItem>, Error> {
+    telegram_delivered_items::table
+        .filter(telegram_delivered_items::chat_id.eq(chat_id))
+        .filter(telegram_delivered_items::telegram_message_id.eq(telegram_message_id))
+        .first::<TelegramDeliveredItem>(conn)
+        .optional()
+}
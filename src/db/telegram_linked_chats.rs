@@ -0,0 +1,169 @@
+use crate::db;
+use crate::models::TelegramChat;
+use crate::schema::{telegram_chats, telegram_linked_chats};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = telegram_linked_chats)]
+pub struct NewTelegramLinkedChat {
+    pub chat_id: i64,
+    pub linked_chat_id: i64,
+}
+
+// Returns 0 if the chat is already linked
+pub fn create(conn: &mut PgConnection, linked_chat: NewTelegramLinkedChat) -> Result<usize, Error> {
+    diesel::insert_into(telegram_linked_chats::table)
+        .values(linked_chat)
+        .on_conflict((
+            telegram_linked_chats::chat_id,
+            telegram_linked_chats::linked_chat_id,
+        ))
+        .do_nothing()
+        .execute(conn)
+}
+
+pub fn exists(conn: &mut PgConnection, chat_id: i64, linked_chat_id: i64) -> Result<bool, Error> {
+    diesel::select(diesel::dsl::exists(
+        telegram_linked_chats::table
+            .filter(telegram_linked_chats::chat_id.eq(chat_id))
+            .filter(telegram_linked_chats::linked_chat_id.eq(linked_chat_id)),
+    ))
+    .get_result::<bool>(conn)
+}
+
+pub fn find_admin_checked_at(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    linked_chat_id: i64,
+) -> Result<Option<DateTime<Utc>>, Error> {
+    telegram_linked_chats::table
+        .filter(telegram_linked_chats::chat_id.eq(chat_id))
+        .filter(telegram_linked_chats::linked_chat_id.eq(linked_chat_id))
+        .select(telegram_linked_chats::admin_checked_at)
+        .first::<DateTime<Utc>>(conn)
+        .optional()
+}
+
+pub fn mark_admin_checked(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    linked_chat_id: i64,
+) -> Result<usize, Error> {
+    diesel::update(
+        telegram_linked_chats::table
+            .filter(telegram_linked_chats::chat_id.eq(chat_id))
+            .filter(telegram_linked_chats::linked_chat_id.eq(linked_chat_id)),
+    )
+    .set(telegram_linked_chats::admin_checked_at.eq(db::current_time()))
+    .execute(conn)
+}
+
+pub fn find_chats(conn: &mut PgConnection, chat_id: i64) -> Result<Vec<TelegramChat>, Error> {
+    let linked_chat_ids = telegram_linked_chats::table
+        .filter(telegram_linked_chats::chat_id.eq(chat_id))
+        .select(telegram_linked_chats::linked_chat_id);
+
+    telegram_chats::table
+        .filter(telegram_chats::id.eq_any(linked_chat_ids))
+        .order(telegram_chats::id.asc())
+        .load::<TelegramChat>(conn)
+}
+
+pub fn delete(conn: &mut PgConnection, chat_id: i64, linked_chat_id: i64) -> Result<usize, Error> {
+    diesel::delete(
+        telegram_linked_chats::table
+            .filter(telegram_linked_chats::chat_id.eq(chat_id))
+            .filter(telegram_linked_chats::linked_chat_id.eq(linked_chat_id)),
+    )
+    .execute(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewTelegramLinkedChat;
+    use crate::db;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    fn create_chat(connection: &mut diesel::PgConnection, id: i64, kind: &str) {
+        telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id,
+                kind: kind.to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: Some(format!("Chat {id}")),
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn create_links_chat_once() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            create_chat(connection, 9301, "private");
+            create_chat(connection, -9302, "channel");
+
+            let linked_chat = NewTelegramLinkedChat {
+                chat_id: 9301,
+                linked_chat_id: -9302,
+            };
+
+            assert_eq!(1, super::create(connection, linked_chat.clone()).unwrap());
+            assert_eq!(0, super::create(connection, linked_chat).unwrap());
+            assert!(super::exists(connection, 9301, -9302).unwrap());
+            assert!(!super::exists(connection, -9302, 9301).unwrap());
+
+            let chats = super::find_chats(connection, 9301).unwrap();
+
+            assert_eq!(1, chats.len());
+            assert_eq!(Some("Chat -9302".to_string()), chats[0].title);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn migrate_chat_moves_links_to_the_new_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            create_chat(connection, 9303, "private");
+            create_chat(connection, -9304, "group");
+
+            super::create(
+                connection,
+                NewTelegramLinkedChat {
+                    chat_id: 9303,
+                    linked_chat_id: -9304,
+                },
+            )
+            .unwrap();
+
+            let chat = telegram::find_chat(connection, 9303).unwrap();
+            telegram::set_selected_chat(connection, &chat, Some(-9304)).unwrap();
+
+            telegram::migrate_chat(connection, -9304, -1009304)
+                .unwrap()
+                .unwrap();
+
+            assert!(super::exists(connection, 9303, -1009304).unwrap());
+            assert!(!super::exists(connection, 9303, -9304).unwrap());
+
+            let chat = telegram::find_chat(connection, 9303).unwrap();
+
+            assert_eq!(Some(-1009304), chat.selected_chat_id);
+
+            Ok(())
+        });
+    }
+}
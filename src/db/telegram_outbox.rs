@@ -0,0 +1,385 @@
+use crate::db;
+use crate::models::TelegramOutboxMessage;
+use crate::models::TelegramSubscription;
+use crate::schema::telegram_outbox;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+pub const PENDING: &str = "pending";
+pub const SENT: &str = "sent";
+pub const FAILED: &str = "failed";
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = telegram_outbox)]
+pub struct NewTelegramOutboxMessage {
+    pub chat_id: i64,
+    pub feed_id: i64,
+    pub idempotency_key: String,
+    pub message: String,
+    pub thread_id: Option<i32>,
+    pub preview_enabled: bool,
+    pub parse_mode: String,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+// Messages with the same idempotency key are enqueued only once per subscription
+pub fn enqueue(
+    conn: &mut PgConnection,
+    messages: Vec<NewTelegramOutboxMessage>,
+) -> Result<usize, Error> {
+    diesel::insert_into(telegram_outbox::table)
+        .values(messages)
+        .on_conflict((
+            telegram_outbox::chat_id,
+            telegram_outbox::feed_id,
+            telegram_outbox::idempotency_key,
+        ))
+        .do_nothing()
+        .execute(conn)
+}
+
+// Messages are sent one by one in the order they were enqueued
+pub fn next_pending(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+) -> Result<Option<TelegramOutboxMessage>, Error> {
+    telegram_outbox::table
+        .filter(telegram_outbox::chat_id.eq(subscription.chat_id))
+        .filter(telegram_outbox::feed_id.eq(subscription.feed_id))
+        .filter(telegram_outbox::status.eq(PENDING))
+        .order(telegram_outbox::id.asc())
+        .first::<TelegramOutboxMessage>(conn)
+        .optional()
+}
+
+// Messages of all subscriptions of the chat are sent in publication order.
+// Messages enqueued before publication dates were saved go first
+pub fn next_pending_for_chat(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    feed_ids: &[i64],
+) -> Result<Option<TelegramOutboxMessage>, Error> {
+    telegram_outbox::table
+        .filter(telegram_outbox::chat_id.eq(chat_id))
+        .filter(telegram_outbox::feed_id.eq_any(feed_ids))
+        .filter(telegram_outbox::status.eq(PENDING))
+        .order((
+            telegram_outbox::published_at.asc().nulls_first(),
+            telegram_outbox::id.asc(),
+        ))
+        .first::<TelegramOutboxMessage>(conn)
+        .optional()
+}
+
+// The message is leased until `lease_until` so only one sender can send it.
+// If the sender crashes, the message is sent again after the lease expires
+pub fn claim(
+    conn: &mut PgConnection,
+    message: &TelegramOutboxMessage,
+    lease_until: DateTime<Utc>,
+) -> Result<Option<TelegramOutboxMessage>, Error> {
+    diesel::update(
+        telegram_outbox::table
+            .filter(telegram_outbox::id.eq(message.id))
+            .filter(telegram_outbox::status.eq(PENDING))
+            .filter(telegram_outbox::next_attempt_at.le(Utc::now())),
+    )
+    .set((
+        telegram_outbox::next_attempt_at.eq(lease_until),
+        telegram_outbox::updated_at.eq(db::current_time()),
+    ))
+    .get_result::<TelegramOutboxMessage>(conn)
+    .optional()
+}
+
+pub fn mark_sent(
+    conn: &mut PgConnection,
+    message: &TelegramOutboxMessage,
+    telegram_message_id: i32,
+) -> Result<TelegramOutboxMessage, Error> {
+    let now = db::current_time();
+
+    diesel::update(message)
+        .set((
+            telegram_outbox::status.eq(SENT),
+            telegram_outbox::telegram_message_id.eq(telegram_message_id),
+            telegram_outbox::sent_at.eq(now),
+            telegram_outbox::updated_at.eq(now),
+        ))
+        .get_result::<TelegramOutboxMessage>(conn)
+}
+
+// Used when telegram asks to slow down. It doesn't count as a failed attempt
+pub fn postpone(
+    conn: &mut PgConnection,
+    message: &TelegramOutboxMessage,
+    until: DateTime<Utc>,
+) -> Result<TelegramOutboxMessage, Error> {
+    diesel::update(message)
+        .set((
+            telegram_outbox::next_attempt_at.eq(until),
+            telegram_outbox::updated_at.eq(db::current_time()),
+        ))
+        .get_result::<TelegramOutboxMessage>(conn)
+}
+
+pub fn set_message(
+    conn: &mut PgConnection,
+    message: &TelegramOutboxMessage,
+    text: String,
+) -> Result<TelegramOutboxMessage, Error> {
+    diesel::update(message)
+        .set((
+            telegram_outbox::message.eq(text),
+            telegram_outbox::updated_at.eq(db::current_time()),
+        ))
+        .get_result::<TelegramOutboxMessage>(conn)
+}
+
+// The message is marked as failed after `max_attempts` so it doesn't block the queue forever
+pub fn fail_attempt(
+    conn: &mut PgConnection,
+    message: &TelegramOutboxMessage,
+    error: &str,
+    next_attempt_at: DateTime<Utc>,
+    max_attempts: i32,
+) -> Result<TelegramOutboxMessage, Error> {
+    let attempts = message.attempts + 1;
+    let status = if attempts >= max_attempts {
+        FAILED
+    } else {
+        PENDING
+    };
+
+    diesel::update(message)
+        .set((
+            telegram_outbox::status.eq(status),
+            telegram_outbox::attempts.eq(attempts),
+            telegram_outbox::last_error.eq(error),
+            telegram_outbox::next_attempt_at.eq(next_attempt_at),
+            telegram_outbox::updated_at.eq(db::current_time()),
+        ))
+        .get_result::<TelegramOutboxMessage>(conn)
+}
+
+pub fn delete_pending(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+) -> Result<usize, Error> {
+    diesel::delete(
+        telegram_outbox::table
+            .filter(telegram_outbox::chat_id.eq(subscription.chat_id))
+            .filter(telegram_outbox::feed_id.eq(subscription.feed_id))
+            .filter(telegram_outbox::status.eq(PENDING)),
+    )
+    .execute(conn)
+}
+
+// Pending messages follow the subscription when it's moved to another topic
+pub fn set_pending_thread_id(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    thread_id: Option<i32>,
+) -> Result<usize, Error> {
+    diesel::update(
+        telegram_outbox::table
+            .filter(telegram_outbox::chat_id.eq(subscription.chat_id))
+            .filter(telegram_outbox::feed_id.eq(subscription.feed_id))
+            .filter(telegram_outbox::status.eq(PENDING)),
+    )
+    .set((
+        telegram_outbox::thread_id.eq(thread_id),
+        telegram_outbox::updated_at.eq(db::current_time()),
+    ))
+    .execute(conn)
+}
+
+pub fn delete_processed(conn: &mut PgConnection, before: DateTime<Utc>) -> Result<usize, Error> {
+    diesel::delete(
+        telegram_outbox::table
+            .filter(telegram_outbox::status.ne(PENDING))
+            .filter(telegram_outbox::updated_at.lt(before)),
+    )
+    .execute(conn)
+}
+
+// Parts of split messages and service messages have `:` in their keys so only items are counted
+pub fn count_items_since(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+    since: DateTime<Utc>,
+) -> Result<i64, Error> {
+    telegram_outbox::table
+        .filter(telegram_outbox::chat_id.eq(subscription.chat_id))
+        .filter(telegram_outbox::feed_id.eq(subscription.feed_id))
+        .filter(telegram_outbox::created_at.ge(since))
+        .filter(telegram_outbox::idempotency_key.not_like("%:%"))
+        .count()
+        .get_result::<i64>(conn)
+}
+
+pub fn find_for_subscription(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+) -> Result<Vec<TelegramOutboxMessage>, Error> {
+    telegram_outbox::table
+        .filter(telegram_outbox::chat_id.eq(subscription.chat_id))
+        .filter(telegram_outbox::feed_id.eq(subscription.feed_id))
+        .order(telegram_outbox::id.asc())
+        .load::<TelegramOutboxMessage>(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewTelegramOutboxMessage;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use chrono::Duration;
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    #[test]
+    fn enqueue_skips_messages_with_the_same_idempotency_key() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let subscription = create_subscription(connection);
+
+            let messages = vec![
+                new_message(&subscription, "1"),
+                new_message(&subscription, "2"),
+            ];
+
+            assert_eq!(2, super::enqueue(connection, messages.clone()).unwrap());
+            assert_eq!(0, super::enqueue(connection, messages).unwrap());
+
+            let next = super::next_pending(connection, &subscription)
+                .unwrap()
+                .unwrap();
+
+            assert_eq!("1", next.idempotency_key);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn claim_leases_message_to_one_sender() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let subscription = create_subscription(connection);
+
+            super::enqueue(connection, vec![new_message(&subscription, "1")]).unwrap();
+
+            let message = super::next_pending(connection, &subscription)
+                .unwrap()
+                .unwrap();
+            let lease_until = db::current_time() + Duration::seconds(60);
+
+            let claimed = super::claim(connection, &message, lease_until)
+                .unwrap()
+                .unwrap();
+
+            assert_eq!(lease_until, claimed.next_attempt_at);
+            assert!(
+                super::claim(connection, &message, lease_until)
+                    .unwrap()
+                    .is_none()
+            );
+
+            let sent = super::mark_sent(connection, &claimed, 42).unwrap();
+
+            assert_eq!(super::SENT, sent.status);
+            assert_eq!(Some(42), sent.telegram_message_id);
+            assert!(
+                super::next_pending(connection, &subscription)
+                    .unwrap()
+                    .is_none()
+            );
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn fail_attempt_marks_message_as_failed_after_max_attempts() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let subscription = create_subscription(connection);
+
+            super::enqueue(connection, vec![new_message(&subscription, "1")]).unwrap();
+
+            let message = super::next_pending(connection, &subscription)
+                .unwrap()
+                .unwrap();
+            let now = db::current_time();
+
+            let message = super::fail_attempt(connection, &message, "error", now, 2).unwrap();
+
+            assert_eq!(super::PENDING, message.status);
+            assert_eq!(1, message.attempts);
+            assert_eq!(Some("error".to_string()), message.last_error);
+
+            let message = super::fail_attempt(connection, &message, "error", now, 2).unwrap();
+
+            assert_eq!(super::FAILED, message.status);
+            assert!(
+                super::next_pending(connection, &subscription)
+                    .unwrap()
+                    .is_none()
+            );
+
+            Ok(())
+        });
+    }
+
+    fn create_subscription(
+        connection: &mut diesel::PgConnection,
+    ) -> crate::models::TelegramSubscription {
+        let feed = feeds::create(connection, "Link", "rss".to_string()).unwrap();
+        let chat = telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 9101,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap();
+
+        telegram::create_subscription(
+            connection,
+            NewTelegramSubscription::builder()
+                .chat_id(chat.id)
+                .feed_id(feed.id)
+                .build(),
+        )
+        .unwrap()
+    }
+
+    fn new_message(
+        subscription: &crate::models::TelegramSubscription,
+        key: &str,
+    ) -> NewTelegramOutboxMessage {
+        NewTelegramOutboxMessage {
+            chat_id: subscription.chat_id,
+            feed_id: subscription.feed_id,
+            idempotency_key: key.to_string(),
+            message: format!("Message {key}"),
+            thread_id: None,
+            preview_enabled: false,
+            parse_mode: "HTML".to_string(),
+            published_at: None,
+        }
+    }
+}
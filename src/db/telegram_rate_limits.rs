@@ -0,0 +1,222 @@
+use crate::schema::telegram_rate_limits;
+use chrono::{DateTime, Duration, SubsecRound, Utc};
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::upsert::excluded;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+// Telegram doesn't use 0 as a chat id so it's used for the limit shared by all chats
+pub const GLOBAL_CHAT_ID: i64 = 0;
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum SendSlot {
+    Ready(DateTime<Utc>),
+    Wait(DateTime<Utc>),
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = telegram_rate_limits)]
+struct NewTelegramRateLimit {
+    chat_id: i64,
+    next_send_at: DateTime<Utc>,
+}
+
+// Reserves the earliest time when a message can be sent to the chat
+// without exceeding both the chat limit and the global limit.
+// Nothing is reserved if the chat has to wait longer than `max_wait`.
+// Rows are always locked in the same order (chat, then global) to avoid deadlocks
+pub fn reserve_slot(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    chat_interval: Duration,
+    global_interval: Duration,
+    max_wait: Duration,
+) -> Result<SendSlot, Error> {
+    conn.transaction::<SendSlot, Error, _>(|conn| {
+        // postgres stores microseconds
+        let now = Utc::now().trunc_subsecs(6);
+
+        let chat_next_send_at = find_or_create_for_update(conn, chat_id, now)?;
+        let global_next_send_at = find_or_create_for_update(conn, GLOBAL_CHAT_ID, now)?;
+
+        let slot = now.max(chat_next_send_at).max(global_next_send_at);
+
+        if slot - now > max_wait {
+            return Ok(SendSlot::Wait(slot));
+        }
+
+        set_next_send_at(conn, chat_id, slot + chat_interval, now)?;
+        set_next_send_at(conn, GLOBAL_CHAT_ID, slot + global_interval, now)?;
+
+        Ok(SendSlot::Ready(slot))
+    })
+}
+
+// Used when telegram responds with `retry_after`. The delay is never shortened
+pub fn delay(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    until: DateTime<Utc>,
+) -> Result<DateTime<Utc>, Error> {
+    diesel::insert_into(telegram_rate_limits::table)
+        .values(NewTelegramRateLimit {
+            chat_id,
+            next_send_at: until,
+        })
+        .on_conflict(telegram_rate_limits::chat_id)
+        .do_update()
+        .set((
+            telegram_rate_limits::next_send_at.eq(
+                diesel::dsl::sql::<diesel::sql_types::Timestamptz>(
+                    "GREATEST(telegram_rate_limits.next_send_at, excluded.next_send_at)",
+                ),
+            ),
+            telegram_rate_limits::updated_at.eq(excluded(telegram_rate_limits::updated_at)),
+        ))
+        .returning(telegram_rate_limits::next_send_at)
+        .get_result::<DateTime<Utc>>(conn)
+}
+
+pub fn delete_expired(conn: &mut PgConnection, before: DateTime<Utc>) -> Result<usize, Error> {
+    diesel::delete(
+        telegram_rate_limits::table.filter(telegram_rate_limits::next_send_at.lt(before)),
+    )
+    .execute(conn)
+}
+
+pub fn find(conn: &mut PgConnection, chat_id: i64) -> Option<DateTime<Utc>> {
+    telegram_rate_limits::table
+        .filter(telegram_rate_limits::chat_id.eq(chat_id))
+        .select(telegram_rate_limits::next_send_at)
+        .first::<DateTime<Utc>>(conn)
+        .ok()
+}
+
+fn set_next_send_at(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    next_send_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<usize, Error> {
+    diesel::update(telegram_rate_limits::table.filter(telegram_rate_limits::chat_id.eq(chat_id)))
+        .set((
+            telegram_rate_limits::next_send_at.eq(next_send_at),
+            telegram_rate_limits::updated_at.eq(now),
+        ))
+        .execute(conn)
+}
+
+fn find_or_create_for_update(
+    conn: &mut PgConnection,
+    chat_id: i64,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, Error> {
+    diesel::insert_into(telegram_rate_limits::table)
+        .values(NewTelegramRateLimit {
+            chat_id,
+            next_send_at: now,
+        })
+        .on_conflict_do_nothing()
+        .execute(conn)?;
+
+    telegram_rate_limits::table
+        .filter(telegram_rate_limits::chat_id.eq(chat_id))
+        .select(telegram_rate_limits::next_send_at)
+        .for_update()
+        .first::<DateTime<Utc>>(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SendSlot;
+    use crate::db;
+    use chrono::{Duration, Utc};
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    #[test]
+    fn reserve_slot_spaces_messages_to_the_same_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let chat_id = -1001;
+            let chat_interval = Duration::seconds(3);
+            let global_interval = Duration::milliseconds(10);
+            let max_wait = Duration::seconds(5);
+
+            let first = super::reserve_slot(
+                connection,
+                chat_id,
+                chat_interval,
+                global_interval,
+                max_wait,
+            )
+            .unwrap();
+
+            let first_slot = match first {
+                SendSlot::Ready(slot) => slot,
+                SendSlot::Wait(_) => panic!("the first message should not wait"),
+            };
+
+            let second = super::reserve_slot(
+                connection,
+                chat_id,
+                chat_interval,
+                global_interval,
+                max_wait,
+            )
+            .unwrap();
+
+            assert_eq!(SendSlot::Ready(first_slot + chat_interval), second);
+
+            let third = super::reserve_slot(
+                connection,
+                chat_id,
+                chat_interval,
+                global_interval,
+                max_wait,
+            )
+            .unwrap();
+
+            assert_eq!(SendSlot::Wait(first_slot + chat_interval * 2), third);
+
+            let other_chat =
+                super::reserve_slot(connection, 1002, chat_interval, global_interval, max_wait)
+                    .unwrap();
+
+            assert!(matches!(other_chat, SendSlot::Ready(_)));
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn delay_postpones_the_chat() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<_, Error, _>(|connection| {
+            let chat_id = 1003;
+            let until = Utc::now() + Duration::seconds(30);
+
+            super::delay(connection, chat_id, until).unwrap();
+
+            let next_send_at =
+                super::delay(connection, chat_id, until - Duration::seconds(10)).unwrap();
+
+            assert_eq!(until.timestamp_micros(), next_send_at.timestamp_micros());
+
+            let slot = super::reserve_slot(
+                connection,
+                chat_id,
+                Duration::seconds(1),
+                Duration::zero(),
+                Duration::seconds(5),
+            )
+            .unwrap();
+
+            assert_eq!(SendSlot::Wait(next_send_at), slot);
+
+            Ok(())
+        });
+    }
+}
@@ -0,0 +1,108 @@
+use crate::models::TelegramRewriteRule;
+use crate::models::TelegramSubscription;
+use crate::schema::telegram_rewrite_rules;
+use diesel::prelude::*;
+use diesel::result::Error;
+use diesel::{ExpressionMethods, PgConnection, QueryDsl, RunQueryDsl};
+
+#[derive(Insertable, Clone, Debug)]
+#[diesel(table_name = telegram_rewrite_rules)]
+pub struct NewTelegramRewriteRule {
+    pub chat_id: i64,
+    pub feed_id: i64,
+    pub field: String,
+    pub pattern: String,
+    pub replacement: String,
+}
+
+pub fn create(
+    conn: &mut PgConnection,
+    rule: NewTelegramRewriteRule,
+) -> Result<TelegramRewriteRule, Error> {
+    diesel::insert_into(telegram_rewrite_rules::table)
+        .values(rule)
+        .get_result::<TelegramRewriteRule>(conn)
+}
+
+// Rules are applied in the order they were added
+pub fn find_for_subscription(
+    conn: &mut PgConnection,
+    subscription: &TelegramSubscription,
+) -> Result<Vec<TelegramRewriteRule>, Error> {
+    telegram_rewrite_rules::table
+        .filter(telegram_rewrite_rules::chat_id.eq(subscription.chat_id))
+        .filter(telegram_rewrite_rules::feed_id.eq(subscription.feed_id))
+        .order(telegram_rewrite_rules::id.asc())
+        .load::<TelegramRewriteRule>(conn)
+}
+
+pub fn delete(conn: &mut PgConnection, rule: &TelegramRewriteRule) -> Result<usize, Error> {
+    diesel::delete(rule).execute(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewTelegramRewriteRule;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::NewTelegramChat;
+    use crate::db::telegram::NewTelegramSubscription;
+    use diesel::connection::Connection;
+    use diesel::result::Error;
+
+    #[test]
+    fn rules_are_removed_with_the_subscription() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let feed = feeds::create(connection, "Link", "rss".to_string()).unwrap();
+            let chat = telegram::create_chat(
+                connection,
+                NewTelegramChat {
+                    id: 9401,
+                    kind: "private".to_string(),
+                    username: None,
+                    first_name: None,
+                    last_name: None,
+                    title: None,
+                },
+            )
+            .unwrap();
+            let new_subscription = NewTelegramSubscription::builder()
+                .chat_id(chat.id)
+                .feed_id(feed.id)
+                .build();
+            let subscription = telegram::create_subscription(connection, new_subscription).unwrap();
+
+            for pattern in ["^\\[Sponsored\\] ", "The post .* appeared first on .*$"] {
+                super::create(
+                    connection,
+                    NewTelegramRewriteRule {
+                        chat_id: subscription.chat_id,
+                        feed_id: subscription.feed_id,
+                        field: "title".to_string(),
+                        pattern: pattern.to_string(),
+                        replacement: "".to_string(),
+                    },
+                )
+                .unwrap();
+            }
+
+            let rules = super::find_for_subscription(connection, &subscription).unwrap();
+
+            assert_eq!(2, rules.len());
+            assert_eq!("^\\[Sponsored\\] ", rules[0].pattern);
+
+            telegram::remove_subscription(connection, new_subscription).unwrap();
+
+            assert!(
+                super::find_for_subscription(connection, &subscription)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+    }
+}
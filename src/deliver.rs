@@ -1,8 +1,17 @@
 const JOB_TYPE: &str = "deliver";
 
+pub mod date_format;
+pub mod dedup;
 pub mod deliver_chat_updates_job;
 pub mod deliver_job;
+pub mod links;
+pub mod markdown;
+pub mod quiet_hours;
 pub mod render_message;
+pub mod rewrite;
+pub mod split_message;
+pub mod telegram_html;
+pub mod timezone;
 
 pub use deliver_chat_updates_job::DeliverChatUpdatesJob;
 pub use deliver_job::DeliverJob;
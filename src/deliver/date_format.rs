@@ -0,0 +1,573 @@
+// Dates in templates are formatted with strftime patterns. Month and weekday names
+// are translated here because chrono only prints English names
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::FixedOffset;
+use chrono::Utc;
+use chrono::format::StrftimeItems;
+
+pub const MAX_DATE_FORMAT_LENGTH: usize = 100;
+
+struct Names {
+    language: &'static str,
+    months: [&'static str; 12],
+    // months after a day number, for example "13 января"
+    genitive_months: Option<[&'static str; 12]>,
+    short_months: [&'static str; 12],
+    // weekdays start from Monday
+    weekdays: [&'static str; 7],
+    short_weekdays: [&'static str; 7],
+}
+
+const NAMES: [Names; 8] = [
+    Names {
+        language: "en",
+        months: [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        genitive_months: None,
+        short_months: [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+        weekdays: [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+        short_weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+    },
+    Names {
+        language: "de",
+        months: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        genitive_months: None,
+        short_months: [
+            "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+        ],
+        weekdays: [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+        short_weekdays: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+    },
+    Names {
+        language: "es",
+        months: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        genitive_months: None,
+        short_months: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+        ],
+        weekdays: [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        short_weekdays: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+    },
+    Names {
+        language: "fr",
+        months: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        genitive_months: None,
+        short_months: [
+            "janv", "févr", "mars", "avr", "mai", "juin", "juil", "août", "sept", "oct", "nov",
+            "déc",
+        ],
+        weekdays: [
+            "lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche",
+        ],
+        short_weekdays: ["lun", "mar", "mer", "jeu", "ven", "sam", "dim"],
+    },
+    Names {
+        language: "it",
+        months: [
+            "gennaio",
+            "febbraio",
+            "marzo",
+            "aprile",
+            "maggio",
+            "giugno",
+            "luglio",
+            "agosto",
+            "settembre",
+            "ottobre",
+            "novembre",
+            "dicembre",
+        ],
+        genitive_months: None,
+        short_months: [
+            "gen", "feb", "mar", "apr", "mag", "giu", "lug", "ago", "set", "ott", "nov", "dic",
+        ],
+        weekdays: [
+            "lunedì",
+            "martedì",
+            "mercoledì",
+            "giovedì",
+            "venerdì",
+            "sabato",
+            "domenica",
+        ],
+        short_weekdays: ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+    },
+    Names {
+        language: "pt",
+        months: [
+            "janeiro",
+            "fevereiro",
+            "março",
+            "abril",
+            "maio",
+            "junho",
+            "julho",
+            "agosto",
+            "setembro",
+            "outubro",
+            "novembro",
+            "dezembro",
+        ],
+        genitive_months: None,
+        short_months: [
+            "jan", "fev", "mar", "abr", "mai", "jun", "jul", "ago", "set", "out", "nov", "dez",
+        ],
+        weekdays: [
+            "segunda-feira",
+            "terça-feira",
+            "quarta-feira",
+            "quinta-feira",
+            "sexta-feira",
+            "sábado",
+            "domingo",
+        ],
+        short_weekdays: ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+    },
+    Names {
+        language: "ru",
+        months: [
+            "январь",
+            "февраль",
+            "март",
+            "апрель",
+            "май",
+            "июнь",
+            "июль",
+            "август",
+            "сентябрь",
+            "октябрь",
+            "ноябрь",
+            "декабрь",
+        ],
+        genitive_months: Some([
+            "января",
+            "февраля",
+            "марта",
+            "апреля",
+            "мая",
+            "июня",
+            "июля",
+            "августа",
+            "сентября",
+            "октября",
+            "ноября",
+            "декабря",
+        ]),
+        short_months: [
+            "янв", "фев", "мар", "апр", "мая", "июн", "июл", "авг", "сен", "окт", "ноя", "дек",
+        ],
+        weekdays: [
+            "понедельник",
+            "вторник",
+            "среда",
+            "четверг",
+            "пятница",
+            "суббота",
+            "воскресенье",
+        ],
+        short_weekdays: ["пн", "вт", "ср", "чт", "пт", "сб", "вс"],
+    },
+    Names {
+        language: "uk",
+        months: [
+            "січень",
+            "лютий",
+            "березень",
+            "квітень",
+            "травень",
+            "червень",
+            "липень",
+            "серпень",
+            "вересень",
+            "жовтень",
+            "листопад",
+            "грудень",
+        ],
+        genitive_months: Some([
+            "січня",
+            "лютого",
+            "березня",
+            "квітня",
+            "травня",
+            "червня",
+            "липня",
+            "серпня",
+            "вересня",
+            "жовтня",
+            "листопада",
+            "грудня",
+        ]),
+        short_months: [
+            "січ", "лют", "бер", "кві", "тра", "чер", "лип", "сер", "вер", "жов", "лис", "гру",
+        ],
+        weekdays: [
+            "понеділок",
+            "вівторок",
+            "середа",
+            "четвер",
+            "пʼятниця",
+            "субота",
+            "неділя",
+        ],
+        short_weekdays: ["пн", "вт", "ср", "чт", "пт", "сб", "нд"],
+    },
+];
+
+pub fn languages() -> Vec<&'static str> {
+    NAMES.iter().map(|names| names.language).collect()
+}
+
+pub fn is_supported_language(language: &str) -> bool {
+    NAMES.iter().any(|names| names.language == language)
+}
+
+pub fn validate(pattern: &str) -> Result<(), String> {
+    if pattern.trim().is_empty() {
+        return Err("The date format can not be empty".to_string());
+    }
+
+    if pattern.chars().count() > MAX_DATE_FORMAT_LENGTH {
+        return Err(format!(
+            "The date format can not be longer than {MAX_DATE_FORMAT_LENGTH} characters"
+        ));
+    }
+
+    match StrftimeItems::new(pattern).parse() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("{pattern} is not a valid strftime date format")),
+    }
+}
+
+// Unknown languages fall back to English names
+pub fn format(
+    date: &DateTime<FixedOffset>,
+    pattern: &str,
+    language: Option<&str>,
+) -> Result<String, String> {
+    validate(pattern)?;
+
+    let pattern = match language.and_then(find_names) {
+        Some(names) => localize(pattern, date, names),
+        None => pattern.to_string(),
+    };
+
+    Ok(date.format(&pattern).to_string())
+}
+
+fn find_names(language: &str) -> Option<&'static Names> {
+    NAMES.iter().find(|names| names.language == language)
+}
+
+// Name specifiers are replaced with translated names. Other specifiers are left for chrono
+fn localize(pattern: &str, date: &DateTime<FixedOffset>, names: &Names) -> String {
+    let month = date.month0() as usize;
+    let weekday = date.weekday().num_days_from_monday() as usize;
+    let has_day = ["%d", "%e", "%-d", "%-e"]
+        .iter()
+        .any(|specifier| pattern.contains(specifier));
+
+    let mut result = String::new();
+    let mut chars = pattern.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '%' {
+            result.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            Some('B') => match names.genitive_months {
+                Some(genitive_months) if has_day => result.push_str(genitive_months[month]),
+                _ => result.push_str(names.months[month]),
+            },
+            Some('b') | Some('h') => result.push_str(names.short_months[month]),
+            Some('A') => result.push_str(names.weekdays[weekday]),
+            Some('a') => result.push_str(names.short_weekdays[weekday]),
+            Some(other) => {
+                result.push('%');
+                result.push(other);
+            }
+            None => result.push('%'),
+        }
+    }
+
+    result
+}
+
+// For example "5 minutes ago", "1 day ago" or "in 2 hours".
+// It's English only, the date language of the chat isn't applied
+pub fn relative(date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - date).num_seconds();
+    let minutes = seconds.abs() / 60;
+
+    if minutes == 0 {
+        return "just now".to_string();
+    }
+
+    let (amount, unit) = match minutes {
+        0..60 => (minutes, "minute"),
+        60..1440 => (minutes / 60, "hour"),
+        1440..43200 => (minutes / 1440, "day"),
+        43200..525600 => (minutes / 43200, "month"),
+        _ => (minutes / 525600, "year"),
+    };
+
+    let unit = if amount == 1 {
+        unit.to_string()
+    } else {
+        format!("{unit}s")
+    };
+
+    if seconds < 0 {
+        format!("in {amount} {unit}")
+    } else {
+        format!("{amount} {unit} ago")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use chrono::FixedOffset;
+    use chrono::TimeZone;
+    use chrono::Utc;
+
+    #[test]
+    fn format_translates_month_and_weekday_names() {
+        let date = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2024, 1, 13, 10, 5, 0)
+            .unwrap();
+
+        assert_eq!(
+            Ok("Saturday, 13 January 2024 10:05".to_string()),
+            super::format(&date, "%A, %d %B %Y %H:%M", None)
+        );
+        assert_eq!(
+            Ok("Samstag, 13. Januar 2024".to_string()),
+            super::format(&date, "%A, %d. %B %Y", Some("de"))
+        );
+        assert_eq!(
+            Ok("сб, 13 января 100%".to_string()),
+            super::format(&date, "%a, %-d %B 100%%", Some("ru"))
+        );
+        assert_eq!(
+            Ok("январь 2024".to_string()),
+            super::format(&date, "%B %Y", Some("ru"))
+        );
+        assert!(super::format(&date, "%Q", None).is_err());
+    }
+
+    #[test]
+    fn format_translates_short_names() {
+        let date = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2024, 3, 4, 8, 0, 0)
+            .unwrap();
+
+        assert_eq!(
+            Ok("Mo, 04 Mär 2024, Mär".to_string()),
+            super::format(&date, "%a, %d %b %Y, %h", Some("de"))
+        );
+        assert_eq!(
+            Ok("lun 4 mars".to_string()),
+            super::format(&date, "%a %-d %b", Some("fr"))
+        );
+    }
+
+    #[test]
+    fn format_uses_genitive_months_after_days() {
+        let date = FixedOffset::east_opt(7200)
+            .unwrap()
+            .with_ymd_and_hms(2024, 11, 5, 0, 0, 0)
+            .unwrap();
+
+        assert_eq!(
+            Ok("5 листопада 2024".to_string()),
+            super::format(&date, "%-d %B %Y", Some("uk"))
+        );
+        assert_eq!(
+            Ok("листопад 2024".to_string()),
+            super::format(&date, "%B %Y", Some("uk"))
+        );
+        assert_eq!(
+            Ok("вівторок, 05 листопада".to_string()),
+            super::format(&date, "%A, %d %B", Some("uk"))
+        );
+    }
+
+    #[test]
+    fn format_falls_back_to_english_names() {
+        let date = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2024, 1, 13, 10, 5, 0)
+            .unwrap();
+
+        assert_eq!(
+            Ok("Sat, 13 Jan 2024".to_string()),
+            super::format(&date, "%a, %d %b %Y", Some("xx"))
+        );
+        assert!(!super::is_supported_language("xx"));
+    }
+
+    #[test]
+    fn validate_rejects_invalid_formats() {
+        assert_eq!(Ok(()), super::validate("%Y-%m-%d %H:%M"));
+        assert_eq!(
+            Err("The date format can not be empty".to_string()),
+            super::validate("  ")
+        );
+        assert_eq!(Ok(()), super::validate(&"%Y".repeat(50)));
+        assert_eq!(
+            Err("The date format can not be longer than 100 characters".to_string()),
+            super::validate(&"%Y-".repeat(34))
+        );
+        assert_eq!(
+            Err("%Y % is not a valid strftime date format".to_string()),
+            super::validate("%Y %")
+        );
+
+        let date = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2024, 1, 13, 10, 5, 0)
+            .unwrap();
+
+        assert!(super::format(&date, "%d %B %", Some("ru")).is_err());
+    }
+
+    #[test]
+    fn relative_switches_units_at_boundaries() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 13, 10, 0, 0).unwrap();
+
+        for (distance, text) in [
+            (Duration::seconds(59), "just now"),
+            (Duration::minutes(59), "59 minutes ago"),
+            (Duration::minutes(60), "1 hour ago"),
+            (Duration::minutes(1439), "23 hours ago"),
+            (Duration::days(1), "1 day ago"),
+            (Duration::days(29), "29 days ago"),
+            (Duration::days(30), "1 month ago"),
+            (Duration::days(364), "12 months ago"),
+            (Duration::days(365), "1 year ago"),
+            (Duration::days(730), "2 years ago"),
+        ] {
+            assert_eq!(text, super::relative(now - distance, now));
+        }
+
+        assert_eq!("in 1 day", super::relative(now + Duration::days(1), now));
+        assert_eq!(
+            "just now",
+            super::relative(now + Duration::seconds(59), now)
+        );
+    }
+
+    #[test]
+    fn relative_describes_the_distance_to_now() {
+        let now = Utc.with_ymd_and_hms(2024, 1, 13, 10, 0, 0).unwrap();
+
+        assert_eq!(
+            "just now",
+            super::relative(now - Duration::seconds(30), now)
+        );
+        assert_eq!(
+            "1 minute ago",
+            super::relative(now - Duration::minutes(1), now)
+        );
+        assert_eq!(
+            "2 hours ago",
+            super::relative(now - Duration::minutes(150), now)
+        );
+        assert_eq!("3 days ago", super::relative(now - Duration::days(3), now));
+        assert_eq!(
+            "2 months ago",
+            super::relative(now - Duration::days(65), now)
+        );
+        assert_eq!(
+            "1 year ago",
+            super::relative(now - Duration::days(400), now)
+        );
+        assert_eq!(
+            "in 5 minutes",
+            super::relative(now + Duration::minutes(5), now)
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use super::links;
+use crate::models::FeedItem;
+use sha2::{Digest, Sha256};
+
+pub const MAX_DEDUP_DAYS: i32 = 30;
+
+// shorter titles like "Weekly update" are too common to compare
+const MIN_TITLE_CHARS: usize = 16;
+// fingerprints are truncated hashes to keep the table compact
+const FINGERPRINT_CHARS: usize = 32;
+
+// An item is a duplicate if any of its fingerprints was already delivered to the chat
+pub fn fingerprints(item: &FeedItem) -> Vec<String> {
+    let mut fingerprints = vec![fingerprint("link", &links::normalize_link(&item.link))];
+
+    if let Some(guid) = &item.guid {
+        if !guid.trim().is_empty() {
+            fingerprints.push(fingerprint("guid", guid.trim()));
+        }
+    }
+
+    let title = normalize_title(&item.title);
+
+    if title.chars().count() >= MIN_TITLE_CHARS {
+        fingerprints.push(fingerprint("title", &title));
+    }
+
+    fingerprints
+}
+
+// Titles that differ only in case, punctuation or spacing are equal
+pub fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn fingerprint(kind: &str, value: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(kind.as_bytes());
+    hasher.update(b":");
+    hasher.update(value.as_bytes());
+
+    let mut fingerprint = hex::encode(hasher.finalize());
+    fingerprint.truncate(FINGERPRINT_CHARS);
+
+    fingerprint
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::FeedItem;
+    use chrono::Utc;
+
+    fn item(title: &str, link: &str, guid: Option<&str>) -> FeedItem {
+        FeedItem {
+            feed_id: 1,
+            title: title.to_string(),
+            description: None,
+            link: link.to_string(),
+            author: None,
+            guid: guid.map(|guid| guid.to_string()),
+            publication_date: Utc::now(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            content_hash: "hash".to_string(),
+        }
+    }
+
+    #[test]
+    fn normalize_title_ignores_case_and_punctuation() {
+        assert_eq!(
+            "rust 2 0 is released",
+            super::normalize_title("  Rust 2.0 is released! ")
+        );
+    }
+
+    #[test]
+    fn fingerprints_match_for_mirrored_items() {
+        let original = super::fingerprints(&item(
+            "Rust 2.0 is released",
+            "https://blog.example.com/rust-2",
+            Some("post-1"),
+        ));
+        let mirror = super::fingerprints(&item(
+            "Rust 2.0 Is Released!",
+            "https://aggregator.example.org/1?utm_source=rss",
+            None,
+        ));
+        let other = super::fingerprints(&item(
+            "Weekly update",
+            "https://blog.example.com/rust-2/?utm_source=rss",
+            None,
+        ));
+
+        assert_eq!(3, original.len());
+        assert_eq!(original[2], mirror[1]);
+        assert_eq!(original[0], other[0]);
+        assert_eq!(1, other.len());
+    }
+}
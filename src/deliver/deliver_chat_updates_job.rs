@@ -1,28 +1,52 @@
 use super::MessageRenderer;
+use super::dedup;
+use super::links;
+use super::markdown;
+use super::quiet_hours;
+use super::render_message;
+use super::render_message::MAX_MESSAGE_CHARS;
+use super::rewrite;
+use super::split_message::split_markdown;
+use super::split_message::split_message;
+use super::telegram_html;
+use super::timezone;
 use crate::bot::SimpleMessageParams;
+use crate::bot::commands::ItemAction;
 use crate::bot::telegram_client;
 use crate::bot::telegram_client::Api;
+use crate::db;
+use crate::db::feed_items;
 use crate::db::feeds;
 use crate::db::telegram;
+use crate::db::telegram_dedup_fingerprints;
+use crate::db::telegram_delivered_items;
+use crate::db::telegram_outbox;
+use crate::db::telegram_outbox::NewTelegramOutboxMessage;
+use crate::db::telegram_rewrite_rules;
 use crate::models::Feed;
 use crate::models::FeedItem;
 use crate::models::TelegramChat;
+use crate::models::TelegramOutboxMessage;
 use crate::models::TelegramSubscription;
 use aho_corasick::AhoCorasickBuilder;
 use chrono::{DateTime, Utc};
+use diesel::Connection;
 use diesel::result::Error;
 use fang::FangError;
 use fang::PgConnection;
 use fang::Queueable;
 use fang::Runnable;
+use fang::Scheduled;
 use fang::typetag;
+use frankenstein::ParseMode;
+use frankenstein::types::InlineKeyboardMarkup;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use typed_builder::TypedBuilder;
 
-const TELEGRAM_ERRORS: [&str; 16] = [
+const TELEGRAM_ERRORS: [&str; 15] = [
     "Bad Request: CHAT_WRITE_FORBIDDEN",
-    "Bad Request: TOPIC_CLOSED",
     "Bad Request: chat not found",
     "Bad Request: group chat was upgraded to a supergroup chat",
     "Bad Request: group chat was upgraded to a supergroup chat, migrate to chat id",
@@ -39,19 +63,45 @@ const TELEGRAM_ERRORS: [&str; 16] = [
     "Forbidden: user is deactivated",
 ];
 
-const MESSAGES_LIMIT: usize = 10;
+// only the topic is closed, other subscriptions of the chat keep working
+const TOPIC_CLOSED_ERROR: &str = "Bad Request: TOPIC_CLOSED";
+
+pub const MESSAGES_LIMIT: usize = 10;
 const JOB_TYPE: &str = "deliver";
+const UNREAD_KEY_PREFIX: &str = "unread:";
+const SUMMARY_KEY_PREFIX: &str = "summary:";
+
+// a claimed message is sent again if the sender doesn't finish in this time
+const LEASE_SECONDS: i64 = 60;
+const RETRY_BASE_SECONDS: i64 = 30;
+const RETRY_MAX_SECONDS: i64 = 3600;
+const MAX_ATTEMPTS: i32 = 5;
+
+// fingerprints of items by their content hash
+type ItemFingerprints = HashMap<String, Vec<String>>;
+
+struct OutboxEntry {
+    message: String,
+    idempotency_key: String,
+    published_at: Option<DateTime<Utc>>,
+}
 
 #[derive(Debug)]
 pub struct DeliverJobError {
     pub msg: String,
+    pub retry_after: Option<u64>,
+    pub migrate_to_chat_id: Option<i64>,
 }
 
 impl From<Error> for DeliverJobError {
     fn from(error: Error) -> Self {
         let msg = format!("{error:?}");
 
-        DeliverJobError { msg }
+        DeliverJobError {
+            msg,
+            retry_after: None,
+            migrate_to_chat_id: None,
+        }
     }
 }
 
@@ -66,29 +116,42 @@ pub struct DeliverChatUpdates<'a> {
 
 impl DeliverChatUpdates<'_> {
     pub fn deliver(&mut self) -> Result<(), DeliverJobError> {
+        self.enqueue_undelivered()?;
+
+        self.send_outbox()
+    }
+
+    // Queues undelivered items without sending them
+    pub fn enqueue_undelivered(&mut self) -> Result<(), DeliverJobError> {
         let feed_items = telegram::find_undelivered_feed_items(
             self.db_connection,
             &self.subscription,
-            MESSAGES_LIMIT as i64,
+            self.messages_limit() as i64,
         )?;
 
-        if feed_items.is_empty() {
-            return Ok(());
-        }
+        if !feed_items.is_empty() {
+            let undelivered_count =
+                telegram::count_undelivered_feed_items(self.db_connection, &self.subscription);
 
-        let filter_words = self.filter_words();
+            // older items that didn't fit into messages_limit
+            let (unread_messages_count, older_items_count) = match self.filter_words() {
+                None => (
+                    self.unread_messages_count(&feed_items, undelivered_count),
+                    (undelivered_count as usize).saturating_sub(feed_items.len()),
+                ),
+                Some(_) => (None, 0),
+            };
 
-        if filter_words.is_none() {
-            self.maybe_send_unread_messages_count(feed_items.len())?;
+            self.enqueue(unread_messages_count, feed_items, older_items_count)?;
         }
 
-        let formatted_messages = self.format_messages(feed_items);
-
-        match self.filter_words() {
-            None => self.send_messages_without_filter(formatted_messages),
+        Ok(())
+    }
 
-            Some(words) => self.send_messages_with_filter(words, formatted_messages),
-        }
+    // Queues the given items, they're sent by the next delivery of the chat.
+    // feed_items are expected to be sorted from the newest to the oldest
+    pub fn enqueue_items(&mut self, feed_items: Vec<FeedItem>) -> Result<(), DeliverJobError> {
+        self.enqueue(None, feed_items, 0)
     }
 
     fn filter_words(&self) -> Option<Vec<String>> {
@@ -99,57 +162,599 @@ impl DeliverChatUpdates<'_> {
         self.subscription.filter_words.clone()
     }
 
-    fn maybe_send_unread_messages_count(
-        &mut self,
-        feed_items_count: usize,
-    ) -> Result<(), DeliverJobError> {
-        let undelivered_count =
-            telegram::count_undelivered_feed_items(self.db_connection, &self.subscription);
+    fn messages_limit(&self) -> usize {
+        match self.subscription.messages_limit {
+            Some(limit) => limit as usize,
+            None => MESSAGES_LIMIT,
+        }
+    }
+
+    // Link rules of the subscription are used instead of link rules of the chat.
+    // Chats without rules get the default rules
+    fn item_link(&self, item: &FeedItem) -> String {
+        let link_rules = match &self.subscription.link_rules {
+            Some(link_rules) => link_rules.clone(),
+            None => match &self.chat.link_rules {
+                Some(link_rules) => link_rules.clone(),
+                None => links::default_rules(),
+            },
+        };
+
+        links::rewrite_link(&item.link, &link_rules)
+    }
+
+    fn parse_mode(&self) -> ParseMode {
+        render_message::parse_mode(&self.chat.parse_mode)
+    }
 
+    fn unread_messages_count(
+        &self,
+        feed_items: &[FeedItem],
+        undelivered_count: i64,
+    ) -> Option<OutboxEntry> {
         if self.chat.kind == "channel" {
-            return Ok(());
+            return None;
         }
 
-        if feed_items_count == MESSAGES_LIMIT && undelivered_count > MESSAGES_LIMIT as i64 {
+        let feed_items_count = feed_items.len();
+        let messages_limit = self.messages_limit();
+
+        if feed_items_count == messages_limit && undelivered_count > messages_limit as i64 {
             let message = format!(
                 "You have {undelivered_count} unread items, below {feed_items_count} last items for {}",
                 self.feed.link
             );
+            let message = render_message::escape_text(&message, self.parse_mode());
+
+            let latest_created_at = feed_items.iter().map(|item| item.created_at).max()?;
+            // the count goes before the items it describes
+            let published_at = feed_items.iter().map(|item| item.publication_date).min();
 
-            self.send_text_message(message)?;
+            return Some(OutboxEntry {
+                message,
+                idempotency_key: format!("{UNREAD_KEY_PREFIX}{}", latest_created_at.timestamp()),
+                published_at,
+            });
         }
 
+        None
+    }
+
+    // Rendered messages and the new delivery cursor are saved in one transaction
+    // so items are never queued twice or skipped
+    fn enqueue(
+        &mut self,
+        unread_messages_count: Option<OutboxEntry>,
+        feed_items: Vec<FeedItem>,
+        older_items_count: usize,
+    ) -> Result<(), DeliverJobError> {
+        let last_delivered_at = feed_items.iter().map(|item| item.created_at).max();
+
+        let feed_items = self.rewrite_items(feed_items)?;
+        let feed_items = self.exclude_authors(feed_items);
+        let (feed_items, mut fingerprints) = self.skip_duplicates(feed_items)?;
+
+        let links = feed_items
+            .iter()
+            .map(|item| {
+                (
+                    item.content_hash.clone(),
+                    (item.title.clone(), self.item_link(item)),
+                )
+            })
+            .collect::<HashMap<String, (String, String)>>();
+
+        let formatted_messages = self.format_messages(feed_items);
+
+        let formatted_messages = match self.filter_words() {
+            None => formatted_messages,
+            Some(words) => self.filter_messages(words, formatted_messages),
+        };
+
+        let delivered_fingerprints = formatted_messages
+            .iter()
+            .filter_map(|entry| fingerprints.remove(&entry.idempotency_key))
+            .flatten()
+            .collect::<Vec<String>>();
+
+        let (formatted_messages, folded) =
+            self.apply_hourly_limit(formatted_messages, &links, older_items_count)?;
+
+        // the summary counts unread items itself
+        let unread_messages_count = if folded { None } else { unread_messages_count };
+
+        let messages = unread_messages_count
+            .into_iter()
+            .chain(formatted_messages)
+            .flat_map(|entry| self.split_entry(entry))
+            .map(|entry| NewTelegramOutboxMessage {
+                chat_id: self.subscription.chat_id,
+                feed_id: self.subscription.feed_id,
+                idempotency_key: entry.idempotency_key,
+                message: entry.message,
+                thread_id: self.subscription.thread_id,
+                preview_enabled: self.chat.preview_enabled,
+                parse_mode: self.chat.parse_mode.clone(),
+                published_at: entry.published_at,
+            })
+            .collect::<Vec<NewTelegramOutboxMessage>>();
+
+        let subscription = self.subscription.clone();
+
+        self.db_connection
+            .transaction::<(), Error, _>(|connection| {
+                telegram_outbox::enqueue(connection, messages)?;
+
+                if !delivered_fingerprints.is_empty() {
+                    telegram_dedup_fingerprints::save(
+                        connection,
+                        subscription.chat_id,
+                        delivered_fingerprints,
+                    )?;
+                }
+
+                if let Some(last_delivered_at) = last_delivered_at {
+                    telegram::set_subscription_last_delivered_at(
+                        connection,
+                        &subscription,
+                        last_delivered_at,
+                    )?;
+                }
+
+                Ok(())
+            })?;
+
         Ok(())
     }
 
-    fn send_text_message(&mut self, message: String) -> Result<(), DeliverJobError> {
-        let delay = self.delay_period();
+    // Titles and descriptions are rewritten before items are rendered and filtered
+    fn rewrite_items(&mut self, feed_items: Vec<FeedItem>) -> Result<Vec<FeedItem>, Error> {
+        let rules =
+            telegram_rewrite_rules::find_for_subscription(self.db_connection, &self.subscription)?;
+
+        Ok(rewrite::apply(feed_items, &rules))
+    }
+
+    fn exclude_authors(&self, feed_items: Vec<FeedItem>) -> Vec<FeedItem> {
+        let excluded_authors = match &self.subscription.excluded_authors {
+            Some(excluded_authors) => excluded_authors,
+            None => return feed_items,
+        };
+
+        feed_items
+            .into_iter()
+            .filter(|item| match &item.author {
+                Some(author) => !excluded_authors
+                    .iter()
+                    .any(|excluded| excluded.eq_ignore_ascii_case(author.trim())),
+                None => true,
+            })
+            .collect()
+    }
+
+    // Items that were already delivered to the chat within the dedup window are skipped.
+    // Returns fingerprints of the remaining items
+    fn skip_duplicates(
+        &mut self,
+        feed_items: Vec<FeedItem>,
+    ) -> Result<(Vec<FeedItem>, ItemFingerprints), DeliverJobError> {
+        let dedup_days = match self.chat.dedup_days {
+            Some(dedup_days) => dedup_days,
+            None => return Ok((feed_items, HashMap::new())),
+        };
+
+        let item_fingerprints = feed_items
+            .iter()
+            .map(dedup::fingerprints)
+            .collect::<Vec<Vec<String>>>();
+        let all_fingerprints = item_fingerprints
+            .iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<String>>();
+
+        let since = db::current_time() - chrono::Duration::days(dedup_days as i64);
+        let mut seen = telegram_dedup_fingerprints::find_delivered(
+            self.db_connection,
+            self.subscription.chat_id,
+            &all_fingerprints,
+            since,
+        )?
+        .into_iter()
+        .collect::<HashSet<String>>();
+
+        let mut kept_items = Vec::new();
+        let mut kept_fingerprints = HashMap::new();
+
+        // the oldest copy is kept if the feed has duplicates itself
+        for (item, fingerprints) in feed_items.into_iter().zip(item_fingerprints).rev() {
+            if fingerprints
+                .iter()
+                .any(|fingerprint| seen.contains(fingerprint))
+            {
+                continue;
+            }
+
+            seen.extend(fingerprints.iter().cloned());
+            kept_fingerprints.insert(item.content_hash.clone(), fingerprints);
+            kept_items.push(item);
+        }
+
+        kept_items.reverse();
+
+        Ok((kept_items, kept_fingerprints))
+    }
+
+    // Items over the hourly limit are folded into one summary message.
+    // The summary also counts older undelivered items that weren't loaded.
+    // Returns true if messages were folded
+    fn apply_hourly_limit(
+        &mut self,
+        mut messages: Vec<OutboxEntry>,
+        links: &HashMap<String, (String, String)>,
+        older_items_count: usize,
+    ) -> Result<(Vec<OutboxEntry>, bool), DeliverJobError> {
+        let hourly_limit = match self.subscription.hourly_limit {
+            Some(limit) => limit as i64,
+            None => return Ok((messages, false)),
+        };
+
+        let since = db::current_time() - chrono::Duration::hours(1);
+        let delivered_count =
+            telegram_outbox::count_items_since(self.db_connection, &self.subscription, since)?;
+        let available = (hourly_limit - delivered_count).max(0) as usize;
+
+        if messages.len() <= available {
+            return Ok((messages, false));
+        }
+
+        let overflow = messages.split_off(available);
+
+        messages.push(self.overflow_summary(&overflow, overflow.len() + older_items_count, links));
+
+        Ok((messages, true))
+    }
+
+    fn overflow_summary(
+        &self,
+        overflow: &[OutboxEntry],
+        overflow_count: usize,
+        links: &HashMap<String, (String, String)>,
+    ) -> OutboxEntry {
+        let feed_name = match &self.feed.title {
+            Some(title) if !title.trim().is_empty() => title.trim(),
+            _ => &self.feed.link,
+        };
+
+        let mut message = render_message::escape_text(
+            &format!("And {overflow_count} more from {feed_name}:"),
+            self.parse_mode(),
+        );
+
+        for entry in overflow {
+            let (title, link) = match links.get(&entry.idempotency_key) {
+                Some(title_and_link) => title_and_link,
+                None => continue,
+            };
+
+            let line = format!(
+                "\n{}",
+                render_message::render_item_link(title, link, self.parse_mode())
+            );
+
+            if message.chars().count() + line.chars().count() > MAX_MESSAGE_CHARS {
+                break;
+            }
+
+            message.push_str(&line);
+        }
+
+        OutboxEntry {
+            message,
+            idempotency_key: format!("{SUMMARY_KEY_PREFIX}{}", overflow[0].idempotency_key),
+            published_at: overflow.iter().filter_map(|entry| entry.published_at).max(),
+        }
+    }
+
+    fn quiet_hours_end(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let start = self.chat.quiet_hours_start?;
+        let end = self.chat.quiet_hours_end?;
+        let offset = timezone::offset_at(
+            self.chat.timezone.as_deref(),
+            self.chat.utc_offset_minutes,
+            now,
+        )
+        .local_minus_utc()
+            / 60;
+
+        quiet_hours::window_end(start, end, offset, now)
+    }
+
+    fn silent(&self) -> bool {
+        self.subscription.silent || self.quiet_hours_end(Utc::now()).is_some()
+    }
+
+    // Only items get buttons, not service messages like the unread count
+    fn item_keyboard(&self, message: &TelegramOutboxMessage) -> Option<InlineKeyboardMarkup> {
+        if message.idempotency_key.starts_with(UNREAD_KEY_PREFIX)
+            || message.idempotency_key.starts_with(SUMMARY_KEY_PREFIX)
+        {
+            return None;
+        }
+
+        ItemAction::keyboard(&self.subscription)
+    }
+
+    // Every part of a split message is queued separately.
+    // The first part keeps the key of the message so it's not queued twice
+    fn split_entry(&self, entry: OutboxEntry) -> Vec<OutboxEntry> {
+        if !self.subscription.split_messages {
+            return vec![entry];
+        }
+
+        let parts = match self.parse_mode() {
+            ParseMode::MarkdownV2 => split_markdown(&entry.message, MAX_MESSAGE_CHARS),
+            _ => split_message(&entry.message, MAX_MESSAGE_CHARS),
+        };
+
+        parts
+            .into_iter()
+            .enumerate()
+            .map(|(idx, message)| OutboxEntry {
+                message,
+                idempotency_key: match idx {
+                    0 => entry.idempotency_key.clone(),
+                    _ => format!("{}:{idx}", entry.idempotency_key),
+                },
+                published_at: entry.published_at,
+            })
+            .collect()
+    }
+
+    // Messages are sent in order. A message that can't be sent yet blocks the following ones
+    fn send_outbox(&mut self) -> Result<(), DeliverJobError> {
+        while let Some(message) =
+            telegram_outbox::next_pending(self.db_connection, &self.subscription)?
+        {
+            self.send_pending(message)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn send_pending(&mut self, message: TelegramOutboxMessage) -> Result<(), DeliverJobError> {
+        let now = Utc::now();
+
+        if message.next_attempt_at > now {
+            return Err(self.retry_error(message.next_attempt_at - now));
+        }
+
+        // held messages stay in the outbox until quiet hours end
+        if let Some(quiet_hours_end) = self.quiet_hours_end(now) {
+            if self.chat.quiet_hours_hold {
+                return Err(self.retry_error(quiet_hours_end - now));
+            }
+        }
+
+        let lease_until = now + chrono::Duration::seconds(LEASE_SECONDS);
+
+        match telegram_outbox::claim(self.db_connection, &message, lease_until)? {
+            Some(message) => self.send_outbox_message(message),
+            None => Err(self.retry_error(chrono::Duration::seconds(LEASE_SECONDS))),
+        }
+    }
+
+    fn send_outbox_message(
+        &mut self,
+        message: TelegramOutboxMessage,
+    ) -> Result<(), DeliverJobError> {
+        let parse_mode = render_message::parse_mode(&message.parse_mode);
 
         let message_params = SimpleMessageParams::builder()
-            .message(message)
-            .chat_id(self.chat.id)
-            .preview_enabled(self.chat.preview_enabled)
-            .message_thread_id(self.subscription.thread_id)
+            .message(message.message.clone())
+            .chat_id(message.chat_id)
+            .preview_enabled(message.preview_enabled)
+            .message_thread_id(message.thread_id)
+            .parse_mode(parse_mode)
+            .reply_markup(self.item_keyboard(&message))
+            .disable_notification(self.silent())
             .build();
 
         match self.api.reply_with_text_message(&message_params) {
-            Ok(_) => {
-                std::thread::sleep(delay);
+            Ok(sent_message) => {
+                let item = self.delivered_item(&message);
+
+                self.db_connection
+                    .transaction::<(), Error, _>(|connection| {
+                        telegram_outbox::mark_sent(connection, &message, sent_message.message_id)?;
+
+                        if let Some(item) = item {
+                            telegram_delivered_items::create(
+                                connection,
+                                message.chat_id,
+                                sent_message.message_id,
+                                item,
+                            )?;
+                        }
+
+                        Ok(())
+                    })?;
+
                 Ok(())
             }
 
             Err(error) => {
+                // a broken template or item shouldn't block the queue
+                if error.is_parse_error() {
+                    let plain_text = match parse_mode {
+                        ParseMode::MarkdownV2 => {
+                            markdown::escape(&markdown::unescape(&message.message))
+                        }
+                        _ => telegram_html::escape(&message.message),
+                    };
+
+                    if plain_text != message.message {
+                        log::error!(
+                            "Failed to parse message {} with {}, sending it as plain text",
+                            message.id,
+                            message.parse_mode
+                        );
+
+                        let message =
+                            telegram_outbox::set_message(self.db_connection, &message, plain_text)?;
+
+                        return self.send_outbox_message(message);
+                    }
+                }
+
+                if let Some(retry_after) = error.retry_after() {
+                    let retry_after = chrono::Duration::seconds(retry_after as i64);
+
+                    telegram_outbox::postpone(
+                        self.db_connection,
+                        &message,
+                        db::current_time() + retry_after,
+                    )?;
+
+                    return Err(self.retry_error(retry_after));
+                }
+
+                if let Some(new_chat_id) = error.migrate_to_chat_id() {
+                    telegram_outbox::postpone(self.db_connection, &message, db::current_time())?;
+
+                    return Err(self.migrate_chat(new_chat_id));
+                }
+
                 let error_message = format!("{error:?}");
 
-                Err(self.handle_error(error_message))
+                if error_message.contains(TOPIC_CLOSED_ERROR) && message.thread_id.is_some() {
+                    return self.leave_closed_topic(message);
+                }
+
+                if self.bot_blocked(&error_message) {
+                    return Err(self.handle_error(error_message));
+                }
+
+                self.fail_attempt(message, error_message)
+            }
+        }
+    }
+
+    // Summaries and unread counters don't belong to items.
+    // Parts of split messages have `:index` suffixes
+    fn delivered_item(&mut self, message: &TelegramOutboxMessage) -> Option<FeedItem> {
+        let key = &message.idempotency_key;
+
+        if key.starts_with(UNREAD_KEY_PREFIX) || key.starts_with(SUMMARY_KEY_PREFIX) {
+            return None;
+        }
+
+        let content_hash = key.split(':').next().unwrap_or_default();
+
+        feed_items::find_by_content_hash(self.db_connection, message.feed_id, content_hash)
+    }
+
+    fn fail_attempt(
+        &mut self,
+        message: TelegramOutboxMessage,
+        error_message: String,
+    ) -> Result<(), DeliverJobError> {
+        log::error!("Failed to deliver updates: {error_message}");
+
+        let exponent = message.attempts.clamp(0, 16) as u32;
+        let retry_after =
+            chrono::Duration::seconds((RETRY_BASE_SECONDS << exponent).min(RETRY_MAX_SECONDS));
+
+        let message = telegram_outbox::fail_attempt(
+            self.db_connection,
+            &message,
+            &error_message,
+            db::current_time() + retry_after,
+            MAX_ATTEMPTS,
+        )?;
+
+        if message.status == telegram_outbox::FAILED {
+            log::error!(
+                "Gave up on delivering message {} to chat {} after {} attempts",
+                message.id,
+                message.chat_id,
+                message.attempts
+            );
+
+            return Ok(());
+        }
+
+        Err(self.retry_error(retry_after))
+    }
+
+    // The subscription moves to a new topic if the chat creates topics for feeds,
+    // otherwise to the general topic. Pending messages follow it
+    fn leave_closed_topic(
+        &mut self,
+        message: TelegramOutboxMessage,
+    ) -> Result<(), DeliverJobError> {
+        log::info!(
+            "Topic {:?} of chat {} is closed, moving subscription to feed {}",
+            message.thread_id,
+            self.chat.id,
+            self.feed.id
+        );
+
+        let thread_id = if self.chat.topic_per_feed {
+            match self.api.create_feed_topic(self.chat.id, &self.feed) {
+                Ok(thread_id) => Some(thread_id),
+                Err(error) => {
+                    log::error!("Failed to create a forum topic {error:?}");
+
+                    None
+                }
             }
+        } else {
+            None
+        };
+
+        let subscription = self.subscription.clone();
+
+        self.subscription = self
+            .db_connection
+            .transaction::<TelegramSubscription, Error, _>(|connection| {
+                telegram_outbox::set_pending_thread_id(connection, &subscription, thread_id)?;
+                telegram_outbox::postpone(connection, &message, db::current_time())?;
+
+                telegram::set_thread_id(connection, &subscription, thread_id)
+            })?;
+
+        Err(self.retry_error(chrono::Duration::seconds(1)))
+    }
+
+    fn retry_error(&self, retry_after: chrono::Duration) -> DeliverJobError {
+        DeliverJobError {
+            msg: format!(
+                "Delivery to chat {} is postponed for {} seconds",
+                self.chat.id,
+                retry_after.num_seconds()
+            ),
+            retry_after: Some(retry_after.num_seconds().max(1) as u64),
+            migrate_to_chat_id: None,
         }
     }
 
-    fn delay_period(&self) -> Duration {
-        match self.chat.kind.as_str() {
-            "group" | "supergroup" => Duration::from_millis(2200),
-            _ => Duration::from_millis(35),
+    fn migrate_chat(&mut self, new_chat_id: i64) -> DeliverJobError {
+        match telegram::migrate_chat(self.db_connection, self.chat.id, new_chat_id) {
+            Ok(_) => {
+                log::info!("Migrated chat {} to {new_chat_id}", self.chat.id);
+
+                DeliverJobError {
+                    msg: format!("Chat {} was migrated to {new_chat_id}", self.chat.id),
+                    retry_after: None,
+                    migrate_to_chat_id: Some(new_chat_id),
+                }
+            }
+            Err(error) => DeliverJobError {
+                msg: format!("Failed to migrate chat {} : {error}", self.chat.id),
+                retry_after: None,
+                migrate_to_chat_id: None,
+            },
         }
     }
 
@@ -165,6 +770,8 @@ impl DeliverChatUpdates<'_> {
 
         DeliverJobError {
             msg: format!("Failed to send updates : {error}"),
+            retry_after: None,
+            migrate_to_chat_id: None,
         }
     }
 
@@ -174,7 +781,8 @@ impl DeliverChatUpdates<'_> {
             .any(|&message| error_message.contains(message))
     }
 
-    fn format_messages(&self, feed_items: Vec<FeedItem>) -> Vec<(String, DateTime<Utc>)> {
+    // Returns messages sorted from the oldest to the newest
+    fn format_messages(&self, feed_items: Vec<FeedItem>) -> Vec<OutboxEntry> {
         let template = match &self.subscription.template {
             Some(template) => Some(template.clone()),
             None => self.chat.template.clone(),
@@ -182,50 +790,49 @@ impl DeliverChatUpdates<'_> {
 
         let message_renderer_builder = MessageRenderer::builder()
             .offset(self.chat.utc_offset_minutes)
+            .timezone(self.chat.timezone.clone())
+            .date_format(self.chat.date_format.clone())
+            .date_language(self.chat.date_language.clone())
             .template(template)
+            .split_messages(self.subscription.split_messages)
+            .parse_mode(self.parse_mode())
             .bot_feed_name(self.feed.title.clone())
             .bot_feed_link(Some(self.feed.link.clone()));
 
         let mut formatted_messages = feed_items
-            .iter()
+            .into_iter()
             .map(|item| {
                 let message_renderer = message_renderer_builder
                     .clone()
                     .bot_date(item.publication_date)
                     .bot_item_name(item.title.clone())
-                    .bot_item_link(item.link.clone())
+                    .bot_item_link(self.item_link(&item))
+                    .bot_item_original_link(item.link.clone())
                     .bot_item_description(item.description.clone())
                     .bot_item_author(item.author.clone())
                     .build();
 
-                match message_renderer.render() {
-                    Ok(message) => (message, item.created_at),
-                    Err(error_message) => (error_message, item.created_at),
+                let message = match message_renderer.render() {
+                    Ok(message) => message,
+                    Err(error_message) => {
+                        render_message::escape_text(&error_message, self.parse_mode())
+                    }
+                };
+
+                OutboxEntry {
+                    message,
+                    idempotency_key: item.content_hash,
+                    published_at: Some(item.publication_date),
                 }
             })
-            .collect::<Vec<(String, DateTime<Utc>)>>();
+            .collect::<Vec<OutboxEntry>>();
 
         formatted_messages.reverse();
 
         formatted_messages
     }
 
-    fn send_messages_without_filter(
-        &mut self,
-        messages: Vec<(String, DateTime<Utc>)>,
-    ) -> Result<(), DeliverJobError> {
-        for (message, publication_date) in messages {
-            self.send_text_message_and_updated_subscription(message, publication_date)?
-        }
-
-        Ok(())
-    }
-
-    fn send_messages_with_filter(
-        &mut self,
-        words: Vec<String>,
-        messages: Vec<(String, DateTime<Utc>)>,
-    ) -> Result<(), DeliverJobError> {
+    fn filter_messages(&self, words: Vec<String>, messages: Vec<OutboxEntry>) -> Vec<OutboxEntry> {
         let (negated_words, regular_words): (Vec<String>, Vec<String>) =
             words.into_iter().partition(|word| word.starts_with('!'));
 
@@ -234,57 +841,25 @@ impl DeliverChatUpdates<'_> {
             .map(|word| word.replace('!', ""))
             .collect();
 
-        for (message, publication_date) in messages {
-            let mut mtch = true;
-            let lowercase_message = message.to_lowercase();
-
-            if !regular_words.is_empty() {
-                mtch = self.check_filter_words(&lowercase_message, &regular_words);
-            }
-
-            if !negated_words.is_empty() {
-                let negated_mtch = self.check_filter_words(&lowercase_message, &negated_words);
-
-                mtch = mtch && !negated_mtch;
-            }
-
-            if mtch {
-                self.send_text_message_and_updated_subscription(message, publication_date)?;
-            } else {
-                self.update_last_deivered_at(publication_date)?;
-            }
-        }
+        messages
+            .into_iter()
+            .filter(|entry| {
+                let mut mtch = true;
+                let lowercase_message = entry.message.to_lowercase();
 
-        Ok(())
-    }
+                if !regular_words.is_empty() {
+                    mtch = self.check_filter_words(&lowercase_message, &regular_words);
+                }
 
-    fn send_text_message_and_updated_subscription(
-        &mut self,
-        message: String,
-        publication_date: DateTime<Utc>,
-    ) -> Result<(), DeliverJobError> {
-        self.send_text_message(message)?;
+                if !negated_words.is_empty() {
+                    let negated_mtch = self.check_filter_words(&lowercase_message, &negated_words);
 
-        self.update_last_deivered_at(publication_date)
-    }
+                    mtch = mtch && !negated_mtch;
+                }
 
-    fn update_last_deivered_at(
-        &mut self,
-        publication_date: DateTime<Utc>,
-    ) -> Result<(), DeliverJobError> {
-        match telegram::set_subscription_last_delivered_at(
-            self.db_connection,
-            &self.subscription,
-            publication_date,
-        ) {
-            Ok(_) => Ok(()),
-            Err(error) => {
-                log::error!("Failed to set last_delivered_at: {error}");
-                Err(DeliverJobError {
-                    msg: format!("Failed to set last_delivered_at : {error}"),
-                })
-            }
-        }
+                mtch
+            })
+            .collect()
     }
 
     fn check_filter_words(&self, text: &str, words: &Vec<String>) -> bool {
@@ -302,25 +877,43 @@ impl DeliverChatUpdates<'_> {
 #[derive(Serialize, Deserialize)]
 pub struct DeliverChatUpdatesJob {
     pub chat_id: i64,
+
+    // not serialized so rescheduled jobs stay unique per chat
+    #[serde(skip)]
+    scheduled_at: Option<DateTime<Utc>>,
 }
 
 impl DeliverChatUpdatesJob {
     pub fn new(chat_id: i64) -> Self {
-        Self { chat_id }
+        Self {
+            chat_id,
+            scheduled_at: None,
+        }
     }
 
-    pub fn deliver(&self, db_connection: &mut PgConnection) -> Result<(), FangError> {
-        let chat = telegram::find_chat(db_connection, self.chat_id);
-
-        if chat.is_none() {
-            return Ok(());
+    pub fn scheduled(chat_id: i64, scheduled_at: DateTime<Utc>) -> Self {
+        Self {
+            chat_id,
+            scheduled_at: Some(scheduled_at),
         }
+    }
+
+    // Returns the number of seconds to wait if telegram rate limited the chat
+    pub fn deliver(&self, db_connection: &mut PgConnection) -> Result<Option<u64>, FangError> {
+        let chat = match telegram::find_chat(db_connection, self.chat_id) {
+            Some(chat) => chat,
+            None => return Ok(None),
+        };
 
         let subscriptions =
             telegram::find_unread_subscriptions_for_chat(db_connection, self.chat_id)?;
 
         let api = telegram_client::api();
 
+        if chat.interleave_items {
+            return self.deliver_interleaved(db_connection, api, chat, subscriptions);
+        }
+
         for subscription in subscriptions {
             let feed = feeds::find(db_connection, subscription.feed_id);
 
@@ -329,7 +922,7 @@ impl DeliverChatUpdatesJob {
             }
 
             let mut deliver_chat_updates = DeliverChatUpdates::builder()
-                .chat(chat.clone().unwrap())
+                .chat(chat.clone())
                 .feed(feed.unwrap())
                 .subscription(subscription.clone())
                 .db_connection(db_connection)
@@ -341,30 +934,114 @@ impl DeliverChatUpdatesJob {
                     telegram::mark_subscription_delivered(db_connection, &subscription)?;
                 }
 
-                Err(error) => {
-                    log::error!(
-                        "Failed to deliver updates for subscription: {subscription:?} {error:?}",
-                    );
-                    break;
-                }
+                Err(error) => return self.delivery_failed(db_connection, &subscription, error),
             }
         }
-        Ok(())
+        Ok(None)
+    }
+
+    // Items of all subscriptions are queued first and then sent in publication order.
+    // Every subscription saves its delivery cursor together with its queued items
+    fn deliver_interleaved(
+        &self,
+        db_connection: &mut PgConnection,
+        api: &Api,
+        chat: TelegramChat,
+        subscriptions: Vec<TelegramSubscription>,
+    ) -> Result<Option<u64>, FangError> {
+        let mut subscription_feeds: HashMap<i64, (TelegramSubscription, Feed)> = HashMap::new();
+
+        for subscription in subscriptions {
+            let feed = match feeds::find(db_connection, subscription.feed_id) {
+                Some(feed) => feed,
+                None => continue,
+            };
+
+            let result = DeliverChatUpdates::builder()
+                .chat(chat.clone())
+                .feed(feed.clone())
+                .subscription(subscription.clone())
+                .db_connection(db_connection)
+                .api(api)
+                .build()
+                .enqueue_undelivered();
+
+            if let Err(error) = result {
+                return self.delivery_failed(db_connection, &subscription, error);
+            }
+
+            subscription_feeds.insert(subscription.feed_id, (subscription, feed));
+        }
+
+        let feed_ids: Vec<i64> = subscription_feeds.keys().copied().collect();
+
+        while let Some(message) =
+            telegram_outbox::next_pending_for_chat(db_connection, chat.id, &feed_ids)?
+        {
+            let (subscription, feed) = &subscription_feeds[&message.feed_id];
+
+            let result = DeliverChatUpdates::builder()
+                .chat(chat.clone())
+                .feed(feed.clone())
+                .subscription(subscription.clone())
+                .db_connection(db_connection)
+                .api(api)
+                .build()
+                .send_pending(message);
+
+            if let Err(error) = result {
+                return self.delivery_failed(db_connection, subscription, error);
+            }
+        }
+
+        for (subscription, _) in subscription_feeds.values() {
+            telegram::mark_subscription_delivered(db_connection, subscription)?;
+        }
+
+        Ok(None)
+    }
+
+    fn delivery_failed(
+        &self,
+        db_connection: &mut PgConnection,
+        subscription: &TelegramSubscription,
+        error: DeliverJobError,
+    ) -> Result<Option<u64>, FangError> {
+        log::error!("Failed to deliver updates for subscription: {subscription:?} {error:?}");
+
+        if let Some(new_chat_id) = error.migrate_to_chat_id {
+            return DeliverChatUpdatesJob::new(new_chat_id).deliver(db_connection);
+        }
+
+        Ok(error.retry_after)
     }
 }
 
 #[typetag::serde]
 impl Runnable for DeliverChatUpdatesJob {
-    fn run(&self, _queue: &dyn Queueable) -> Result<(), FangError> {
+    fn run(&self, queue: &dyn Queueable) -> Result<(), FangError> {
         let mut db_connection = crate::db::pool().get()?;
 
-        self.deliver(&mut db_connection)
+        if let Some(retry_after) = self.deliver(&mut db_connection)? {
+            let scheduled_at = Utc::now() + chrono::Duration::seconds(retry_after as i64);
+
+            queue.schedule_task(&DeliverChatUpdatesJob::scheduled(
+                self.chat_id,
+                scheduled_at,
+            ))?;
+        }
+
+        Ok(())
     }
 
     fn uniq(&self) -> bool {
         true
     }
 
+    fn cron(&self) -> Option<Scheduled> {
+        self.scheduled_at.map(Scheduled::ScheduleOnce)
+    }
+
     fn max_retries(&self) -> i32 {
         0
     }
@@ -373,3 +1050,1033 @@ impl Runnable for DeliverChatUpdatesJob {
         JOB_TYPE.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::DeliverChatUpdates;
+    use super::DeliverChatUpdatesJob;
+    use crate::bot::rate_limiter::RateLimiter;
+    use crate::bot::telegram_client::Api;
+    use crate::db;
+    use crate::db::feed_items;
+    use crate::db::feeds;
+    use crate::db::telegram;
+    use crate::db::telegram::{NewTelegramChat, NewTelegramSubscription};
+    use crate::db::telegram_outbox;
+    use crate::db::telegram_rewrite_rules;
+    use crate::db::telegram_rewrite_rules::NewTelegramRewriteRule;
+    use crate::http_client;
+    use crate::models::{Feed, TelegramChat, TelegramSubscription};
+    use crate::schema::telegram_outbox as telegram_outbox_table;
+    use crate::sync::FetchedFeedItem;
+    use chrono::Duration;
+    use chrono::Timelike;
+    use diesel::connection::Connection;
+    use diesel::prelude::*;
+    use diesel::result::Error;
+    use frankenstein::methods::GetUpdatesParams;
+    use mockito::Matcher;
+    use mockito::Mock;
+    use std::collections::VecDeque;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    const SENT_MESSAGE: &str = "{\"ok\":true,\"result\":{\"message_id\":2746,\"date\":1618207352,\"chat\":{\"id\":9201,\"type\":\"private\"},\"text\":\"Hello!\"}}";
+    const SERVER_ERROR: &str =
+        "{\"ok\":false,\"error_code\":500,\"description\":\"Internal Server Error\"}";
+    const CANT_PARSE_ENTITIES: &str = "{\"ok\":false,\"error_code\":400,\"description\":\"Bad Request: can't parse entities: Can't find end tag corresponding to start tag \\\"b\\\"\"}";
+    const TOPIC_CLOSED: &str =
+        "{\"ok\":false,\"error_code\":400,\"description\":\"Bad Request: TOPIC_CLOSED\"}";
+    const TOO_MANY_REQUESTS: &str = "{\"ok\":false,\"error_code\":429,\"description\":\"Too Many Requests: retry after 7\",\"parameters\":{\"retry_after\":7}}";
+
+    fn fake_telegram(server: &mut mockito::Server, status: usize, body: &str) -> Mock {
+        server
+            .mock("POST", "/bot/sendMessage")
+            .with_status(status)
+            .with_body(body)
+            .create()
+    }
+
+    fn api(server: &mockito::Server) -> Api {
+        Api {
+            api_url: format!("{}/bot", server.url()),
+            update_params: GetUpdatesParams::builder().build(),
+            buffer: VecDeque::new(),
+            http_client: http_client::client().clone(),
+            rate_limiter: RateLimiter::disabled(),
+        }
+    }
+
+    fn setup(
+        connection: &mut PgConnection,
+        items_count: i64,
+    ) -> (TelegramChat, Feed, TelegramSubscription) {
+        let feed = feeds::create(connection, "Link", "rss".to_string()).unwrap();
+        let chat = telegram::create_chat(
+            connection,
+            NewTelegramChat {
+                id: 9201,
+                kind: "private".to_string(),
+                username: None,
+                first_name: None,
+                last_name: None,
+                title: None,
+            },
+        )
+        .unwrap();
+        let subscription = telegram::create_subscription(
+            connection,
+            NewTelegramSubscription::builder()
+                .chat_id(chat.id)
+                .feed_id(feed.id)
+                .build(),
+        )
+        .unwrap();
+
+        let now = db::current_time();
+        let items = (1..=items_count)
+            .map(|number| FetchedFeedItem {
+                title: format!("Item {number}"),
+                description: None,
+                link: format!("https://example.com/{number}"),
+                author: None,
+                guid: None,
+                publication_date: now - Duration::minutes(number),
+            })
+            .collect();
+
+        feed_items::create(connection, &feed, items).unwrap();
+
+        (chat, feed, subscription)
+    }
+
+    fn deliver(
+        connection: &mut PgConnection,
+        api: &Api,
+        (chat, feed, subscription): &(TelegramChat, Feed, TelegramSubscription),
+    ) -> Result<(), super::DeliverJobError> {
+        let subscription = telegram::find_subscription(
+            connection,
+            NewTelegramSubscription::builder()
+                .chat_id(subscription.chat_id)
+                .feed_id(subscription.feed_id)
+                .build(),
+        )
+        .unwrap();
+
+        DeliverChatUpdates::builder()
+            .chat(chat.clone())
+            .feed(feeds::find(connection, feed.id).unwrap())
+            .subscription(subscription)
+            .db_connection(connection)
+            .api(api)
+            .build()
+            .deliver()
+    }
+
+    fn expire_leases(connection: &mut PgConnection) {
+        diesel::update(telegram_outbox_table::table)
+            .set(
+                telegram_outbox_table::next_attempt_at
+                    .eq(db::current_time() - Duration::seconds(1)),
+            )
+            .execute(connection)
+            .unwrap();
+    }
+
+    #[test]
+    fn it_sends_queued_messages_once() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(2);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let data = setup(connection, 2);
+
+            deliver(connection, &api, &data).unwrap();
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(2, messages.len());
+            assert_eq!("Item 2", messages[0].message.lines().next().unwrap_or(""));
+
+            for message in messages {
+                assert_eq!(telegram_outbox::SENT, message.status);
+                assert_eq!(Some(2746), message.telegram_message_id);
+            }
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_sends_messages_queued_before_a_crash() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(2);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let data = setup(connection, 2);
+            let (chat, feed, subscription) = &data;
+            let items =
+                telegram::find_undelivered_feed_items(connection, subscription, 10).unwrap();
+
+            // the process crashes after messages are queued
+            DeliverChatUpdates::builder()
+                .chat(chat.clone())
+                .feed(feeds::find(connection, feed.id).unwrap())
+                .subscription(subscription.clone())
+                .db_connection(connection)
+                .api(&api)
+                .build()
+                .enqueue(None, items, 0)
+                .unwrap();
+
+            // and after the first message is claimed
+            let message = telegram_outbox::next_pending(connection, subscription)
+                .unwrap()
+                .unwrap();
+            let lease_until = db::current_time() + Duration::seconds(60);
+            telegram_outbox::claim(connection, &message, lease_until).unwrap();
+
+            let error = deliver(connection, &api, &data).unwrap_err();
+
+            assert!(error.retry_after.is_some());
+
+            expire_leases(connection);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages =
+                telegram_outbox::find_for_subscription(connection, subscription).unwrap();
+
+            assert_eq!(2, messages.len());
+            assert!(
+                messages
+                    .iter()
+                    .all(|message| message.status == telegram_outbox::SENT)
+            );
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_retries_failed_messages_with_backoff() {
+        let mut server = mockito::Server::new();
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let data = setup(connection, 2);
+
+            let failure = fake_telegram(&mut server, 500, SERVER_ERROR).expect(1);
+            let error = deliver(connection, &api, &data).unwrap_err();
+
+            assert_eq!(Some(30), error.retry_after);
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+            let failed = messages[0].clone();
+
+            assert_eq!(telegram_outbox::PENDING, failed.status);
+            assert_eq!(1, failed.attempts);
+            assert!(failed.last_error.is_some());
+            assert_eq!(0, messages[1].attempts);
+
+            // the second message waits for the first one
+            let error = deliver(connection, &api, &data).unwrap_err();
+
+            assert!(error.retry_after.is_some());
+
+            failure.assert();
+            failure.remove();
+
+            // the same message is retried once its backoff is over
+            expire_leases(connection);
+
+            let failure = fake_telegram(&mut server, 500, SERVER_ERROR).expect(1);
+            let error = deliver(connection, &api, &data).unwrap_err();
+
+            assert_eq!(Some(60), error.retry_after);
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(failed.id, messages[0].id);
+            assert_eq!(telegram_outbox::PENDING, messages[0].status);
+            assert_eq!(2, messages[0].attempts);
+            assert!(messages[0].next_attempt_at > failed.next_attempt_at);
+            assert_eq!(0, messages[1].attempts);
+
+            failure.assert();
+            failure.remove();
+
+            expire_leases(connection);
+
+            let success = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(2);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(2, messages.len());
+            assert_eq!(failed.id, messages[0].id);
+            assert_eq!(2, messages[0].attempts);
+            assert!(
+                messages
+                    .iter()
+                    .all(|message| message.status == telegram_outbox::SENT)
+            );
+
+            success.assert();
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn it_postpones_rate_limited_messages() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 429, TOO_MANY_REQUESTS).expect(1);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let data = setup(connection, 1);
+
+            let error = deliver(connection, &api, &data).unwrap_err();
+
+            assert_eq!(Some(7), error.retry_after);
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(telegram_outbox::PENDING, messages[0].status);
+            assert_eq!(0, messages[0].attempts);
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_skips_muted_and_paused_subscriptions() {
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, _feed, subscription) = setup(connection, 2);
+
+            let subscription = telegram::set_muted(connection, &subscription, true)?;
+
+            assert_eq!(
+                None,
+                DeliverChatUpdatesJob::new(chat.id)
+                    .deliver(connection)
+                    .unwrap()
+            );
+
+            let subscription = telegram::set_muted(connection, &subscription, false)?;
+            let subscription = telegram::pause_subscription(connection, &subscription, None)?;
+
+            assert_eq!(
+                None,
+                DeliverChatUpdatesJob::new(chat.id)
+                    .deliver(connection)
+                    .unwrap()
+            );
+
+            let messages_count = telegram_outbox_table::table
+                .filter(telegram_outbox_table::chat_id.eq(chat.id))
+                .count()
+                .get_result::<i64>(connection)?;
+
+            assert_eq!(0, messages_count);
+            assert!(subscription.has_updates);
+            assert_eq!(None, subscription.last_delivered_at);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn it_splits_long_messages_into_parts() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(2);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 0);
+            let subscription =
+                telegram::set_split_messages(connection, &subscription, true).unwrap();
+
+            let item = FetchedFeedItem {
+                title: "Long item".to_string(),
+                description: Some("This is a long sentence. ".repeat(240)),
+                link: "https://example.com/long".to_string(),
+                author: None,
+                guid: None,
+                publication_date: db::current_time(),
+            };
+            feed_items::create(connection, &feed, vec![item]).unwrap();
+
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(2, messages.len());
+            assert!(messages[1].idempotency_key.ends_with(":1"));
+            assert!(messages[0].message.starts_with("Long item"));
+            assert!(messages[1].message.ends_with("https://example.com/long"));
+
+            for message in messages {
+                assert_eq!(telegram_outbox::SENT, message.status);
+                assert!(message.message.chars().count() <= 4000);
+                assert!(message.message.ends_with('.') || message.message.ends_with("long"));
+            }
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_splits_long_markdown_messages_into_parts() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(2);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 0);
+            let chat = telegram::set_parse_mode(connection, &chat, "MarkdownV2").unwrap();
+            let subscription =
+                telegram::set_split_messages(connection, &subscription, true).unwrap();
+            let subscription = telegram::set_template(
+                connection,
+                &subscription,
+                Some("*{{bot_item_name}}*\n\n_{{bot_item_description}}_".to_string()),
+            )
+            .unwrap();
+
+            let item = FetchedFeedItem {
+                title: "Long item".to_string(),
+                description: Some("This is a long sentence. ".repeat(240)),
+                link: "https://example.com/long".to_string(),
+                author: None,
+                guid: None,
+                publication_date: db::current_time(),
+            };
+            feed_items::create(connection, &feed, vec![item]).unwrap();
+
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(2, messages.len());
+            assert!(messages[0].message.starts_with("*Long item*\n\n_This is"));
+            assert!(messages[1].message.starts_with("_This is"));
+
+            for message in messages {
+                assert_eq!(telegram_outbox::SENT, message.status);
+                assert!(message.message.chars().count() <= 4000);
+                assert!(message.message.ends_with("\\._"));
+            }
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_sends_messages_with_invalid_html_as_plain_text() {
+        let mut server = mockito::Server::new();
+        let invalid_html = server
+            .mock("POST", "/bot/sendMessage")
+            .match_body(Matcher::Regex("<b>Item 1".to_string()))
+            .with_status(400)
+            .with_body(CANT_PARSE_ENTITIES)
+            .expect(1)
+            .create();
+        let plain_text = server
+            .mock("POST", "/bot/sendMessage")
+            .match_body(Matcher::Regex("&lt;b&gt;Item 1".to_string()))
+            .with_status(200)
+            .with_body(SENT_MESSAGE)
+            .expect(1)
+            .create();
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 1);
+            let subscription = telegram::set_template(
+                connection,
+                &subscription,
+                Some("<b>{{bot_item_name}}".to_string()),
+            )
+            .unwrap();
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(1, messages.len());
+            assert_eq!(telegram_outbox::SENT, messages[0].status);
+            assert_eq!("&lt;b&gt;Item 1", messages[0].message);
+
+            Ok(())
+        });
+
+        invalid_html.assert();
+        plain_text.assert();
+    }
+
+    #[test]
+    fn it_renders_markdown_for_chats_with_markdown_parse_mode() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/bot/sendMessage")
+            .match_body(Matcher::Regex("\"parse_mode\":\"MarkdownV2\"".to_string()))
+            .with_status(200)
+            .with_body(SENT_MESSAGE)
+            .expect(1)
+            .create();
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 1);
+            let chat = telegram::set_parse_mode(connection, &chat, "MarkdownV2").unwrap();
+            let subscription = telegram::set_template(
+                connection,
+                &subscription,
+                Some(
+                    "{{bold bot_item_name}} \\- {{create_link \"link\" bot_item_link}}".to_string(),
+                ),
+            )
+            .unwrap();
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(1, messages.len());
+            assert_eq!("MarkdownV2", messages[0].parse_mode);
+            assert_eq!(
+                "*Item 1* \\- [link](https://example.com/1)",
+                messages[0].message
+            );
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_adds_item_buttons_and_skips_excluded_authors() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/bot/sendMessage")
+            .match_body(Matcher::Regex("/item_action description".to_string()))
+            .with_status(200)
+            .with_body(SENT_MESSAGE)
+            .expect(1)
+            .create();
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 0);
+            let subscription = telegram::set_item_buttons(
+                connection,
+                &subscription,
+                Some(vec!["description".to_string(), "mute".to_string()]),
+            )
+            .unwrap();
+            let subscription = telegram::set_excluded_authors(
+                connection,
+                &subscription,
+                Some(vec!["spammer".to_string()]),
+            )
+            .unwrap();
+
+            let items = ["Spammer", "Writer"]
+                .into_iter()
+                .map(|author| FetchedFeedItem {
+                    title: format!("Item by {author}"),
+                    description: None,
+                    link: format!("https://example.com/{author}"),
+                    author: Some(author.to_string()),
+                    guid: None,
+                    publication_date: db::current_time(),
+                })
+                .collect();
+            feed_items::create(connection, &feed, items).unwrap();
+
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(1, messages.len());
+            assert!(messages[0].message.contains("Item by Writer"));
+
+            let subscription =
+                telegram::find_subscription_by_external_id(connection, data.2.external_id).unwrap();
+
+            assert!(subscription.last_delivered_at.is_some());
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    fn current_quiet_hours() -> (i32, i32) {
+        let now = chrono::Utc::now();
+        let minutes = (now.hour() * 60 + now.minute()) as i32;
+
+        ((minutes + 1440 - 5) % 1440, (minutes + 60) % 1440)
+    }
+
+    #[test]
+    fn it_holds_items_during_quiet_hours() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(0);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 1);
+            let chat =
+                telegram::set_quiet_hours(connection, &chat, Some(current_quiet_hours()), true)
+                    .unwrap();
+            let data = (chat, feed, subscription);
+
+            let result = deliver(connection, &api, &data);
+
+            assert!(result.unwrap_err().msg.contains("is postponed"));
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(1, messages.len());
+            assert_eq!(telegram_outbox::PENDING, messages[0].status);
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_sends_items_without_notifications_for_silent_subscriptions() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/bot/sendMessage")
+            .match_body(Matcher::Regex("\"disable_notification\":true".to_string()))
+            .with_status(200)
+            .with_body(SENT_MESSAGE)
+            .expect(1)
+            .create();
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 1);
+            let subscription = telegram::set_silent(connection, &subscription, true).unwrap();
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_folds_items_over_the_hourly_limit_into_a_summary() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(2);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 3);
+            let subscription =
+                telegram::set_limits(connection, &subscription, None, Some(1)).unwrap();
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(2, messages.len());
+            assert_eq!("Item 3", messages[0].message.lines().next().unwrap_or(""));
+            assert!(messages[1].message.starts_with("And 2 more from Link:"));
+            assert!(messages[1].message.contains("https://example.com/1"));
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_counts_all_undelivered_items_in_the_summary() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(2);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 5);
+            let subscription =
+                telegram::set_limits(connection, &subscription, Some(3), Some(1)).unwrap();
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            // 2 items over the hourly limit and 2 items over messages_limit
+            assert_eq!(2, messages.len());
+            assert!(messages[0].message.starts_with("Item "));
+            assert!(messages[1].message.starts_with("And 4 more from Link:"));
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_interleaves_items_of_all_subscriptions_in_publication_order() {
+        // every sent message gets the next message id so the send order can be checked
+        let sent_count = Arc::new(AtomicI32::new(0));
+        let counter = sent_count.clone();
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("POST", "/bot/sendMessage")
+            .with_status(200)
+            .with_body_from_request(move |_| {
+                let message_id = counter.fetch_add(1, Ordering::SeqCst) + 1;
+
+                SENT_MESSAGE
+                    .replace("2746", &message_id.to_string())
+                    .into_bytes()
+            })
+            .expect(4)
+            .create();
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, _feed, _subscription) = setup(connection, 2);
+            let chat = telegram::set_interleave_items(connection, &chat, true).unwrap();
+
+            let other_feed = feeds::create(connection, "Other", "rss".to_string()).unwrap();
+            telegram::create_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(chat.id)
+                    .feed_id(other_feed.id)
+                    .build(),
+            )
+            .unwrap();
+
+            let now = db::current_time();
+            let items = [90, 30]
+                .into_iter()
+                .map(|seconds| FetchedFeedItem {
+                    title: format!("Other {seconds}"),
+                    description: None,
+                    link: format!("https://example.com/other/{seconds}"),
+                    author: None,
+                    guid: None,
+                    publication_date: now - Duration::seconds(seconds),
+                })
+                .collect();
+            feed_items::create(connection, &other_feed, items).unwrap();
+
+            let subscriptions =
+                telegram::find_unread_subscriptions_for_chat(connection, chat.id).unwrap();
+
+            assert_eq!(2, subscriptions.len());
+
+            DeliverChatUpdatesJob::new(chat.id)
+                .deliver_interleaved(connection, &api, chat.clone(), subscriptions.clone())
+                .unwrap();
+
+            let messages = telegram_outbox_table::table
+                .filter(telegram_outbox_table::chat_id.eq(chat.id))
+                .order(telegram_outbox_table::telegram_message_id.asc())
+                .load::<crate::models::TelegramOutboxMessage>(connection)
+                .unwrap();
+            let titles: Vec<&str> = messages
+                .iter()
+                .map(|message| message.message.lines().next().unwrap_or(""))
+                .collect();
+
+            assert_eq!(vec!["Item 2", "Other 90", "Item 1", "Other 30"], titles);
+            assert_eq!(
+                vec![Some(1), Some(2), Some(3), Some(4)],
+                messages
+                    .iter()
+                    .map(|message| message.telegram_message_id)
+                    .collect::<Vec<_>>()
+            );
+            assert!(
+                messages
+                    .iter()
+                    .all(|message| message.status == telegram_outbox::SENT)
+            );
+
+            for subscription in &subscriptions {
+                let subscription = telegram::find_subscription(
+                    connection,
+                    NewTelegramSubscription::builder()
+                        .chat_id(subscription.chat_id)
+                        .feed_id(subscription.feed_id)
+                        .build(),
+                )
+                .unwrap();
+
+                assert!(subscription.last_delivered_at.is_some());
+            }
+
+            assert!(
+                telegram::find_unread_subscriptions_for_chat(connection, chat.id)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_skips_items_already_delivered_to_the_chat_by_other_feeds() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(1);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 1);
+            let chat = telegram::set_dedup_days(connection, &chat, Some(7)).unwrap();
+
+            let mirror_feed = feeds::create(connection, "Mirror", "rss".to_string()).unwrap();
+            let mirror_subscription = telegram::create_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(chat.id)
+                    .feed_id(mirror_feed.id)
+                    .build(),
+            )
+            .unwrap();
+            let item = FetchedFeedItem {
+                title: "Mirrored item".to_string(),
+                description: None,
+                link: "http://www.example.com/1/?utm_source=mirror".to_string(),
+                author: None,
+                guid: None,
+                publication_date: db::current_time(),
+            };
+            feed_items::create(connection, &mirror_feed, vec![item]).unwrap();
+
+            deliver(connection, &api, &(chat.clone(), feed, subscription)).unwrap();
+
+            let data = (chat, mirror_feed, mirror_subscription);
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert!(messages.is_empty());
+
+            let mirror_subscription = telegram::find_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(data.2.chat_id)
+                    .feed_id(data.2.feed_id)
+                    .build(),
+            )
+            .unwrap();
+
+            assert!(mirror_subscription.last_delivered_at.is_some());
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_rewrites_item_links_with_link_rules_of_the_chat() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(1);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 1);
+            let chat = telegram::set_global_link_rules(
+                connection,
+                &chat,
+                Some(vec!["example.com=mirror.example.org".to_string()]),
+            )
+            .unwrap();
+            let subscription = telegram::set_template(
+                connection,
+                &subscription,
+                Some("{{bot_item_link}} {{bot_item_original_link}}".to_string()),
+            )
+            .unwrap();
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(
+                "https://mirror.example.org/1 https://example.com/1",
+                messages[0].message
+            );
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_rewrites_titles_before_filtering() {
+        let mut server = mockito::Server::new();
+        let mock = fake_telegram(&mut server, 200, SENT_MESSAGE).expect(1);
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 2);
+            telegram_rewrite_rules::create(
+                connection,
+                NewTelegramRewriteRule {
+                    chat_id: subscription.chat_id,
+                    feed_id: subscription.feed_id,
+                    field: "title".to_string(),
+                    pattern: "^Item (1)$".to_string(),
+                    replacement: "Post $1".to_string(),
+                },
+            )
+            .unwrap();
+            let subscription =
+                telegram::set_filter(connection, &subscription, Some(vec!["post".to_string()]))
+                    .unwrap();
+            let data = (chat, feed, subscription);
+
+            deliver(connection, &api, &data).unwrap();
+
+            let messages = telegram_outbox::find_for_subscription(connection, &data.2).unwrap();
+
+            assert_eq!(1, messages.len());
+            assert!(messages[0].message.contains("Post 1"));
+
+            Ok(())
+        });
+
+        mock.assert();
+    }
+
+    #[test]
+    fn it_moves_subscriptions_out_of_closed_topics() {
+        let mut server = mockito::Server::new();
+        let api = api(&server);
+        let mut connection = db::establish_test_connection();
+
+        let closed = fake_telegram(&mut server, 400, TOPIC_CLOSED).expect(2);
+
+        let new_topic = server
+            .mock("POST", "/bot/createForumTopic")
+            .with_status(200)
+            .with_body(
+                "{\"ok\":true,\"result\":{\"message_thread_id\":77,\"name\":\"Feed\",\"icon_color\":7322096}}",
+            )
+            .expect(1)
+            .create();
+
+        connection.test_transaction::<(), Error, _>(|connection| {
+            let (chat, feed, subscription) = setup(connection, 1);
+            telegram::set_thread_id(connection, &subscription, Some(5)).unwrap();
+            let data = (chat, feed, subscription);
+
+            // without topics per feed the subscription moves to the general topic
+            let error = deliver(connection, &api, &data).unwrap_err();
+
+            assert_eq!(Some(1), error.retry_after);
+            assert!(telegram::find_chat(connection, data.0.id).is_some());
+
+            let subscription = telegram::find_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(data.2.chat_id)
+                    .feed_id(data.2.feed_id)
+                    .build(),
+            )
+            .unwrap();
+            let messages =
+                telegram_outbox::find_for_subscription(connection, &subscription).unwrap();
+
+            assert_eq!(None, subscription.thread_id);
+            assert_eq!(telegram_outbox::PENDING, messages[0].status);
+            assert_eq!(None, messages[0].thread_id);
+            assert_eq!(0, messages[0].attempts);
+
+            // with topics per feed a new topic is created
+            telegram::set_thread_id(connection, &subscription, Some(5)).unwrap();
+            telegram_outbox::set_pending_thread_id(connection, &subscription, Some(5)).unwrap();
+            let chat = telegram::set_topic_per_feed(connection, &data.0, true).unwrap();
+            let data = (chat, data.1, subscription);
+            expire_leases(connection);
+
+            deliver(connection, &api, &data).unwrap_err();
+
+            let subscription = telegram::find_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(data.2.chat_id)
+                    .feed_id(data.2.feed_id)
+                    .build(),
+            )
+            .unwrap();
+            let messages =
+                telegram_outbox::find_for_subscription(connection, &subscription).unwrap();
+
+            assert_eq!(Some(77), subscription.thread_id);
+            assert_eq!(Some(77), messages[0].thread_id);
+
+            Ok(())
+        });
+
+        closed.assert();
+        new_topic.assert();
+    }
+}
@@ -60,7 +60,7 @@ impl Runnable for DeliverJob {
 
             for chat_id in current_chats {
                 queue
-                    .insert_task(&DeliverChatUpdatesJob { chat_id })
+                    .insert_task(&DeliverChatUpdatesJob::new(chat_id))
                     .unwrap();
             }
         }
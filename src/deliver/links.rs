@@ -0,0 +1,318 @@
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+const STRIP_TRACKERS: &str = "strip_trackers";
+const UNWRAP_REDIRECTS: &str = "unwrap_redirects";
+// redirectors can wrap each other
+const MAX_REDIRECTS: usize = 3;
+// rules of chats that didn't set their own rules
+const DEFAULT_RULES: [&str; 1] = [STRIP_TRACKERS];
+
+// Redirectors that keep the target url in a query parameter: host, path and parameter
+const REDIRECTORS: [(&str, &str, &str); 11] = [
+    ("www.google.com", "/url", "q"),
+    ("www.google.com", "/url", "url"),
+    ("l.facebook.com", "/l.php", "u"),
+    ("lm.facebook.com", "/l.php", "u"),
+    ("l.instagram.com", "/", "u"),
+    ("out.reddit.com", "/", "url"),
+    ("www.youtube.com", "/redirect", "q"),
+    ("vk.com", "/away.php", "to"),
+    ("steamcommunity.com", "/linkfilter/", "url"),
+    ("t.umblr.com", "/redirect", "z"),
+    ("slack-redir.net", "/link", "url"),
+];
+
+// FeedBurner proxies keep the target url in the path: /~r/<feed>/~3/<id>/<url>
+const PATH_REDIRECTORS: [&str; 2] = ["feedproxy.google.com", "feeds.feedburner.com"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkRule {
+    StripTrackers,
+    UnwrapRedirects,
+    ReplaceHost(String, String),
+}
+
+impl FromStr for LinkRule {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        match rule {
+            STRIP_TRACKERS => Ok(LinkRule::StripTrackers),
+            UNWRAP_REDIRECTS => Ok(LinkRule::UnwrapRedirects),
+            _ => match rule.split_once('=') {
+                Some((from, to)) if is_host(from) && is_host(to) => Ok(LinkRule::ReplaceHost(
+                    from.to_lowercase(),
+                    to.to_lowercase(),
+                )),
+                _ => Err(format!(
+                    "Unknown rule {rule}. Use {STRIP_TRACKERS}, {UNWRAP_REDIRECTS} or host=other.host"
+                )),
+            },
+        }
+    }
+}
+
+impl fmt::Display for LinkRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkRule::StripTrackers => write!(f, "{STRIP_TRACKERS}"),
+            LinkRule::UnwrapRedirects => write!(f, "{UNWRAP_REDIRECTS}"),
+            LinkRule::ReplaceHost(from, to) => write!(f, "{from}={to}"),
+        }
+    }
+}
+
+fn is_host(value: &str) -> bool {
+    match Url::parse(&format!("https://{value}")) {
+        Ok(url) => url.host_str() == Some(&value.to_lowercase()) && value.contains('.'),
+        Err(_) => false,
+    }
+}
+
+pub fn default_rules() -> Vec<String> {
+    DEFAULT_RULES.iter().map(|rule| rule.to_string()).collect()
+}
+
+// Rules are separated by spaces
+pub fn parse_rules(rules: &str) -> Result<Vec<LinkRule>, String> {
+    rules.split_whitespace().map(LinkRule::from_str).collect()
+}
+
+// Redirects are unwrapped first so trackers and hosts of the target link are rewritten too.
+// Invalid rules and links are left as they are
+pub fn rewrite_link(link: &str, rules: &[String]) -> String {
+    let rules: Vec<LinkRule> = rules
+        .iter()
+        .filter_map(|rule| LinkRule::from_str(rule).ok())
+        .collect();
+
+    if rules.is_empty() {
+        return link.to_string();
+    }
+
+    let mut url = match Url::parse(link.trim()) {
+        Ok(url) => url,
+        Err(_) => return link.to_string(),
+    };
+
+    if rules.contains(&LinkRule::UnwrapRedirects) {
+        for _ in 0..MAX_REDIRECTS {
+            match unwrap_redirect(&url) {
+                Some(target) => url = target,
+                None => break,
+            }
+        }
+    }
+
+    if rules.contains(&LinkRule::StripTrackers) {
+        strip_tracking_params(&mut url);
+    }
+
+    for rule in &rules {
+        if let LinkRule::ReplaceHost(from, to) = rule {
+            let host = url.host_str().unwrap_or_default().to_lowercase();
+
+            let matches = host == *from || host == format!("www.{from}");
+
+            if matches && url.set_host(Some(to)).is_ok() {
+                break;
+            }
+        }
+    }
+
+    url.to_string()
+}
+
+fn unwrap_redirect(url: &Url) -> Option<Url> {
+    let host = url.host_str()?;
+
+    if PATH_REDIRECTORS.contains(&host) {
+        return unwrap_path_redirect(url);
+    }
+
+    REDIRECTORS
+        .iter()
+        .filter(|(redirector_host, path, _)| *redirector_host == host && *path == url.path())
+        .find_map(|(_, _, param)| {
+            let (_, target) = url.query_pairs().find(|(name, _)| name == param)?;
+
+            http_url(&target)
+        })
+}
+
+fn unwrap_path_redirect(url: &Url) -> Option<Url> {
+    let mut segments = url.path().splitn(6, '/').skip(1);
+
+    if segments.next()? != "~r" || segments.nth(1)? != "~3" {
+        return None;
+    }
+
+    let encoded_target = segments.nth(1)?;
+    let target = percent_encoding::percent_decode_str(encoded_target)
+        .decode_utf8()
+        .ok()?;
+
+    http_url(&target)
+}
+
+fn http_url(value: &str) -> Option<Url> {
+    let url = Url::parse(value).ok()?;
+
+    match url.scheme() {
+        "http" | "https" => Some(url),
+        _ => None,
+    }
+}
+
+const TRACKING_PARAMS: [&str; 14] = [
+    "fbclid", "gclid", "dclid", "msclkid", "yclid", "igshid", "mc_cid", "mc_eid", "_hsenc",
+    "_hsmi", "ref", "ref_src", "spm", "si",
+];
+
+pub fn is_tracking_param(name: &str) -> bool {
+    let name = name.to_lowercase();
+
+    name.starts_with("utm_") || TRACKING_PARAMS.contains(&name.as_str())
+}
+
+pub fn strip_tracking_params(url: &mut Url) {
+    let params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| !is_tracking_param(name))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+
+    if params.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(params);
+    }
+}
+
+// Links of the same page published by different feeds become equal:
+// the scheme, `www.`, the fragment, trailing slashes and tracking parameters are dropped
+pub fn normalize_link(link: &str) -> String {
+    let mut url = match Url::parse(link.trim()) {
+        Ok(url) => url,
+        Err(_) => return link.trim().to_lowercase(),
+    };
+
+    strip_tracking_params(&mut url);
+
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let path = url.path().trim_end_matches('/');
+
+    match url.query() {
+        Some(query) => format!("{host}{path}?{query}"),
+        None => format!("{host}{path}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    #[test]
+    fn strip_tracking_params_keeps_other_params() {
+        let mut url =
+            Url::parse("https://example.com/post?id=5&utm_source=rss&fbclid=abc#comments").unwrap();
+
+        super::strip_tracking_params(&mut url);
+
+        assert_eq!("https://example.com/post?id=5#comments", url.as_str());
+    }
+
+    #[test]
+    fn parse_rules_validates_hosts() {
+        assert_eq!(
+            Ok(vec![
+                super::LinkRule::StripTrackers,
+                super::LinkRule::ReplaceHost("twitter.com".to_string(), "nitter.net".to_string()),
+            ]),
+            super::parse_rules("strip_trackers Twitter.com=nitter.net")
+        );
+        assert!(super::parse_rules("twitter.com=").is_err());
+        assert!(super::parse_rules("twitter.com=nitter.net/path").is_err());
+        assert!(super::parse_rules("shorten").is_err());
+    }
+
+    #[test]
+    fn rewrite_link_applies_rules() {
+        let rules = vec![
+            "unwrap_redirects".to_string(),
+            "strip_trackers".to_string(),
+            "youtube.com=invidious.example.org".to_string(),
+        ];
+
+        assert_eq!(
+            "https://invidious.example.org/watch?v=1",
+            super::rewrite_link(
+                "https://www.google.com/url?q=https%3A%2F%2Fwww.youtube.com%2Fwatch%3Fv%3D1%26utm_source%3Dfeed&sa=D",
+                &rules
+            )
+        );
+        assert_eq!(
+            "https://example.com/?utm_source=feed",
+            super::rewrite_link("https://example.com/?utm_source=feed", &[])
+        );
+        assert_eq!("not a link", super::rewrite_link("not a link", &rules));
+    }
+
+    #[test]
+    fn rewrite_link_unwraps_feedburner_links() {
+        let rules = vec!["unwrap_redirects".to_string()];
+
+        assert_eq!(
+            "https://example.com/post?id=1",
+            super::rewrite_link(
+                "http://feedproxy.google.com/~r/Example/~3/AbC123/https%3A%2F%2Fexample.com%2Fpost%3Fid%3D1",
+                &rules
+            )
+        );
+        assert_eq!(
+            "https://example.com/post",
+            super::rewrite_link(
+                "https://feeds.feedburner.com/~r/Example/~3/AbC123/https%3A%2F%2Fexample.com%2Fpost",
+                &rules
+            )
+        );
+        // the target is unknown without following the redirect
+        assert_eq!(
+            "http://feedproxy.google.com/~r/Example/~3/AbC123/post-slug",
+            super::rewrite_link(
+                "http://feedproxy.google.com/~r/Example/~3/AbC123/post-slug",
+                &rules
+            )
+        );
+    }
+
+    #[test]
+    fn default_rules_strip_trackers() {
+        assert_eq!(
+            "https://example.com/post?id=1",
+            super::rewrite_link(
+                "https://example.com/post?id=1&utm_source=feed",
+                &super::default_rules()
+            )
+        );
+    }
+
+    #[test]
+    fn normalize_link_drops_differences_between_mirrors() {
+        assert_eq!(
+            "example.com/news/post",
+            super::normalize_link("http://www.Example.com/news/post/?utm_medium=feed#top")
+        );
+        assert_eq!(
+            "example.com/news/post",
+            super::normalize_link("https://example.com/news/post")
+        );
+        assert_eq!(
+            "example.com/news?id=1",
+            super::normalize_link("https://example.com/news?id=1&ref=hn")
+        );
+    }
+}
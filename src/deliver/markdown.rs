@@ -0,0 +1,337 @@
+// MarkdownV2 formatting rules
+// https://core.telegram.org/bots/api#markdownv2-style
+
+const SPECIAL_CHARS: [char; 19] = [
+    '\\', '_', '*', '[', ']', '(', ')', '~', '`', '>', '#', '+', '-', '=', '|', '{', '}', '.', '!',
+];
+const ELLIPSIS: &str = "\\.\\.\\.";
+
+// Escapes all special characters so the text is shown as is
+pub fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if SPECIAL_CHARS.contains(&c) {
+            result.push('\\');
+        }
+
+        result.push(c);
+    }
+
+    result
+}
+
+// Removes escaping backslashes
+pub fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.peek() {
+                if SPECIAL_CHARS.contains(next) {
+                    result.push(*next);
+                    chars.next();
+                    continue;
+                }
+            }
+        }
+
+        result.push(c);
+    }
+
+    result
+}
+
+// Inside the url part of a link only `)` and `\` are escaped
+pub fn escape_link(url: &str) -> String {
+    url.replace('\\', "\\\\").replace(')', "\\)")
+}
+
+// Entity delimiters that are matched in pairs. `[` starts a link
+const DELIMITERS: [&str; 6] = ["||", "__", "_", "*", "~", "`"];
+const PRE: &str = "```";
+const LINK: &str = "[";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token<'a> {
+    Opening {
+        delimiter: &'static str,
+        raw: &'a str,
+        // the text that closes the entity, for links it's `](url)`
+        closing: String,
+    },
+    Closing {
+        delimiter: &'static str,
+        raw: &'a str,
+    },
+    // a single character or an escape sequence
+    Text(&'a str),
+}
+
+// Splits escaped text into entity delimiters and text.
+// Escape sequences are kept together and nothing is parsed inside code
+pub fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut open: Vec<&'static str> = vec![];
+    let mut link_closing: Option<&str> = None;
+    let mut idx = 0;
+
+    while idx < text.len() {
+        let rest = &text[idx..];
+        let code = open
+            .last()
+            .copied()
+            .filter(|delimiter| *delimiter == "`" || *delimiter == PRE);
+
+        let token = if rest.starts_with('\\') && rest.len() > 1 {
+            Token::Text(&rest[..1 + char_len(&rest[1..])])
+        } else if let Some(delimiter) = code {
+            if rest.starts_with(delimiter) {
+                Token::Closing {
+                    delimiter,
+                    raw: &rest[..delimiter.len()],
+                }
+            } else {
+                Token::Text(&rest[..char_len(rest)])
+            }
+        } else if rest.starts_with(PRE) {
+            let raw = &rest[..pre_opening_len(rest)];
+
+            Token::Opening {
+                delimiter: PRE,
+                raw,
+                closing: PRE.to_string(),
+            }
+        } else if let (true, Some(closing)) = (rest.starts_with(']'), link_closing) {
+            link_closing = None;
+
+            Token::Closing {
+                delimiter: LINK,
+                raw: &rest[..closing.len()],
+            }
+        } else if let (true, None, Some(closing)) =
+            (rest.starts_with('['), link_closing, find_link_closing(rest))
+        {
+            link_closing = Some(closing);
+
+            Token::Opening {
+                delimiter: LINK,
+                raw: &rest[..1],
+                closing: closing.to_string(),
+            }
+        } else if let Some(delimiter) = DELIMITERS.iter().find(|d| rest.starts_with(**d)) {
+            let raw = &rest[..delimiter.len()];
+
+            if open.contains(delimiter) {
+                Token::Closing { delimiter, raw }
+            } else {
+                Token::Opening {
+                    delimiter,
+                    raw,
+                    closing: raw.to_string(),
+                }
+            }
+        } else {
+            Token::Text(&rest[..char_len(rest)])
+        };
+
+        match &token {
+            Token::Opening { delimiter, .. } => open.push(delimiter),
+            Token::Closing { delimiter, .. } => {
+                if let Some(position) = open.iter().rposition(|open| open == delimiter) {
+                    open.truncate(position);
+                }
+            }
+            Token::Text(_) => (),
+        }
+
+        idx += token_raw(&token).len();
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+pub fn token_raw<'a>(token: &Token<'a>) -> &'a str {
+    match token {
+        Token::Opening { raw, .. } | Token::Closing { raw, .. } | Token::Text(raw) => raw,
+    }
+}
+
+fn char_len(text: &str) -> usize {
+    text.chars().next().map(char::len_utf8).unwrap_or(1)
+}
+
+// A pre block may start with a language on the first line
+fn pre_opening_len(text: &str) -> usize {
+    let language_end = text[PRE.len()..]
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '+' || c == '_'))
+        .map(|idx| idx + PRE.len());
+
+    match language_end {
+        Some(end) if text[end..].starts_with('\n') => end + 1,
+        _ => PRE.len(),
+    }
+}
+
+// `](url)` part of a link that starts at the beginning of the text
+fn find_link_closing(text: &str) -> Option<&str> {
+    let text_end = find_unescaped(text, 1, ']')?;
+
+    if !text[text_end + 1..].starts_with('(') {
+        return None;
+    }
+
+    let url_end = find_unescaped(text, text_end + 2, ')')?;
+
+    Some(&text[text_end..=url_end])
+}
+
+fn find_unescaped(text: &str, from: usize, target: char) -> Option<usize> {
+    let mut chars = text[from..].char_indices();
+
+    while let Some((idx, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == target {
+            return Some(from + idx);
+        }
+    }
+
+    None
+}
+
+// Truncates escaped text without cutting escape sequences.
+// Entities that are open at the cut are closed
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    let mut result = String::new();
+    let mut count = 0;
+    let mut open: Vec<(&str, String)> = vec![];
+
+    for token in tokenize(text) {
+        let closing_len: usize = open
+            .iter()
+            .map(|(_, closing)| closing.chars().count())
+            .sum();
+
+        if count + closing_len >= max_chars {
+            let in_code = open
+                .last()
+                .is_some_and(|(delimiter, _)| *delimiter == "`" || *delimiter == PRE);
+
+            // only `\` and ` are escaped inside code
+            result.push_str(if in_code { "..." } else { ELLIPSIS });
+
+            for (_, closing) in open.iter().rev() {
+                result.push_str(closing);
+            }
+
+            return result.trim().to_string();
+        }
+
+        match &token {
+            Token::Opening {
+                delimiter, closing, ..
+            } => open.push((delimiter, closing.clone())),
+            Token::Closing { delimiter, .. } => {
+                if let Some(position) = open.iter().rposition(|(open, _)| open == delimiter) {
+                    open.truncate(position);
+                }
+            }
+            Token::Text(_) => (),
+        }
+
+        let raw = token_raw(&token);
+
+        result.push_str(raw);
+        count += raw.chars().count();
+    }
+
+    result.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Token;
+    use super::escape;
+    use super::escape_link;
+    use super::tokenize;
+    use super::truncate;
+    use super::unescape;
+
+    #[test]
+    fn it_escapes_special_characters() {
+        let text = "Hello, world! 1 + 1 = 2. [link](url) *bold* _italic_ \\";
+
+        assert_eq!(
+            "Hello, world\\! 1 \\+ 1 \\= 2\\. \\[link\\]\\(url\\) \\*bold\\* \\_italic\\_ \\\\",
+            escape(text)
+        );
+        assert_eq!(text, unescape(&escape(text)));
+    }
+
+    #[test]
+    fn it_escapes_links() {
+        assert_eq!(
+            "https://example.com/a_(b\\)",
+            escape_link("https://example.com/a_(b)")
+        );
+    }
+
+    #[test]
+    fn it_truncates_without_cutting_escapes() {
+        assert_eq!("abc\\.\\.\\.", truncate("abcdef", 3));
+        assert_eq!("ab\\.\\.\\.\\.", truncate("ab\\.cd", 3));
+        assert_eq!("ab\\.", truncate("ab\\.", 3));
+    }
+
+    #[test]
+    fn it_closes_entities_when_truncating() {
+        assert_eq!("*bold te\\.\\.\\.*", truncate("*bold text*", 9));
+        assert_eq!(
+            "[link te\\.\\.\\.](https://example.com)",
+            truncate("[link text](https://example.com)", 30)
+        );
+        assert_eq!("`code...`", truncate("`code text`", 6));
+    }
+
+    #[test]
+    fn it_tokenizes_entities() {
+        assert_eq!(
+            vec![
+                Token::Opening {
+                    delimiter: "[",
+                    raw: "[",
+                    closing: "](https://example.com/\\))".to_string()
+                },
+                Token::Opening {
+                    delimiter: "*",
+                    raw: "*",
+                    closing: "*".to_string()
+                },
+                Token::Text("a"),
+                Token::Text("\\*"),
+                Token::Closing {
+                    delimiter: "*",
+                    raw: "*"
+                },
+                Token::Closing {
+                    delimiter: "[",
+                    raw: "](https://example.com/\\))"
+                },
+                Token::Opening {
+                    delimiter: "`",
+                    raw: "`",
+                    closing: "`".to_string()
+                },
+                Token::Text("_"),
+                Token::Closing {
+                    delimiter: "`",
+                    raw: "`"
+                },
+            ],
+            tokenize("[*a\\**](https://example.com/\\))`_`")
+        );
+    }
+}
@@ -0,0 +1,111 @@
+// Quiet hours are stored as minutes since midnight in the chat's timezone.
+// The window may cross midnight, for example 23:00-07:00
+
+use chrono::{DateTime, Duration, FixedOffset, Timelike, Utc};
+
+const MINUTES_IN_DAY: i32 = 24 * 60;
+
+// Parses windows like `23:00-07:00`
+pub fn parse(value: &str) -> Result<(i32, i32), String> {
+    let (start, end) = match value.split_once('-') {
+        Some((start, end)) => (parse_time(start)?, parse_time(end)?),
+        None => return Err("Quiet hours should look like 23:00-07:00".to_string()),
+    };
+
+    if start == end {
+        return Err("Quiet hours can't start and end at the same time".to_string());
+    }
+
+    Ok((start, end))
+}
+
+pub fn format(start: i32, end: i32) -> String {
+    format!("{}-{}", format_time(start), format_time(end))
+}
+
+// Returns the end of the window if `now` is inside it
+pub fn window_end(
+    start: i32,
+    end: i32,
+    offset_minutes: i32,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let offset = FixedOffset::east_opt(offset_minutes * 60)?;
+    let local_now = now.with_timezone(&offset);
+    let minute = (local_now.hour() * 60 + local_now.minute()) as i32;
+
+    let inside = if start < end {
+        start <= minute && minute < end
+    } else {
+        minute >= start || minute < end
+    };
+
+    if !inside {
+        return None;
+    }
+
+    let minutes_left = (end - minute).rem_euclid(MINUTES_IN_DAY);
+    let end_of_window =
+        now + Duration::minutes(minutes_left as i64) - Duration::seconds(local_now.second() as i64);
+
+    Some(end_of_window)
+}
+
+fn parse_time(value: &str) -> Result<i32, String> {
+    let error = || format!("{} is not a valid time. Use HH:MM", value.trim());
+
+    let (hours, minutes) = value.trim().split_once(':').ok_or_else(error)?;
+    let hours = hours.parse::<i32>().map_err(|_| error())?;
+    let minutes = minutes.parse::<i32>().map_err(|_| error())?;
+
+    if !(0..24).contains(&hours) || !(0..60).contains(&minutes) {
+        return Err(error());
+    }
+
+    Ok(hours * 60 + minutes)
+}
+
+fn format_time(minutes: i32) -> String {
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn it_parses_quiet_hours() {
+        assert_eq!(Ok((23 * 60, 7 * 60 + 30)), super::parse("23:00-07:30"));
+        assert_eq!("23:00-07:30", super::format(23 * 60, 7 * 60 + 30));
+        assert_eq!(
+            Err("25:00 is not a valid time. Use HH:MM".to_string()),
+            super::parse("25:00-07:00")
+        );
+        assert_eq!(
+            Err("Quiet hours should look like 23:00-07:00".to_string()),
+            super::parse("23:00")
+        );
+    }
+
+    #[test]
+    fn it_finds_the_end_of_the_window() {
+        let start = 23 * 60;
+        let end = 7 * 60;
+
+        let night = Utc.with_ymd_and_hms(2024, 1, 13, 23, 30, 15).unwrap();
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2024, 1, 14, 7, 0, 0).unwrap()),
+            super::window_end(start, end, 0, night)
+        );
+
+        let day = Utc.with_ymd_and_hms(2024, 1, 13, 12, 0, 0).unwrap();
+        assert_eq!(None, super::window_end(start, end, 0, day));
+
+        // 21:30 UTC is 23:30 in UTC +2
+        let evening = Utc.with_ymd_and_hms(2024, 1, 13, 21, 30, 0).unwrap();
+        assert_eq!(
+            Some(Utc.with_ymd_and_hms(2024, 1, 14, 5, 0, 0).unwrap()),
+            super::window_end(start, end, 120, evening)
+        );
+    }
+}
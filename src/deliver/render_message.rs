@@ -1,15 +1,29 @@
+use super::date_format;
+use super::markdown;
+use super::split_message::split_message;
+use super::telegram_html;
+use super::timezone;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::MatchKind;
 use chrono::DateTime;
+use chrono::FixedOffset;
 use chrono::Utc;
-use chrono::offset::FixedOffset;
 use chrono::prelude::*;
+use frankenstein::ParseMode;
+use handlebars::Context;
 use handlebars::Handlebars;
+use handlebars::Helper;
+use handlebars::HelperDef;
 use handlebars::JsonValue;
+use handlebars::RenderContext;
+use handlebars::RenderError;
+use handlebars::RenderErrorReason;
+use handlebars::ScopedJson;
 use handlebars::handlebars_helper;
 use handlebars::to_json;
 use htmlescape::decode_html;
 use serde_json::value::Map;
+use std::str::FromStr;
 use typed_builder::TypedBuilder;
 
 const BOT_DATE: &str = "bot_date";
@@ -19,25 +33,106 @@ const BOT_ITEM_AUTHOR: &str = "bot_item_author";
 const BOT_ITEM_DESCRIPTION: &str = "bot_item_description";
 const BOT_ITEM_LINK: &str = "bot_item_link";
 const BOT_ITEM_NAME: &str = "bot_item_name";
+const BOT_ITEM_ORIGINAL_LINK: &str = "bot_item_original_link";
 
 const SUBSTRING_HELPER: &str = "substring";
 const CREATE_LINK_HELPER: &str = "create_link";
 const BOLD_HELPER: &str = "bold";
 const ITALIC_HELPER: &str = "italic";
+const FORMAT_DATE_HELPER: &str = "format_date";
+const RELATIVE_DATE_HELPER: &str = "relative_date";
 
 const DEFAULT_TEMPLATE: &str = "{{bot_feed_name}}\n\n{{bot_item_name}}\n\n{{bot_item_description}}\n\n{{bot_date}}\n\n{{bot_item_link}}\n\n";
-const MAX_MESSAGE_CHARS: usize = 4000;
+pub const MAX_MESSAGE_CHARS: usize = 4000;
 const MAX_ITEM_CHARS: usize = 3000;
+// messages that are split into parts are still limited to a few parts
+const MAX_SPLIT_MESSAGE_CHARS: usize = 16000;
 const MAX_LINK_CHARS: usize = 1000;
 
 const RENDER_ERROR: &str = "Failed to render template";
 const EMPTY_MESSAGE_ERROR: &str = "According to your template the message is empty. Telegram doesn't support empty messages. That's why we're sending this placeholder message.";
 
 handlebars_helper!(create_link: |string: String, link: String| render_link(&string,&link));
+
+// Formats the item date with a strftime pattern, for example {{format_date "%d %B %Y"}}
+struct FormatDateHelper {
+    date: Option<DateTime<FixedOffset>>,
+    language: Option<String>,
+    markdown: bool,
+}
+
+impl HelperDef for FormatDateHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        helper: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let pattern = helper
+            .param(0)
+            .and_then(|param| param.value().as_str())
+            .ok_or(RenderErrorReason::ParamNotFoundForIndex(
+                FORMAT_DATE_HELPER,
+                0,
+            ))?;
+
+        let value = match &self.date {
+            Some(date) => date_format::format(date, pattern, self.language.as_deref())
+                .map_err(RenderErrorReason::Other)?,
+            None => "".to_string(),
+        };
+
+        Ok(ScopedJson::Derived(to_json(escape_helper_value(
+            value,
+            self.markdown,
+        ))))
+    }
+}
+
+// The distance from the item date to now, for example "2 hours ago". Always in English
+struct RelativeDateHelper {
+    date: Option<DateTime<Utc>>,
+    markdown: bool,
+}
+
+impl HelperDef for RelativeDateHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        _: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> Result<ScopedJson<'rc>, RenderError> {
+        let value = match self.date {
+            Some(date) => date_format::relative(date, Utc::now()),
+            None => "".to_string(),
+        };
+
+        Ok(ScopedJson::Derived(to_json(escape_helper_value(
+            value,
+            self.markdown,
+        ))))
+    }
+}
+
+// html is escaped by handlebars, markdown escaping is turned off for templates
+fn escape_helper_value(value: String, markdown: bool) -> String {
+    if markdown {
+        markdown::escape(&value)
+    } else {
+        value
+    }
+}
 handlebars_helper!(bold: |string: String| format!("<b>{string}</b>"));
 handlebars_helper!(italic: |string: String| format!("<i>{string}</i>"));
 handlebars_helper!(substring: |string: String, length: usize| truncate(&string, length));
 
+handlebars_helper!(markdown_create_link: |string: String, link: String| render_markdown_link(&string, &link));
+handlebars_helper!(markdown_bold: |string: String| format!("*{string}*"));
+handlebars_helper!(markdown_italic: |string: String| format!("_{string}_"));
+handlebars_helper!(markdown_substring: |string: String, length: usize| markdown::truncate(&string, length));
+
 #[derive(TypedBuilder)]
 pub struct MessageRenderer {
     #[builder(setter(into), default)]
@@ -50,6 +145,9 @@ pub struct MessageRenderer {
     bot_feed_link: Option<String>,
     #[builder(setter(into), default)]
     bot_item_link: Option<String>,
+    // the link before link rules were applied
+    #[builder(setter(into), default)]
+    bot_item_original_link: Option<String>,
     #[builder(setter(into), default)]
     bot_item_description: Option<String>,
     #[builder(setter(into), default)]
@@ -58,6 +156,19 @@ pub struct MessageRenderer {
     template: Option<String>,
     #[builder(setter(into), default)]
     offset: Option<i32>,
+    // IANA timezone name, it takes precedence over the offset
+    #[builder(setter(into), default)]
+    timezone: Option<String>,
+    // strftime pattern for bot_date
+    #[builder(setter(into), default)]
+    date_format: Option<String>,
+    // language of month and weekday names
+    #[builder(setter(into), default)]
+    date_language: Option<String>,
+    #[builder(default = false)]
+    split_messages: bool,
+    #[builder(default = ParseMode::Html)]
+    parse_mode: ParseMode,
 }
 
 impl MessageRenderer {
@@ -79,10 +190,27 @@ impl MessageRenderer {
             BOT_ITEM_NAME,
             &self.maybe_remove_html(&self.bot_item_name),
         );
-        self.maybe_set_value(&mut data, BOT_DATE, &self.date());
-        self.maybe_set_value(&mut data, BOT_FEED_LINK, &self.bot_feed_link);
-        self.maybe_set_value(&mut data, BOT_ITEM_LINK, &self.bot_item_link);
-        self.maybe_set_value(&mut data, BOT_ITEM_AUTHOR, &self.bot_item_author);
+        self.maybe_set_value(&mut data, BOT_DATE, &self.maybe_escape(&self.date()));
+        self.maybe_set_value(
+            &mut data,
+            BOT_FEED_LINK,
+            &self.maybe_escape(&self.bot_feed_link),
+        );
+        self.maybe_set_value(
+            &mut data,
+            BOT_ITEM_LINK,
+            &self.maybe_escape(&self.bot_item_link),
+        );
+        self.maybe_set_value(
+            &mut data,
+            BOT_ITEM_ORIGINAL_LINK,
+            &self.maybe_escape(&self.bot_item_original_link),
+        );
+        self.maybe_set_value(
+            &mut data,
+            BOT_ITEM_AUTHOR,
+            &self.maybe_escape(&self.bot_item_author),
+        );
         self.maybe_set_value(
             &mut data,
             BOT_ITEM_DESCRIPTION,
@@ -91,45 +219,94 @@ impl MessageRenderer {
 
         let mut reg = Handlebars::new();
 
-        reg.register_helper(SUBSTRING_HELPER, Box::new(substring));
-        reg.register_helper(BOLD_HELPER, Box::new(bold));
-        reg.register_helper(ITALIC_HELPER, Box::new(italic));
-        reg.register_helper(CREATE_LINK_HELPER, Box::new(create_link));
+        if self.markdown() {
+            // values are already escaped for markdown
+            reg.register_escape_fn(handlebars::no_escape);
+
+            reg.register_helper(SUBSTRING_HELPER, Box::new(markdown_substring));
+            reg.register_helper(BOLD_HELPER, Box::new(markdown_bold));
+            reg.register_helper(ITALIC_HELPER, Box::new(markdown_italic));
+            reg.register_helper(CREATE_LINK_HELPER, Box::new(markdown_create_link));
+        } else {
+            reg.register_helper(SUBSTRING_HELPER, Box::new(substring));
+            reg.register_helper(BOLD_HELPER, Box::new(bold));
+            reg.register_helper(ITALIC_HELPER, Box::new(italic));
+            reg.register_helper(CREATE_LINK_HELPER, Box::new(create_link));
+        }
+
+        reg.register_helper(
+            FORMAT_DATE_HELPER,
+            Box::new(FormatDateHelper {
+                date: self.local_date(),
+                language: self.date_language.clone(),
+                markdown: self.markdown(),
+            }),
+        );
+        reg.register_helper(
+            RELATIVE_DATE_HELPER,
+            Box::new(RelativeDateHelper {
+                date: self.bot_date,
+                markdown: self.markdown(),
+            }),
+        );
 
         match reg.render_template(&template, &data) {
             Err(error) => {
                 log::error!("Failed to render template {error:?}");
                 Err(RENDER_ERROR.to_string())
             }
-            Ok(result) => Ok(truncate_and_check(&result)),
+            Ok(result) if self.markdown() => Ok(truncate_markdown_and_check(
+                &result,
+                self.max_message_chars(),
+            )),
+            Ok(result) => Ok(truncate_and_check(&result, self.max_message_chars())),
+        }
+    }
+
+    fn markdown(&self) -> bool {
+        self.parse_mode == ParseMode::MarkdownV2
+    }
+
+    fn maybe_escape(&self, value_option: &Option<String>) -> Option<String> {
+        match value_option {
+            Some(value) if self.markdown() => Some(markdown::escape(value)),
+            _ => value_option.clone(),
         }
     }
 
+    fn local_date(&self) -> Option<DateTime<FixedOffset>> {
+        let date = self.bot_date?;
+        let time_offset = timezone::offset_at(self.timezone.as_deref(), self.offset, date);
+
+        Some(date.with_timezone(&time_offset))
+    }
+
+    // Invalid chat formats fall back to the default format
     fn date(&self) -> Option<String> {
-        if let Some(date) = &self.bot_date {
-            let time_offset = match self.offset {
-                None => FixedOffset::west_opt(0),
-                Some(value) => {
-                    if value > 0 {
-                        FixedOffset::east_opt(value * 60)
-                    } else {
-                        FixedOffset::west_opt(-value * 60)
-                    }
-                }
-            };
-
-            let date_with_timezone = date.with_timezone(&time_offset.unwrap());
-
-            return Some(format!("{date_with_timezone}"));
+        let date = self.local_date()?;
+
+        if let Some(pattern) = &self.date_format {
+            match date_format::format(&date, pattern, self.date_language.as_deref()) {
+                Ok(formatted_date) => return Some(formatted_date),
+                Err(error) => log::error!("Failed to format date: {error}"),
+            }
         }
 
-        None
+        Some(format!("{date}"))
     }
 
     fn maybe_remove_html(&self, value_option: &Option<String>) -> Option<String> {
         if let Some(value) = value_option {
             let without_html = remove_html(value);
-            let truncated = truncate(&without_html, MAX_ITEM_CHARS);
+
+            if self.markdown() {
+                let text = decode_html(&without_html).unwrap_or(without_html);
+                let truncated = truncate(&text, self.max_item_chars());
+
+                return Some(markdown::escape(&truncated));
+            }
+
+            let truncated = truncate(&without_html, self.max_item_chars());
 
             return Some(truncated);
         }
@@ -137,6 +314,22 @@ impl MessageRenderer {
         None
     }
 
+    fn max_message_chars(&self) -> usize {
+        if self.split_messages {
+            MAX_SPLIT_MESSAGE_CHARS
+        } else {
+            MAX_MESSAGE_CHARS
+        }
+    }
+
+    fn max_item_chars(&self) -> usize {
+        if self.split_messages {
+            MAX_SPLIT_MESSAGE_CHARS
+        } else {
+            MAX_ITEM_CHARS
+        }
+    }
+
     fn maybe_set_value(
         &self,
         map: &mut Map<String, JsonValue>,
@@ -150,19 +343,73 @@ impl MessageRenderer {
     }
 }
 
-pub fn render_template_example(template: &str) -> Result<String, String> {
+pub fn render_template_example(template: &str, parse_mode: ParseMode) -> Result<String, String> {
     let message_renderer = MessageRenderer::builder()
         .bot_feed_name(Some("feed_name".to_string()))
         .bot_item_name(Some("item_name".to_string()))
         .bot_date(Some(Utc::now().round_subsecs(0)))
         .bot_feed_link(Some("https://www.badykov.com/feed.xml".to_string()))
         .bot_item_link(Some("https://www.badykov.com/".to_string()))
+        .bot_item_original_link(Some("https://www.badykov.com/?utm_source=rss".to_string()))
         .bot_item_description(Some("item_description".to_string()))
         .bot_item_author(Some("Airat".to_string()))
         .template(Some(template.to_string()))
+        .parse_mode(parse_mode)
         .build();
 
-    message_renderer.render()
+    let example = message_renderer.render()?;
+
+    if parse_mode == ParseMode::Html {
+        telegram_html::validate(&example)?;
+    }
+
+    Ok(example)
+}
+
+// The full description of an item split into messages
+pub fn render_description(description: &str) -> Vec<String> {
+    let text = truncate(&remove_html(description), MAX_SPLIT_MESSAGE_CHARS);
+
+    if text.is_empty() {
+        return vec![];
+    }
+
+    split_message(&text, MAX_MESSAGE_CHARS)
+}
+
+// Parse modes are stored by their names in telegram api
+pub fn parse_mode(name: &str) -> ParseMode {
+    ParseMode::from_str(name).unwrap_or(ParseMode::Html)
+}
+
+// Text that is sent as is in the specified parse mode
+pub fn escape_text(text: &str, parse_mode: ParseMode) -> String {
+    match parse_mode {
+        ParseMode::MarkdownV2 => markdown::escape(text),
+        _ => telegram_html::escape_text(text),
+    }
+}
+
+// Links of items listed in service messages like overflow summaries
+pub fn render_item_link(title: &str, link: &str, parse_mode: ParseMode) -> String {
+    let title = match title.trim() {
+        "" => link,
+        title => title,
+    };
+    let title: String = title.chars().take(MAX_LINK_CHARS).collect();
+
+    match parse_mode {
+        ParseMode::MarkdownV2 => format!(
+            "[{}]({})",
+            markdown::escape(&title),
+            markdown::escape_link(link)
+        ),
+        _ => format!(
+            "<a href=\"{}\">{}</a>",
+            telegram_html::escape_text(link).replace('"', "&quot;"),
+            telegram_html::escape_text(&title)
+        ),
+    }
 }
 
 fn render_link(s: &str, l: &str) -> String {
@@ -174,13 +421,34 @@ fn render_link(s: &str, l: &str) -> String {
     format!("<a href=\"{l}\">{value}</a>")
 }
 
-fn truncate_and_check(s: &str) -> String {
+fn render_markdown_link(s: &str, l: &str) -> String {
+    let value = if s.is_empty() {
+        "link".to_string()
+    } else {
+        markdown::truncate(s, MAX_LINK_CHARS)
+    };
+    let link = markdown::escape_link(&markdown::unescape(l));
+
+    format!("[{value}]({link})")
+}
+
+fn truncate_markdown_and_check(s: &str, max_chars: usize) -> String {
+    let truncated_result = markdown::truncate(s, max_chars);
+
+    if truncated_result.is_empty() {
+        markdown::escape(EMPTY_MESSAGE_ERROR)
+    } else {
+        truncated_result
+    }
+}
+
+fn truncate_and_check(s: &str, max_chars: usize) -> String {
     let escaped_data = match decode_html(s) {
         Ok(escaped_html) => escaped_html,
         Err(_) => return RENDER_ERROR.to_string(),
     };
 
-    let truncated_result = truncate(&escaped_data, MAX_MESSAGE_CHARS);
+    let truncated_result = truncate(&escaped_data, max_chars);
 
     if truncated_result.is_empty() {
         EMPTY_MESSAGE_ERROR.to_string()
@@ -219,3 +487,56 @@ fn remove_html(string_with_maybe_html: &str) -> String {
         &[" ", "&amp;", "&lt;", "&gt;", " ", " ", " ", " ", " "],
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::MessageRenderer;
+    use crate::deliver::date_format;
+    use chrono::TimeZone;
+    use chrono::Utc;
+    use frankenstein::ParseMode;
+
+    const DATE_TEMPLATE: &str = "{{format_date \"%d %B %Y\"}}\n{{relative_date}}\n{{bot_date}}";
+
+    fn render_dates(parse_mode: ParseMode) -> String {
+        MessageRenderer::builder()
+            .bot_date(Some(Utc.with_ymd_and_hms(2024, 1, 13, 10, 5, 0).unwrap()))
+            .template(Some(DATE_TEMPLATE.to_string()))
+            .timezone(Some("Europe/Berlin".to_string()))
+            .date_format(Some("%A, %d. %B %Y %H:%M".to_string()))
+            .date_language(Some("de".to_string()))
+            .parse_mode(parse_mode)
+            .build()
+            .render()
+            .unwrap()
+    }
+
+    fn relative_date() -> String {
+        date_format::relative(
+            Utc.with_ymd_and_hms(2024, 1, 13, 10, 5, 0).unwrap(),
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn it_renders_dates_in_the_chat_format_and_language() {
+        assert_eq!(
+            format!(
+                "13 Januar 2024\n{}\nSamstag, 13. Januar 2024 11:05",
+                relative_date()
+            ),
+            render_dates(ParseMode::Html)
+        );
+    }
+
+    #[test]
+    fn it_escapes_dates_for_markdown() {
+        assert_eq!(
+            format!(
+                "13 Januar 2024\n{}\nSamstag, 13\\. Januar 2024 11:05",
+                relative_date()
+            ),
+            render_dates(ParseMode::MarkdownV2)
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use crate::models::FeedItem;
+use crate::models::TelegramRewriteRule;
+use regex::Regex;
+use regex::RegexBuilder;
+use std::fmt;
+use std::str::FromStr;
+
+const TITLE: &str = "title";
+const DESCRIPTION: &str = "description";
+// spaces are required so patterns can contain `=>`
+const SEPARATOR: &str = " => ";
+// patterns are set by users so compiled regexes are kept small
+const REGEX_SIZE_LIMIT: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewriteField {
+    Title,
+    Description,
+}
+
+impl FromStr for RewriteField {
+    type Err = String;
+
+    fn from_str(field: &str) -> Result<Self, Self::Err> {
+        match field {
+            TITLE => Ok(RewriteField::Title),
+            DESCRIPTION => Ok(RewriteField::Description),
+            _ => Err(format!(
+                "Unknown field {field}. Use {TITLE} or {DESCRIPTION}"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for RewriteField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RewriteField::Title => write!(f, "{TITLE}"),
+            RewriteField::Description => write!(f, "{DESCRIPTION}"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Rewrite {
+    pub field: RewriteField,
+    pub pattern: String,
+    pub replacement: String,
+}
+
+// Parses `field pattern => replacement`. Without the replacement matches are removed
+pub fn parse(rule: &str) -> Result<Rewrite, String> {
+    let (field, rest) = match rule.trim().split_once(' ') {
+        Some((field, rest)) => (field.parse::<RewriteField>()?, rest),
+        None => {
+            return Err(format!(
+                "Rewrite rules should look like: title pattern{SEPARATOR}replacement"
+            ));
+        }
+    };
+
+    let rest = format!(" {} ", rest.trim());
+
+    let (pattern, replacement) = match rest.split_once(SEPARATOR) {
+        Some((pattern, replacement)) => (pattern.trim(), replacement.trim()),
+        None => (rest.trim(), ""),
+    };
+
+    if pattern.is_empty() {
+        return Err("The pattern can not be empty".to_string());
+    }
+
+    compile(pattern)?;
+
+    Ok(Rewrite {
+        field,
+        pattern: pattern.to_string(),
+        replacement: replacement.to_string(),
+    })
+}
+
+pub fn compile(pattern: &str) -> Result<Regex, String> {
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|error| format!("Invalid pattern: {error}"))
+}
+
+pub fn format_rule(rule: &TelegramRewriteRule) -> String {
+    if rule.replacement.is_empty() {
+        format!("{} {}", rule.field, rule.pattern)
+    } else {
+        format!(
+            "{} {}{SEPARATOR}{}",
+            rule.field, rule.pattern, rule.replacement
+        )
+    }
+}
+
+// Rules that can't be compiled anymore are skipped
+pub fn apply(feed_items: Vec<FeedItem>, rules: &[TelegramRewriteRule]) -> Vec<FeedItem> {
+    let compiled_rules: Vec<(RewriteField, Regex, &str)> = rules
+        .iter()
+        .filter_map(|rule| {
+            let field = rule.field.parse::<RewriteField>().ok()?;
+
+            match compile(&rule.pattern) {
+                Ok(regex) => Some((field, regex, rule.replacement.as_str())),
+                Err(error) => {
+                    log::error!("Failed to compile rewrite rule {}: {error}", rule.id);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    if compiled_rules.is_empty() {
+        return feed_items;
+    }
+
+    feed_items
+        .into_iter()
+        .map(|mut item| {
+            for (field, regex, replacement) in &compiled_rules {
+                match field {
+                    RewriteField::Title => {
+                        item.title = regex
+                            .replace_all(&item.title, *replacement)
+                            .trim()
+                            .to_string();
+                    }
+                    RewriteField::Description => {
+                        item.description = item.description.map(|description| {
+                            regex
+                                .replace_all(&description, *replacement)
+                                .trim()
+                                .to_string()
+                        });
+                    }
+                }
+            }
+
+            item
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rewrite;
+    use super::RewriteField;
+    use crate::models::FeedItem;
+    use crate::models::TelegramRewriteRule;
+    use chrono::Utc;
+
+    fn rule(field: &str, pattern: &str, replacement: &str) -> TelegramRewriteRule {
+        TelegramRewriteRule {
+            id: 1,
+            chat_id: 1,
+            feed_id: 1,
+            field: field.to_string(),
+            pattern: pattern.to_string(),
+            replacement: replacement.to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn parse_reads_field_pattern_and_replacement() {
+        assert_eq!(
+            Ok(Rewrite {
+                field: RewriteField::Title,
+                pattern: "^Site: (.*)$".to_string(),
+                replacement: "$1 (Site)".to_string(),
+            }),
+            super::parse("title ^Site: (.*)$ => $1 (Site)")
+        );
+        assert_eq!(
+            Ok(Rewrite {
+                field: RewriteField::Description,
+                pattern: "The post .* appeared first on .*".to_string(),
+                replacement: "".to_string(),
+            }),
+            super::parse("description The post .* appeared first on .*")
+        );
+        assert!(super::parse("author .*").is_err());
+        assert!(super::parse("title (").is_err());
+        assert!(super::parse("title => x").is_err());
+    }
+
+    #[test]
+    fn parse_requires_spaces_around_the_separator() {
+        assert_eq!(
+            Ok(Rewrite {
+                field: RewriteField::Title,
+                pattern: "a=>b".to_string(),
+                replacement: "c => d".to_string(),
+            }),
+            super::parse("title a=>b => c => d")
+        );
+        assert_eq!(
+            Ok(Rewrite {
+                field: RewriteField::Title,
+                pattern: "x=>y".to_string(),
+                replacement: "".to_string(),
+            }),
+            super::parse("title x=>y")
+        );
+        assert_eq!(
+            Ok(Rewrite {
+                field: RewriteField::Title,
+                pattern: "^x".to_string(),
+                replacement: "".to_string(),
+            }),
+            super::parse("title ^x =>")
+        );
+    }
+
+    #[test]
+    fn apply_rewrites_titles_and_descriptions() {
+        let item = FeedItem {
+            feed_id: 1,
+            title: "[Sponsored] Rust 2.0".to_string(),
+            description: Some("News.\nThe post Rust 2.0 appeared first on Blog.".to_string()),
+            link: "https://example.com".to_string(),
+            author: None,
+            guid: None,
+            publication_date: Utc::now(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            content_hash: "hash".to_string(),
+        };
+        let rules = vec![
+            rule("title", "^\\[Sponsored\\]", ""),
+            rule("description", "The post .* appeared first on .*", ""),
+        ];
+
+        let items = super::apply(vec![item], &rules);
+
+        assert_eq!("Rust 2.0", items[0].title);
+        assert_eq!(Some("News.".to_string()), items[0].description);
+    }
+}
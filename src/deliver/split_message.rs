@@ -0,0 +1,421 @@
+// Splits rendered messages that don't fit into one Telegram message.
+// Parts are cut at paragraph, sentence or word boundaries. Tags that are open
+// at the cut are closed at the end of the part and reopened in the next one
+// so every part stays valid for `ParseMode::Html` and `ParseMode::MarkdownV2`
+
+use super::markdown;
+use super::telegram_html;
+
+// Tags and MarkdownV2 entities are handled the same way
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'a> {
+    Opening {
+        name: String,
+        raw: &'a str,
+        closing: String,
+    },
+    Closing {
+        name: String,
+        raw: &'a str,
+    },
+    Text(&'a str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Boundary {
+    Word,
+    Sentence,
+    Line,
+    Paragraph,
+}
+
+#[derive(Debug, Clone)]
+struct OpenTag<'a> {
+    name: String,
+    raw: &'a str,
+    closing: String,
+}
+
+#[derive(Debug, Clone)]
+struct BreakPoint<'a> {
+    // index of the first token of the next part
+    token_idx: usize,
+    // length of the current part before the break
+    len: usize,
+    open_tags: Vec<OpenTag<'a>>,
+}
+
+struct Splitter<'a> {
+    tokens: Vec<Token<'a>>,
+    max_chars: usize,
+    parts: Vec<String>,
+    current: String,
+    current_len: usize,
+    has_text: bool,
+    open_tags: Vec<OpenTag<'a>>,
+    break_points: Vec<(Boundary, BreakPoint<'a>)>,
+}
+
+pub fn split_message(html: &str, max_chars: usize) -> Vec<String> {
+    if html.chars().count() <= max_chars {
+        return vec![html.to_string()];
+    }
+
+    let tokens = telegram_html::tokenize(html)
+        .into_iter()
+        .map(|token| match token {
+            telegram_html::Token::OpeningTag { name, raw } => Token::Opening {
+                closing: format!("</{name}>"),
+                name,
+                raw,
+            },
+            telegram_html::Token::ClosingTag { name, raw } => Token::Closing { name, raw },
+            telegram_html::Token::Text(text) => Token::Text(text),
+        })
+        .collect();
+
+    split(tokens, max_chars)
+}
+
+pub fn split_markdown(text: &str, max_chars: usize) -> Vec<String> {
+    if text.chars().count() <= max_chars {
+        return vec![text.to_string()];
+    }
+
+    let tokens = markdown::tokenize(text)
+        .into_iter()
+        .map(|token| match token {
+            markdown::Token::Opening {
+                delimiter,
+                raw,
+                closing,
+            } => Token::Opening {
+                name: delimiter.to_string(),
+                raw,
+                closing,
+            },
+            markdown::Token::Closing { delimiter, raw } => Token::Closing {
+                name: delimiter.to_string(),
+                raw,
+            },
+            markdown::Token::Text(text) => Token::Text(text),
+        })
+        .collect();
+
+    split(tokens, max_chars)
+}
+
+fn split(tokens: Vec<Token<'_>>, max_chars: usize) -> Vec<String> {
+    Splitter {
+        tokens,
+        max_chars,
+        parts: vec![],
+        current: String::new(),
+        current_len: 0,
+        has_text: false,
+        open_tags: vec![],
+        break_points: vec![],
+    }
+    .split()
+}
+
+impl<'a> Splitter<'a> {
+    fn split(mut self) -> Vec<String> {
+        let mut idx = 0;
+
+        while idx < self.tokens.len() {
+            let token = self.tokens[idx].clone();
+            let token_len = token_len(&token);
+
+            if self.has_text && self.current_len + token_len + self.closing_len() > self.max_chars {
+                idx = self.cut(idx);
+                continue;
+            }
+
+            match token {
+                Token::Opening { name, raw, closing } => {
+                    self.open_tags.push(OpenTag { name, raw, closing });
+                    self.push(raw);
+                }
+                Token::Closing { name, raw } => {
+                    if let Some(position) = self.open_tags.iter().rposition(|tag| tag.name == name)
+                    {
+                        self.open_tags.truncate(position);
+                    }
+
+                    self.push(raw);
+                }
+                Token::Text(text) => {
+                    // parts don't start with whitespace
+                    if !self.has_text && text.trim().is_empty() {
+                        idx += 1;
+                        continue;
+                    }
+
+                    self.push(text);
+                    self.has_text = true;
+
+                    if let Some(boundary) = self.boundary_after(idx) {
+                        let break_point = BreakPoint {
+                            token_idx: idx + 1,
+                            len: self.current_len,
+                            open_tags: self.open_tags.clone(),
+                        };
+
+                        self.break_points.push((boundary, break_point));
+                    }
+                }
+            }
+
+            idx += 1;
+        }
+
+        if self.has_text {
+            let part = self.current.trim_end().to_string();
+            self.parts.push(part);
+        }
+
+        self.parts
+    }
+
+    // Saves the current part and returns the index of the token the next part starts with
+    fn cut(&mut self, idx: usize) -> usize {
+        let break_point = self.best_break_point().unwrap_or(BreakPoint {
+            token_idx: idx,
+            len: self.current_len,
+            open_tags: self.open_tags.clone(),
+        });
+
+        let mut part: String = self.current.chars().take(break_point.len).collect();
+        part.truncate(part.trim_end().len());
+
+        for tag in break_point.open_tags.iter().rev() {
+            part.push_str(&tag.closing);
+        }
+
+        self.parts.push(part);
+
+        self.current = String::new();
+        self.current_len = 0;
+        self.has_text = false;
+        self.break_points = vec![];
+        self.open_tags = break_point.open_tags;
+
+        for raw in self.open_tags.iter().map(|tag| tag.raw).collect::<Vec<_>>() {
+            self.push(raw);
+        }
+
+        break_point.token_idx
+    }
+
+    // The latest break point of the strongest boundary in the second half of the part.
+    // Falls back to weaker boundaries so parts are not too short
+    fn best_break_point(&self) -> Option<BreakPoint<'a>> {
+        let min_len = self.max_chars / 2;
+
+        for boundary in [
+            Boundary::Paragraph,
+            Boundary::Line,
+            Boundary::Sentence,
+            Boundary::Word,
+        ] {
+            let break_point = self
+                .break_points
+                .iter()
+                .rev()
+                .find(|(point_boundary, point)| {
+                    *point_boundary >= boundary && point.len >= min_len
+                });
+
+            if let Some((_, break_point)) = break_point {
+                return Some(break_point.clone());
+            }
+        }
+
+        self.break_points
+            .last()
+            .map(|(_, break_point)| break_point.clone())
+    }
+
+    fn boundary_after(&self, idx: usize) -> Option<Boundary> {
+        let text = match &self.tokens[idx] {
+            Token::Text(text) => *text,
+            _ => return None,
+        };
+
+        if !text.chars().all(char::is_whitespace) {
+            return None;
+        }
+
+        if text == "\n" {
+            let previous_newline = idx > 0 && self.tokens[idx - 1] == Token::Text("\n");
+
+            if previous_newline {
+                return Some(Boundary::Paragraph);
+            }
+
+            return Some(Boundary::Line);
+        }
+
+        let previous_text = self.tokens[..idx]
+            .iter()
+            .rev()
+            .find_map(|token| match token {
+                Token::Text(text) => Some(*text),
+                _ => None,
+            });
+
+        // escaped punctuation ends sentences in MarkdownV2
+        match previous_text.map(|text| text.trim_start_matches('\\')) {
+            Some(".") | Some("!") | Some("?") | Some("…") => Some(Boundary::Sentence),
+            _ => Some(Boundary::Word),
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        self.current.push_str(text);
+        self.current_len += text.chars().count();
+    }
+
+    fn closing_len(&self) -> usize {
+        self.open_tags
+            .iter()
+            .map(|tag| tag.closing.chars().count())
+            .sum()
+    }
+}
+
+fn token_len(token: &Token) -> usize {
+    match token {
+        Token::Opening { raw, .. } | Token::Closing { raw, .. } | Token::Text(raw) => {
+            raw.chars().count()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_markdown;
+    use super::split_message;
+
+    #[test]
+    fn it_does_not_split_short_messages() {
+        let message = "<b>Title</b>\n\nDescription";
+
+        assert_eq!(vec![message.to_string()], split_message(message, 100));
+    }
+
+    #[test]
+    fn it_splits_at_paragraphs() {
+        let message = format!("{}\n\n{}", "a".repeat(60), "b".repeat(60));
+
+        assert_eq!(
+            vec!["a".repeat(60), "b".repeat(60)],
+            split_message(&message, 100)
+        );
+    }
+
+    #[test]
+    fn it_splits_at_sentences_and_words() {
+        let message = "First sentence is here. Second sentence is here and it's long";
+
+        assert_eq!(
+            vec![
+                "First sentence is here.".to_string(),
+                "Second sentence is here and it's long".to_string()
+            ],
+            split_message(message, 40)
+        );
+
+        let message = "word ".repeat(10);
+
+        assert_eq!(
+            vec![
+                "word word word word".to_string(),
+                "word word word word".to_string(),
+                "word word".to_string()
+            ],
+            split_message(&message, 20)
+        );
+    }
+
+    #[test]
+    fn it_closes_and_reopens_tags() {
+        let message = format!(
+            "<b>Title</b>\n\n<a href=\"https://example.com\"><i>{}\n\n{}</i></a>",
+            "a".repeat(40),
+            "b".repeat(40)
+        );
+
+        let parts = split_message(&message, 100);
+
+        assert_eq!(
+            vec![
+                format!(
+                    "<b>Title</b>\n\n<a href=\"https://example.com\"><i>{}</i></a>",
+                    "a".repeat(40)
+                ),
+                format!(
+                    "<a href=\"https://example.com\"><i>{}</i></a>",
+                    "b".repeat(40)
+                ),
+            ],
+            parts
+        );
+
+        for part in parts {
+            assert!(part.chars().count() <= 100);
+        }
+    }
+
+    #[test]
+    fn it_does_not_cut_entities() {
+        let message = format!("{}&amp;&amp;", "a".repeat(8));
+
+        assert_eq!(
+            vec![format!("{}&amp;", "a".repeat(8)), "&amp;".to_string()],
+            split_message(&message, 13)
+        );
+    }
+
+    #[test]
+    fn it_splits_markdown_without_breaking_entities() {
+        let message = format!(
+            "*Title*\n\n[_{}\n\n{}_](https://example.com/\\))",
+            "a\\.".repeat(20),
+            "b\\.".repeat(20)
+        );
+
+        let parts = split_markdown(&message, 100);
+
+        assert_eq!(
+            vec![
+                format!(
+                    "*Title*\n\n[_{}_](https://example.com/\\))",
+                    "a\\.".repeat(20)
+                ),
+                format!("[_{}_](https://example.com/\\))", "b\\.".repeat(20)),
+            ],
+            parts
+        );
+
+        for part in parts {
+            assert!(part.chars().count() <= 100);
+        }
+    }
+
+    #[test]
+    fn it_does_not_cut_markdown_escapes() {
+        let message = "word\\. ".repeat(10);
+
+        assert_eq!(
+            vec![
+                "word\\. word\\. word\\.".to_string(),
+                "word\\. word\\. word\\.".to_string(),
+                "word\\. word\\. word\\.".to_string(),
+                "word\\.".to_string()
+            ],
+            split_markdown(&message, 21)
+        );
+    }
+}
@@ -0,0 +1,290 @@
+// Telegram supports only a small subset of HTML
+// https://core.telegram.org/bots/api#html-style
+
+const SUPPORTED_TAGS: [&str; 15] = [
+    "a",
+    "b",
+    "blockquote",
+    "code",
+    "del",
+    "em",
+    "i",
+    "ins",
+    "pre",
+    "s",
+    "span",
+    "strike",
+    "strong",
+    "tg-emoji",
+    "tg-spoiler",
+];
+const SUPPORTED_ENTITIES: [&str; 4] = ["lt", "gt", "amp", "quot"];
+const CONTEXT_CHARS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token<'a> {
+    OpeningTag { name: String, raw: &'a str },
+    ClosingTag { name: String, raw: &'a str },
+    Text(&'a str),
+}
+
+// Checks that Telegram can parse the message with `ParseMode::Html`.
+// Returns the description of the first problem
+pub fn validate(html: &str) -> Result<(), String> {
+    let tokens = tokenize(html);
+    let mut open_tags: Vec<&str> = vec![];
+    let mut offset = 0;
+
+    for token in &tokens {
+        match token {
+            Token::OpeningTag { name, raw } => {
+                validate_opening_tag(name, raw)?;
+
+                open_tags.push(raw);
+            }
+            Token::ClosingTag { name, raw } => {
+                if !SUPPORTED_TAGS.contains(&name.as_str()) {
+                    return Err(format!("Unsupported tag {raw}"));
+                }
+
+                match open_tags.pop() {
+                    None => return Err(format!("Tag {raw} doesn't have an opening tag")),
+                    Some(opening_tag) => {
+                        if tag_name(opening_tag) != name {
+                            return Err(format!("Tag {opening_tag} is closed by {raw}"));
+                        }
+                    }
+                }
+            }
+            Token::Text("<") => {
+                return Err(format!(
+                    "Unescaped `<` near \"{}\". Use &lt; instead",
+                    context(html, offset)
+                ));
+            }
+            Token::Text("&") => {
+                return Err(format!(
+                    "Unescaped `&` near \"{}\". Use &amp; instead",
+                    context(html, offset)
+                ));
+            }
+            Token::Text(text) => {
+                if let Some(name) = text.strip_prefix('&').and_then(|t| t.strip_suffix(';')) {
+                    if !supported_entity(name) {
+                        return Err(format!("Unsupported entity {text}"));
+                    }
+                }
+            }
+        }
+
+        offset += token_raw(token).len();
+    }
+
+    match open_tags.last() {
+        Some(tag) => Err(format!("Tag {tag} is not closed")),
+        None => Ok(()),
+    }
+}
+
+// Converts the message to plain text that can be sent with `ParseMode::Html`.
+// Tags are shown as is, supported entities are kept
+pub fn escape(html: &str) -> String {
+    tokenize(html)
+        .into_iter()
+        .map(|token| match token {
+            Token::Text(text) if text.starts_with('&') && text.len() > 1 => {
+                let name = &text[1..text.len() - 1];
+
+                if supported_entity(name) {
+                    text.to_string()
+                } else {
+                    escape_text(text)
+                }
+            }
+            token => escape_text(token_raw(&token)),
+        })
+        .collect()
+}
+
+// Escapes all html special characters
+pub fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn validate_opening_tag(name: &str, raw: &str) -> Result<(), String> {
+    if !SUPPORTED_TAGS.contains(&name) {
+        return Err(format!("Unsupported tag {raw}"));
+    }
+
+    let required_attribute = match name {
+        "a" => Some("href="),
+        "tg-emoji" => Some("emoji-id="),
+        "span" => Some("class=\"tg-spoiler\""),
+        _ => None,
+    };
+
+    match required_attribute {
+        Some(attribute) if !raw.contains(attribute) => {
+            Err(format!("Tag {raw} must have the {attribute} attribute"))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn supported_entity(name: &str) -> bool {
+    if SUPPORTED_ENTITIES.contains(&name) {
+        return true;
+    }
+
+    match name.strip_prefix('#') {
+        Some(number) => match number.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()),
+        },
+        None => false,
+    }
+}
+
+fn context(html: &str, offset: usize) -> String {
+    html[offset..].chars().take(CONTEXT_CHARS).collect()
+}
+
+fn tag_name(raw: &str) -> &str {
+    raw[1..]
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+        .next()
+        .unwrap_or_default()
+}
+
+// Text is split into single characters and entities
+pub fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut idx = 0;
+
+    while idx < html.len() {
+        let rest = &html[idx..];
+
+        let token = parse_tag(rest)
+            .or_else(|| parse_entity(rest))
+            .unwrap_or_else(|| {
+                let char_len = rest.chars().next().map(char::len_utf8).unwrap_or(1);
+
+                Token::Text(&rest[..char_len])
+            });
+
+        idx += token_raw(&token).len();
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+fn token_raw<'a>(token: &Token<'a>) -> &'a str {
+    match token {
+        Token::OpeningTag { raw, .. } | Token::ClosingTag { raw, .. } | Token::Text(raw) => raw,
+    }
+}
+
+fn parse_tag(text: &str) -> Option<Token<'_>> {
+    if !text.starts_with('<') {
+        return None;
+    }
+
+    let end = text.find('>')?;
+    let raw = &text[..=end];
+    let closing = raw.starts_with("</");
+    let name_start = if closing { 2 } else { 1 };
+
+    let name: String = raw[name_start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+
+    if name.is_empty() || raw[name_start..].starts_with(|c: char| !c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    if closing {
+        Some(Token::ClosingTag { name, raw })
+    } else {
+        Some(Token::OpeningTag { name, raw })
+    }
+}
+
+fn parse_entity(text: &str) -> Option<Token<'_>> {
+    if !text.starts_with('&') {
+        return None;
+    }
+
+    let end = text.char_indices().take(10).find(|(_, c)| *c == ';')?.0;
+    let name = &text[1..end];
+
+    let valid = !name.is_empty()
+        && (name.chars().all(|c| c.is_ascii_alphanumeric())
+            || (name.starts_with('#') && name[1..].chars().all(|c| c.is_ascii_alphanumeric())));
+
+    if valid {
+        Some(Token::Text(&text[..=end]))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+    use super::validate;
+
+    #[test]
+    fn it_accepts_supported_html() {
+        let message = "<b>Title</b>\n\n<a href=\"https://example.com\"><i>link</i></a> &lt;3 &#8217; &#x2019;\n<span class=\"tg-spoiler\">spoiler</span>";
+
+        assert_eq!(Ok(()), validate(message));
+    }
+
+    #[test]
+    fn it_reports_problems() {
+        assert_eq!(
+            Err("Tag <b> is not closed".to_string()),
+            validate("<b>Title")
+        );
+        assert_eq!(
+            Err("Tag <i> is closed by </b>".to_string()),
+            validate("<b><i>Title</b></i>")
+        );
+        assert_eq!(
+            Err("Tag </b> doesn't have an opening tag".to_string()),
+            validate("Title</b>")
+        );
+        assert_eq!(
+            Err("Unsupported tag <div>".to_string()),
+            validate("<div>Title</div>")
+        );
+        assert_eq!(
+            Err("Tag <a> must have the href= attribute".to_string()),
+            validate("<a>Title</a>")
+        );
+        assert_eq!(
+            Err("Unsupported entity &nbsp;".to_string()),
+            validate("Title&nbsp;")
+        );
+        assert_eq!(
+            Err("Unescaped `<` near \"< 3 and 2 > 1\". Use &lt; instead".to_string()),
+            validate("1 < 3 and 2 > 1")
+        );
+        assert_eq!(
+            Err("Unescaped `&` near \"& Co\". Use &amp; instead".to_string()),
+            validate("Smith & Co")
+        );
+    }
+
+    #[test]
+    fn it_escapes_html() {
+        assert_eq!(
+            "&lt;b&gt;Smith &amp; Co &amp;nbsp; &amp;&lt;/i&gt;",
+            escape("<b>Smith & Co &nbsp; &amp;</i>")
+        );
+    }
+}
@@ -0,0 +1,131 @@
+// Chats either have a named IANA timezone or a fixed offset in minutes.
+// Named timezones follow daylight saving time so the offset depends on the date
+
+use chrono::{DateTime, FixedOffset, Offset, Utc};
+use chrono_tz::{TZ_VARIANTS, Tz};
+
+pub const COMMON_TIMEZONES: [&str; 12] = [
+    "America/Los_Angeles",
+    "America/New_York",
+    "America/Sao_Paulo",
+    "Europe/London",
+    "Europe/Berlin",
+    "Europe/Kyiv",
+    "Europe/Moscow",
+    "Asia/Dubai",
+    "Asia/Kolkata",
+    "Asia/Shanghai",
+    "Asia/Tokyo",
+    "Australia/Sydney",
+];
+const MAX_SEARCH_RESULTS: usize = 10;
+
+// Names are matched ignoring case. Spaces can be used instead of underscores
+pub fn find(name: &str) -> Option<Tz> {
+    let name = name.trim().replace(' ', "_");
+
+    TZ_VARIANTS
+        .iter()
+        .find(|timezone| timezone.name().eq_ignore_ascii_case(&name))
+        .copied()
+}
+
+// Returns timezones with the query in their name, for example a city
+pub fn search(query: &str) -> Vec<Tz> {
+    let query = query.trim().replace(' ', "_").to_lowercase();
+
+    if query.is_empty() {
+        return vec![];
+    }
+
+    TZ_VARIANTS
+        .iter()
+        .filter(|timezone| timezone.name().to_lowercase().contains(&query))
+        .take(MAX_SEARCH_RESULTS)
+        .copied()
+        .collect()
+}
+
+pub fn parse_offset(value: &str) -> Result<i32, String> {
+    let offset = match value.parse::<i32>() {
+        Ok(result) => result,
+        Err(_) => return Err("The value is not a number".to_string()),
+    };
+
+    if offset % 30 != 0 {
+        return Err("Offset must be divisible by 30".to_string());
+    }
+
+    if !(-720..=840).contains(&offset) {
+        return Err("Offset must be >= -720 (UTC -12) and <= 840 (UTC +14)".to_string());
+    }
+
+    Ok(offset)
+}
+
+// The named timezone is used if it's set, otherwise the fixed offset
+pub fn offset_at(
+    timezone: Option<&str>,
+    offset_minutes: Option<i32>,
+    date: DateTime<Utc>,
+) -> FixedOffset {
+    // stored names are canonical so they are parsed directly instead of searched
+    if let Some(timezone) = timezone.and_then(|name| name.parse::<Tz>().ok()) {
+        return date.with_timezone(&timezone).offset().fix();
+    }
+
+    FixedOffset::east_opt(offset_minutes.unwrap_or(0) * 60).unwrap_or(Utc.fix())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn find_ignores_case_and_spaces() {
+        assert_eq!(
+            Some(chrono_tz::Europe::Berlin),
+            super::find("europe/berlin")
+        );
+        assert_eq!(
+            Some(chrono_tz::America::New_York),
+            super::find("America/New York")
+        );
+        assert_eq!(None, super::find("Europe/Atlantis"));
+    }
+
+    #[test]
+    fn search_finds_timezones_by_city() {
+        assert_eq!(vec![chrono_tz::Europe::Berlin], super::search("berlin"));
+        assert!(super::search("atlantis").is_empty());
+        assert!(super::search(" ").is_empty());
+    }
+
+    #[test]
+    fn offset_at_follows_daylight_saving_time() {
+        let winter = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2024, 7, 15, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            3600,
+            super::offset_at(Some("Europe/Berlin"), Some(600), winter).local_minus_utc()
+        );
+        assert_eq!(
+            7200,
+            super::offset_at(Some("Europe/Berlin"), None, summer).local_minus_utc()
+        );
+        assert_eq!(
+            -5400,
+            super::offset_at(None, Some(-90), summer).local_minus_utc()
+        );
+        assert_eq!(0, super::offset_at(None, None, summer).local_minus_utc());
+    }
+
+    #[test]
+    fn parse_offset_validates_minutes() {
+        assert_eq!(Ok(600), super::parse_offset("600"));
+        assert!(super::parse_offset("45").is_err());
+        assert!(super::parse_offset("900").is_err());
+        assert!(super::parse_offset("Berlin").is_err());
+    }
+}
@@ -1,9 +1,19 @@
 pub mod feed;
 pub mod feed_item;
+pub mod sync_host;
+pub mod telegram_bookmark;
 pub mod telegram_chat;
+pub mod telegram_delivered_item;
+pub mod telegram_outbox_message;
+pub mod telegram_rewrite_rule;
 pub mod telegram_subscription;
 
 pub use feed::Feed;
 pub use feed_item::FeedItem;
+pub use sync_host::SyncHost;
+pub use telegram_bookmark::TelegramBookmark;
 pub use telegram_chat::TelegramChat;
+pub use telegram_delivered_item::TelegramDeliveredItem;
+pub use telegram_outbox_message::TelegramOutboxMessage;
+pub use telegram_rewrite_rule::TelegramRewriteRule;
 pub use telegram_subscription::TelegramSubscription;
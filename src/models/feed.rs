@@ -1,7 +1,7 @@
 use crate::schema::feeds;
 use chrono::{DateTime, Utc};
 
-#[derive(Queryable, Identifiable, Debug, Eq, PartialEq)]
+#[derive(Queryable, Identifiable, Debug, Clone, Eq, PartialEq)]
 #[diesel(table_name = feeds)]
 pub struct Feed {
     pub id: i64,
@@ -20,4 +20,7 @@ pub struct Feed {
     pub sync_skips: i32,
 
     pub content_fields: Option<Vec<String>>,
+
+    pub fetch_options: Option<String>,
+    pub fetch_options_hash: String,
 }
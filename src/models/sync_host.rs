@@ -0,0 +1,13 @@
+use crate::schema::sync_hosts;
+use chrono::{DateTime, Utc};
+
+#[derive(Queryable, Identifiable, Debug, Eq, PartialEq)]
+#[diesel(table_name = sync_hosts, primary_key(host))]
+pub struct SyncHost {
+    pub host: String,
+    pub next_request_at: DateTime<Utc>,
+    pub backoff_until: Option<DateTime<Utc>>,
+    pub backoff_count: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
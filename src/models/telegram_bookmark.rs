@@ -0,0 +1,17 @@
+use crate::schema::telegram_bookmarks;
+use chrono::{DateTime, Utc};
+
+#[derive(Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = telegram_bookmarks)]
+pub struct TelegramBookmark {
+    pub id: i64,
+    pub chat_id: i64,
+    pub feed_link: String,
+    pub title: String,
+    pub link: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub publication_date: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -18,4 +18,21 @@ pub struct TelegramChat {
     pub filter_words: Option<Vec<String>>,
     pub preview_enabled: bool,
     pub command: Option<String>,
+    pub parse_mode: String,
+    pub quiet_hours_start: Option<i32>,
+    pub quiet_hours_end: Option<i32>,
+    pub quiet_hours_hold: bool,
+    pub selected_chat_id: Option<i64>,
+    pub topic_per_feed: bool,
+    // items of all subscriptions are sent in publication order
+    pub interleave_items: bool,
+    // items already delivered within this number of days are skipped
+    pub dedup_days: Option<i32>,
+    pub link_rules: Option<Vec<String>>,
+    // IANA timezone name, it takes precedence over utc_offset_minutes
+    pub timezone: Option<String>,
+    // strftime pattern used for bot_date
+    pub date_format: Option<String>,
+    // language of month and weekday names in dates
+    pub date_language: Option<String>,
 }
@@ -0,0 +1,16 @@
+use crate::schema::telegram_delivered_items;
+use chrono::{DateTime, Utc};
+
+#[derive(Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = telegram_delivered_items, primary_key(chat_id, telegram_message_id))]
+pub struct TelegramDeliveredItem {
+    pub chat_id: i64,
+    pub telegram_message_id: i32,
+    pub feed_id: i64,
+    pub title: String,
+    pub link: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub publication_date: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
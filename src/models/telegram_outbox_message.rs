@@ -0,0 +1,24 @@
+use crate::schema::telegram_outbox;
+use chrono::{DateTime, Utc};
+
+#[derive(Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = telegram_outbox)]
+pub struct TelegramOutboxMessage {
+    pub id: i64,
+    pub chat_id: i64,
+    pub feed_id: i64,
+    pub idempotency_key: String,
+    pub message: String,
+    pub thread_id: Option<i32>,
+    pub preview_enabled: bool,
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub telegram_message_id: Option<i32>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub parse_mode: String,
+    pub published_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,16 @@
+use crate::schema::telegram_rewrite_rules;
+use chrono::{DateTime, Utc};
+
+#[derive(Queryable, Identifiable, Debug, Clone)]
+#[diesel(table_name = telegram_rewrite_rules)]
+pub struct TelegramRewriteRule {
+    pub id: i64,
+    pub chat_id: i64,
+    pub feed_id: i64,
+    // title or description
+    pub field: String,
+    pub pattern: String,
+    pub replacement: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
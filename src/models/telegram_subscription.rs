@@ -18,4 +18,18 @@ pub struct TelegramSubscription {
     pub has_updates: bool,
     pub external_id: Uuid,
     pub thread_id: Option<i32>,
+    pub split_messages: bool,
+    pub item_buttons: Option<Vec<String>>,
+    pub muted: bool,
+    pub excluded_authors: Option<Vec<String>>,
+    pub silent: bool,
+    pub paused_at: Option<DateTime<Utc>>,
+    // snoozed subscriptions are resumed after this time
+    pub paused_until: Option<DateTime<Utc>>,
+    // items delivered in one run
+    pub messages_limit: Option<i32>,
+    // items over the limit are folded into a summary
+    pub hourly_limit: Option<i32>,
+    // used instead of link rules of the chat
+    pub link_rules: Option<Vec<String>>,
 }
@@ -27,6 +27,43 @@ table! {
         sync_retries -> Int4,
         sync_skips -> Int4,
         content_fields -> Nullable<Array<Text>>,
+        fetch_options -> Nullable<Text>,
+        fetch_options_hash -> Text,
+    }
+}
+
+table! {
+    sync_host_tokens (id) {
+        id -> Int8,
+        host -> Text,
+        expires_at -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    sync_hosts (host) {
+        host -> Text,
+        next_request_at -> Timestamptz,
+        backoff_until -> Nullable<Timestamptz>,
+        backoff_count -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+table! {
+    telegram_bookmarks (id) {
+        id -> Int8,
+        chat_id -> Int8,
+        feed_link -> Text,
+        title -> Text,
+        link -> Text,
+        description -> Nullable<Text>,
+        author -> Nullable<Text>,
+        publication_date -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -45,6 +82,95 @@ table! {
         filter_words -> Nullable<Array<Text>>,
         preview_enabled -> Bool,
         command -> Nullable<Text>,
+        parse_mode -> Text,
+        quiet_hours_start -> Nullable<Int4>,
+        quiet_hours_end -> Nullable<Int4>,
+        quiet_hours_hold -> Bool,
+        selected_chat_id -> Nullable<Int8>,
+        topic_per_feed -> Bool,
+        interleave_items -> Bool,
+        dedup_days -> Nullable<Int4>,
+        link_rules -> Nullable<Array<Text>>,
+        timezone -> Nullable<Text>,
+        date_format -> Nullable<Text>,
+        date_language -> Nullable<Text>,
+    }
+}
+
+table! {
+    telegram_dedup_fingerprints (chat_id, fingerprint) {
+        chat_id -> Int8,
+        fingerprint -> Text,
+        delivered_at -> Timestamptz,
+    }
+}
+
+table! {
+    telegram_delivered_items (chat_id, telegram_message_id) {
+        chat_id -> Int8,
+        telegram_message_id -> Int4,
+        feed_id -> Int8,
+        title -> Text,
+        link -> Text,
+        description -> Nullable<Text>,
+        author -> Nullable<Text>,
+        publication_date -> Timestamptz,
+        created_at -> Timestamptz,
+    }
+}
+
+table! {
+    telegram_linked_chats (id) {
+        id -> Int8,
+        chat_id -> Int8,
+        linked_chat_id -> Int8,
+        admin_checked_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+table! {
+    telegram_outbox (id) {
+        id -> Int8,
+        chat_id -> Int8,
+        feed_id -> Int8,
+        idempotency_key -> Text,
+        message -> Text,
+        thread_id -> Nullable<Int4>,
+        preview_enabled -> Bool,
+        status -> Text,
+        attempts -> Int4,
+        next_attempt_at -> Timestamptz,
+        last_error -> Nullable<Text>,
+        telegram_message_id -> Nullable<Int4>,
+        sent_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        parse_mode -> Text,
+        published_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    telegram_rate_limits (chat_id) {
+        chat_id -> Int8,
+        next_send_at -> Timestamptz,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+table! {
+    telegram_rewrite_rules (id) {
+        id -> Int8,
+        chat_id -> Int8,
+        feed_id -> Int8,
+        field -> Text,
+        pattern -> Text,
+        replacement -> Text,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -60,11 +186,39 @@ table! {
         has_updates -> Bool,
         external_id -> Uuid,
         thread_id -> Nullable<Int4>,
+        split_messages -> Bool,
+        item_buttons -> Nullable<Array<Text>>,
+        muted -> Bool,
+        excluded_authors -> Nullable<Array<Text>>,
+        silent -> Bool,
+        paused_at -> Nullable<Timestamptz>,
+        paused_until -> Nullable<Timestamptz>,
+        messages_limit -> Nullable<Int4>,
+        hourly_limit -> Nullable<Int4>,
+        link_rules -> Nullable<Array<Text>>,
     }
 }
 
 joinable!(feed_items -> feeds (feed_id));
+joinable!(sync_host_tokens -> sync_hosts (host));
+joinable!(telegram_bookmarks -> telegram_chats (chat_id));
+joinable!(telegram_dedup_fingerprints -> telegram_chats (chat_id));
+joinable!(telegram_delivered_items -> telegram_chats (chat_id));
 joinable!(telegram_subscriptions -> feeds (feed_id));
 joinable!(telegram_subscriptions -> telegram_chats (chat_id));
 
-allow_tables_to_appear_in_same_query!(feed_items, feeds, telegram_chats, telegram_subscriptions,);
+allow_tables_to_appear_in_same_query!(
+    feed_items,
+    feeds,
+    sync_host_tokens,
+    sync_hosts,
+    telegram_bookmarks,
+    telegram_chats,
+    telegram_dedup_fingerprints,
+    telegram_delivered_items,
+    telegram_linked_chats,
+    telegram_outbox,
+    telegram_rate_limits,
+    telegram_rewrite_rules,
+    telegram_subscriptions,
+);
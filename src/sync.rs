@@ -1,7 +1,13 @@
+pub mod archive;
+pub mod backfill_feed_job;
+pub mod fetch_options;
+pub mod fetch_policy;
+pub mod host_limiter;
 pub mod reader;
 pub mod sync_feed_job;
 pub mod sync_job;
 
+pub use backfill_feed_job::BackfillFeedJob;
 pub use reader::{FetchedFeed, FetchedFeedItem};
 pub use sync_feed_job::SyncFeedJob;
 pub use sync_job::SyncJob;
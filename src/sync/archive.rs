@@ -0,0 +1,294 @@
+use super::FetchedFeed;
+use super::FetchedFeedItem;
+use super::fetch_options::FetchOptions;
+use super::reader;
+use crate::cleaner::remove_old_items_job::MESSAGES_LIMIT_PER_FEED;
+use crate::config::Config;
+use crate::db::feed_items;
+use crate::models::Feed;
+use diesel::PgConnection;
+use std::collections::HashSet;
+use url::Url;
+
+// RFC 5005: archived feeds link older items with `prev-archive`, paged feeds with `next`
+pub const ARCHIVE_LINK_RELS: [&str; 2] = ["prev-archive", "next"];
+
+const WORDPRESS_GENERATOR: &str = "wordpress";
+const WORDPRESS_PAGE_PARAM: &str = "paged";
+
+// Follows paging links starting from the first page of the feed.
+// WordPress feeds don't advertise paging links but support `?paged=N`
+pub struct ArchiveReader<'a> {
+    pub feed: &'a Feed,
+    pub options: &'a FetchOptions,
+    pub depth: u32,
+    pub max_items: usize,
+}
+
+impl ArchiveReader<'_> {
+    pub fn read(&self, first_page: &FetchedFeed) -> Vec<FetchedFeedItem> {
+        let wordpress = is_wordpress(first_page);
+
+        let mut items: Vec<FetchedFeedItem> = vec![];
+        let mut visited = HashSet::from([self.feed.link.clone()]);
+        let mut current_url = self.feed.link.clone();
+        let mut next_page_link = first_page.next_page_link.clone();
+
+        for page_number in 2..self.depth + 2 {
+            if items.len() >= self.max_items {
+                break;
+            }
+
+            let next_url =
+                match self.next_url(&current_url, &next_page_link, wordpress, page_number) {
+                    Some(next_url) => next_url,
+                    None => break,
+                };
+
+            if !visited.insert(next_url.clone()) {
+                break;
+            }
+
+            let page = match self.read_page(&next_url) {
+                Ok(page) => page,
+                Err(error) => {
+                    log::info!(
+                        "Stopped reading archive of {} at {next_url}: {error}",
+                        self.feed.link
+                    );
+                    break;
+                }
+            };
+
+            if page.items.is_empty() {
+                break;
+            }
+
+            items.extend(page.items);
+            next_page_link = page.next_page_link;
+            current_url = next_url;
+        }
+
+        items.truncate(self.max_items);
+        items
+    }
+
+    fn read_page(&self, url: &str) -> Result<FetchedFeed, String> {
+        let data = reader::read_url_with_options(url, self.options).map_err(|error| error.msg)?;
+
+        reader::parse_feed(url, &self.feed.feed_type, &data).map_err(|error| error.msg)
+    }
+
+    fn next_url(
+        &self,
+        current_url: &str,
+        next_page_link: &Option<String>,
+        wordpress: bool,
+        page_number: u32,
+    ) -> Option<String> {
+        if let Some(link) = next_page_link {
+            let next_url = Url::parse(current_url).ok()?.join(link).ok()?;
+
+            return Some(next_url.to_string());
+        }
+
+        if wordpress {
+            return wordpress_page_url(&self.feed.link, page_number);
+        }
+
+        None
+    }
+}
+
+fn is_wordpress(feed: &FetchedFeed) -> bool {
+    feed.generator
+        .as_ref()
+        .is_some_and(|generator| generator.to_lowercase().contains(WORDPRESS_GENERATOR))
+}
+
+fn wordpress_page_url(link: &str, page_number: u32) -> Option<String> {
+    let mut url = Url::parse(link).ok()?;
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| name != WORDPRESS_PAGE_PARAM)
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(WORDPRESS_PAGE_PARAM, &page_number.to_string());
+
+    Some(url.to_string())
+}
+
+// Backfills a feed with older items. Returns the number of saved items
+pub fn backfill(conn: &mut PgConnection, feed: &Feed) -> Result<usize, String> {
+    let options = FetchOptions::from_feed(feed).map_err(|error| error.to_string())?;
+
+    let data = reader::read_url_with_options(&feed.link, &options).map_err(|error| error.msg)?;
+    let first_page =
+        reader::parse_feed(&feed.link, &feed.feed_type, &data).map_err(|error| error.msg)?;
+
+    let items_count = feed_items::count(conn, feed.id).map_err(|error| error.to_string())?;
+    let max_items = (MESSAGES_LIMIT_PER_FEED - items_count).max(0) as usize;
+
+    let items = ArchiveReader {
+        feed,
+        options: &options,
+        depth: Config::sync_archive_depth(),
+        max_items,
+    }
+    .read(&first_page);
+
+    if items.is_empty() {
+        return Ok(0);
+    }
+
+    feed_items::create_archived(conn, feed, items)
+        .map(|items| items.len())
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArchiveReader;
+    use crate::db;
+    use crate::db::feeds;
+    use crate::sync::fetch_options::FetchOptions;
+    use crate::sync::reader;
+    use diesel::Connection;
+    use std::fs;
+
+    fn allow_local_feeds() {
+        unsafe {
+            std::env::set_var("FETCH_ALLOWED_HOSTS", "127.0.0.1");
+        }
+    }
+
+    fn fixture(name: &str, server_url: &str) -> String {
+        fs::read_to_string(format!("./tests/support/{name}"))
+            .unwrap()
+            .replace("{server_url}", server_url)
+    }
+
+    #[test]
+    fn it_follows_rfc_5005_archive_links() {
+        let mut server = mockito::Server::new();
+        allow_local_feeds();
+
+        let url = server.url();
+
+        let _feed = server
+            .mock("GET", "/feed")
+            .with_status(200)
+            .with_body(fixture("atom_archive_page1.xml", &url))
+            .create();
+        let _page2 = server
+            .mock("GET", "/archive/2")
+            .with_status(200)
+            .with_body(fixture("atom_archive_page2.xml", &url))
+            .create();
+        let _page3 = server
+            .mock("GET", "/archive/3")
+            .with_status(200)
+            .with_body(fixture("atom_archive_page3.xml", &url))
+            .create();
+
+        let mut connection = db::establish_test_connection();
+        let link = format!("{url}/feed");
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let feed = feeds::create(connection, &link, "atom".to_string()).unwrap();
+            let options = FetchOptions::default();
+
+            let data = reader::read_url(&link).unwrap();
+            let first_page = reader::parse_feed(&link, "atom", &data).unwrap();
+
+            assert_eq!(Some(format!("{url}/archive/2")), first_page.next_page_link);
+
+            let items = ArchiveReader {
+                feed: &feed,
+                options: &options,
+                depth: 5,
+                max_items: 100,
+            }
+            .read(&first_page);
+
+            let titles: Vec<&str> = items.iter().map(|item| item.title.as_str()).collect();
+
+            assert_eq!(vec!["Item 2", "Item 3"], titles);
+
+            let items = ArchiveReader {
+                feed: &feed,
+                options: &options,
+                depth: 1,
+                max_items: 100,
+            }
+            .read(&first_page);
+
+            assert_eq!(1, items.len());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn it_pages_wordpress_feeds() {
+        let mut server = mockito::Server::new();
+        allow_local_feeds();
+
+        let url = server.url();
+
+        let _page2 = server
+            .mock("GET", "/feed?paged=2")
+            .with_status(200)
+            .with_body(fixture("rss_wordpress_page2.xml", &url))
+            .create();
+        let _page3 = server
+            .mock("GET", "/feed?paged=3")
+            .with_status(404)
+            .create();
+        let _feed = server
+            .mock("GET", "/feed")
+            .with_status(200)
+            .with_body(fixture("rss_wordpress_page1.xml", &url))
+            .create();
+
+        let mut connection = db::establish_test_connection();
+        let link = format!("{url}/feed");
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let feed = feeds::create(connection, &link, "rss".to_string()).unwrap();
+            let options = FetchOptions::default();
+
+            let data = reader::read_url(&link).unwrap();
+            let first_page = reader::parse_feed(&link, "rss", &data).unwrap();
+
+            let items = ArchiveReader {
+                feed: &feed,
+                options: &options,
+                depth: 5,
+                max_items: 100,
+            }
+            .read(&first_page);
+
+            let titles: Vec<&str> = items.iter().map(|item| item.title.as_str()).collect();
+
+            assert_eq!(vec!["Old post"], titles);
+
+            let items = ArchiveReader {
+                feed: &feed,
+                options: &options,
+                depth: 5,
+                max_items: 0,
+            }
+            .read(&first_page);
+
+            assert!(items.is_empty());
+
+            Ok(())
+        });
+    }
+}
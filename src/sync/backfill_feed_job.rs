@@ -0,0 +1,55 @@
+use crate::db::feeds;
+use crate::sync::archive;
+use fang::FangError;
+use fang::Queueable;
+use fang::Runnable;
+use fang::typetag;
+use serde::{Deserialize, Serialize};
+
+// Reads older pages of a new feed after its first sync is saved.
+// Paging can take a while so it doesn't block the sync or the /subscribe command
+#[derive(Serialize, Deserialize)]
+pub struct BackfillFeedJob {
+    pub feed_id: i64,
+}
+
+impl BackfillFeedJob {
+    pub fn new(feed_id: i64) -> Self {
+        Self { feed_id }
+    }
+
+    pub fn run(&self) -> Result<(), FangError> {
+        let conn = &mut crate::db::pool().get()?;
+
+        let feed = match feeds::find(conn, self.feed_id) {
+            Some(feed) => feed,
+            None => return Ok(()),
+        };
+
+        match archive::backfill(conn, &feed) {
+            Ok(count) => log::info!("Saved {count} archived items of {}", feed.link),
+            Err(error) => log::error!("Failed to backfill feed {}: {error}", self.feed_id),
+        }
+
+        Ok(())
+    }
+}
+
+#[typetag::serde]
+impl Runnable for BackfillFeedJob {
+    fn run(&self, _queue: &dyn Queueable) -> Result<(), FangError> {
+        self.run()
+    }
+
+    fn uniq(&self) -> bool {
+        true
+    }
+
+    fn max_retries(&self) -> i32 {
+        0
+    }
+
+    fn task_type(&self) -> String {
+        super::JOB_TYPE.to_string()
+    }
+}
@@ -0,0 +1,516 @@
+use crate::config::Config;
+use isahc::http::HeaderMap;
+use isahc::http::header::CONTENT_LENGTH;
+use isahc::http::header::CONTENT_TYPE;
+use std::fmt;
+use std::io::Read;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddr;
+use std::net::ToSocketAddrs;
+use url::Url;
+
+pub const MAX_REDIRECTS: usize = 10;
+
+const ALLOWED_CONTENT_TYPES: [&str; 4] = ["xml", "rss", "atom", "json"];
+// names that only resolve inside a local network
+const LOCAL_DOMAINS: [&str; 7] = [
+    "localhost",
+    "local",
+    "localdomain",
+    "internal",
+    "intranet",
+    "lan",
+    "home.arpa",
+];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum FetchPolicyError {
+    InvalidUrl,
+    UnsupportedScheme(String),
+    BlockedHost(String),
+    PrivateAddress(String),
+    UnresolvedHost(String),
+    TooManyRedirects,
+    UnsupportedContentType(String),
+    BodyTooLarge(u64),
+    ReadFailed(String),
+    TorProxyMissing,
+}
+
+impl fmt::Display for FetchPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchPolicyError::InvalidUrl => write!(f, "Invalid url"),
+            FetchPolicyError::UnsupportedScheme(scheme) => {
+                write!(f, "Only http and https urls are supported, got {scheme}")
+            }
+            FetchPolicyError::BlockedHost(host) => {
+                write!(f, "Fetching feeds from {host} is not allowed")
+            }
+            FetchPolicyError::PrivateAddress(host) => {
+                write!(f, "{host} points to a private network address")
+            }
+            FetchPolicyError::UnresolvedHost(host) => write!(f, "Failed to resolve {host}"),
+            FetchPolicyError::TooManyRedirects => {
+                write!(f, "The url redirected more than {MAX_REDIRECTS} times")
+            }
+            FetchPolicyError::UnsupportedContentType(content_type) => {
+                write!(
+                    f,
+                    "The url returned unsupported content type {content_type}"
+                )
+            }
+            FetchPolicyError::BodyTooLarge(limit) => {
+                write!(f, "The response is larger than {limit} bytes")
+            }
+            FetchPolicyError::ReadFailed(error) => {
+                write!(f, "Failed to read the response: {error}")
+            }
+            FetchPolicyError::TorProxyMissing => {
+                write!(f, "Onion feeds are not supported by this bot")
+            }
+        }
+    }
+}
+
+pub struct FetchPolicy {
+    allowed_hosts: Vec<String>,
+    blocked_hosts: Vec<String>,
+    max_body_bytes: u64,
+}
+
+impl FetchPolicy {
+    pub fn from_config() -> Self {
+        Self {
+            allowed_hosts: Config::fetch_allowed_hosts(),
+            blocked_hosts: Config::fetch_blocked_hosts(),
+            max_body_bytes: Config::fetch_max_body_bytes(),
+        }
+    }
+
+    pub fn parse_url(&self, url: &str) -> Result<Url, FetchPolicyError> {
+        let url = Url::parse(url.trim()).map_err(|_| FetchPolicyError::InvalidUrl)?;
+
+        match url.scheme() {
+            "http" | "https" => Ok(url),
+            scheme => Err(FetchPolicyError::UnsupportedScheme(scheme.to_string())),
+        }
+    }
+
+    pub fn parse_proxy_url(&self, url: &str) -> Result<Url, FetchPolicyError> {
+        let url = Url::parse(url.trim()).map_err(|_| FetchPolicyError::InvalidUrl)?;
+
+        match url.scheme() {
+            "http" | "https" | "socks5" | "socks5h" => Ok(url),
+            scheme => Err(FetchPolicyError::UnsupportedScheme(scheme.to_string())),
+        }
+    }
+
+    pub fn check_host(&self, url: &Url) -> Result<String, FetchPolicyError> {
+        let host = url
+            .host_str()
+            .ok_or(FetchPolicyError::InvalidUrl)?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_lowercase();
+
+        if Self::matches_any(&host, &self.blocked_hosts) {
+            return Err(FetchPolicyError::BlockedHost(host));
+        }
+
+        if Self::matches_any(&host, &self.allowed_hosts) {
+            return Ok(host);
+        }
+
+        match host.parse::<IpAddr>() {
+            Ok(ip) if is_private(&ip) => Err(FetchPolicyError::PrivateAddress(host)),
+            _ => Ok(host),
+        }
+    }
+
+    // Hosts fetched through a proxy are resolved by the proxy. They're still rejected
+    // if they have local names or resolve to private addresses here.
+    // Hosts that can't be resolved here (for example onion services) are left to the proxy
+    pub fn check_proxied_host(&self, url: &Url) -> Result<String, FetchPolicyError> {
+        let host = self.check_host(url)?;
+
+        if Self::matches_any(&host, &self.allowed_hosts) {
+            return Ok(host);
+        }
+
+        if LOCAL_DOMAINS.iter().any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain)
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        }) {
+            return Err(FetchPolicyError::PrivateAddress(host));
+        }
+
+        let port = url
+            .port_or_known_default()
+            .ok_or(FetchPolicyError::InvalidUrl)?;
+
+        if let Ok(mut addresses) = (host.as_str(), port).to_socket_addrs() {
+            if addresses.any(|address| is_private(&address.ip())) {
+                return Err(FetchPolicyError::PrivateAddress(host));
+            }
+        }
+
+        Ok(host)
+    }
+
+    // The returned address is used for the connection so the host can't be
+    // re-resolved to another address between the check and the request
+    pub fn resolve(&self, url: &Url) -> Result<SocketAddr, FetchPolicyError> {
+        let host = self.check_host(url)?;
+
+        let port = url
+            .port_or_known_default()
+            .ok_or(FetchPolicyError::InvalidUrl)?;
+
+        let addresses: Vec<SocketAddr> = (host.as_str(), port)
+            .to_socket_addrs()
+            .map_err(|_| FetchPolicyError::UnresolvedHost(host.clone()))?
+            .collect();
+
+        if addresses.is_empty() {
+            return Err(FetchPolicyError::UnresolvedHost(host));
+        }
+
+        if Self::matches_any(&host, &self.allowed_hosts) {
+            return Ok(addresses[0]);
+        }
+
+        if addresses.iter().any(|address| is_private(&address.ip())) {
+            return Err(FetchPolicyError::PrivateAddress(host));
+        }
+
+        Ok(addresses[0])
+    }
+
+    pub fn check_headers(&self, headers: &HeaderMap) -> Result<(), FetchPolicyError> {
+        if let Some(content_length) = headers
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+        {
+            if content_length > self.max_body_bytes {
+                return Err(FetchPolicyError::BodyTooLarge(self.max_body_bytes));
+            }
+        }
+
+        let content_type = match headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+        {
+            Some(content_type) => content_type.to_lowercase(),
+            None => return Ok(()),
+        };
+
+        let mime_type = content_type.split(';').next().unwrap_or("").trim();
+
+        if mime_type.is_empty()
+            || mime_type.starts_with("text/")
+            || ALLOWED_CONTENT_TYPES
+                .iter()
+                .any(|allowed| mime_type.contains(allowed))
+        {
+            Ok(())
+        } else {
+            Err(FetchPolicyError::UnsupportedContentType(
+                mime_type.to_string(),
+            ))
+        }
+    }
+
+    pub fn read_body(&self, body: impl Read) -> Result<Vec<u8>, FetchPolicyError> {
+        let mut writer: Vec<u8> = vec![];
+
+        body.take(self.max_body_bytes + 1)
+            .read_to_end(&mut writer)
+            .map_err(|err| FetchPolicyError::ReadFailed(format!("{err}")))?;
+
+        if writer.len() as u64 > self.max_body_bytes {
+            return Err(FetchPolicyError::BodyTooLarge(self.max_body_bytes));
+        }
+
+        Ok(writer)
+    }
+
+    fn matches_any(host: &str, domains: &[String]) -> bool {
+        domains.iter().any(|domain| {
+            host == domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|subdomain| subdomain.ends_with('.'))
+        })
+    }
+}
+
+pub fn is_private(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ipv4) => is_private_ipv4(&ipv4),
+            None => is_private_ipv6(ip),
+        },
+    }
+}
+
+fn is_private_ipv4(ip: &Ipv4Addr) -> bool {
+    let octets = ip.octets();
+
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8
+        || octets[0] == 0
+        // 100.64.0.0/10 - carrier-grade NAT
+        || (octets[0] == 100 && (octets[1] & 0b1100_0000) == 64)
+        // 192.0.0.0/24 - IETF protocol assignments
+        || (octets[0] == 192 && octets[1] == 0 && octets[2] == 0)
+        // 198.18.0.0/15 - benchmarking
+        || (octets[0] == 198 && (octets[1] & 0xfe) == 18)
+        // 240.0.0.0/4 - reserved
+        || octets[0] >= 240
+}
+
+fn is_private_ipv6(ip: &Ipv6Addr) -> bool {
+    if let Some(ipv4) = embedded_ipv4(ip) {
+        return is_private_ipv4(&ipv4);
+    }
+
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || ip.is_unique_local()
+        || ip.is_unicast_link_local()
+}
+
+// IPv6 addresses that are translated to IPv4 addresses on the way
+fn embedded_ipv4(ip: &Ipv6Addr) -> Option<Ipv4Addr> {
+    let segments = ip.segments();
+    let octets = ip.octets();
+
+    match segments {
+        // 64:ff9b::/96 - NAT64
+        [0x64, 0xff9b, 0, 0, 0, 0, _, _] => Some(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        )),
+        // 2002::/16 - 6to4
+        [0x2002, _, _, _, _, _, _, _] => {
+            Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5]))
+        }
+        // ::a.b.c.d - IPv4-compatible (:: and ::1 are checked as IPv6)
+        [0, 0, 0, 0, 0, 0, high, low] if high != 0 || low > 1 => Some(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FetchPolicy;
+    use super::FetchPolicyError;
+    use isahc::http::HeaderMap;
+    use isahc::http::HeaderValue;
+    use isahc::http::header::CONTENT_LENGTH;
+    use isahc::http::header::CONTENT_TYPE;
+    use std::net::IpAddr;
+    use url::Url;
+
+    fn policy(allowed_hosts: Vec<&str>, blocked_hosts: Vec<&str>) -> FetchPolicy {
+        FetchPolicy {
+            allowed_hosts: allowed_hosts.into_iter().map(String::from).collect(),
+            blocked_hosts: blocked_hosts.into_iter().map(String::from).collect(),
+            max_body_bytes: 10,
+        }
+    }
+
+    #[test]
+    fn is_private_detects_private_ranges() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fe80::1",
+            "fd00::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::7f00:1",
+            "64:ff9b::a9fe:a9fe",
+            "2002:a00:1::1",
+            "2002:c0a8:101::",
+            "::127.0.0.1",
+            "::10.0.0.1",
+        ] {
+            let ip: IpAddr = ip.parse().unwrap();
+
+            assert!(super::is_private(&ip), "{ip} should be private");
+        }
+
+        for ip in [
+            "1.1.1.1",
+            "140.82.121.4",
+            "2606:4700:4700::1111",
+            "64:ff9b::101:101",
+            "2002:101:101::1",
+            "::8c52:7904",
+        ] {
+            let ip: IpAddr = ip.parse().unwrap();
+
+            assert!(!super::is_private(&ip), "{ip} should be public");
+        }
+    }
+
+    #[test]
+    fn parse_url_rejects_unsupported_schemes() {
+        let policy = policy(vec![], vec![]);
+
+        assert_eq!(
+            Err(FetchPolicyError::UnsupportedScheme("file".to_string())),
+            policy.parse_url("file:///etc/passwd")
+        );
+        assert_eq!(Err(FetchPolicyError::InvalidUrl), policy.parse_url("11"));
+    }
+
+    #[test]
+    fn resolve_rejects_private_addresses() {
+        let policy = policy(vec![], vec![]);
+
+        for url in [
+            "http://127.0.0.1:5432/",
+            "http://localhost/",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/",
+        ] {
+            let url = Url::parse(url).unwrap();
+
+            assert!(matches!(
+                policy.resolve(&url),
+                Err(FetchPolicyError::PrivateAddress(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn resolve_allows_private_addresses_from_allowlist() {
+        let policy = policy(vec!["127.0.0.1", "localhost"], vec![]);
+
+        let url = Url::parse("http://127.0.0.1:8080/feed").unwrap();
+        assert_eq!(8080, policy.resolve(&url).unwrap().port());
+
+        let url = Url::parse("http://localhost/feed").unwrap();
+        assert!(policy.resolve(&url).is_ok());
+    }
+
+    #[test]
+    fn resolve_rejects_blocked_domains_and_subdomains() {
+        let policy = policy(vec![], vec!["example.com"]);
+
+        for url in ["http://example.com/", "https://feeds.example.com/rss"] {
+            let url = Url::parse(url).unwrap();
+
+            assert!(matches!(
+                policy.resolve(&url),
+                Err(FetchPolicyError::BlockedHost(_))
+            ));
+        }
+
+        let url = Url::parse("http://notexample.com/").unwrap();
+
+        assert!(!matches!(
+            policy.resolve(&url),
+            Err(FetchPolicyError::BlockedHost(_))
+        ));
+    }
+
+    #[test]
+    fn check_proxied_host_rejects_local_names() {
+        let policy = policy(vec!["feeds.internal"], vec![]);
+
+        for url in [
+            "http://localhost/",
+            "http://printer.local/",
+            "http://wiki.corp.internal/feed",
+            "http://127.0.0.1/",
+        ] {
+            let url = Url::parse(url).unwrap();
+
+            assert!(matches!(
+                policy.check_proxied_host(&url),
+                Err(FetchPolicyError::PrivateAddress(_))
+            ));
+        }
+
+        for url in [
+            "http://feeds.internal/rss",
+            "http://examplexyzexample.onion/feed",
+        ] {
+            let url = Url::parse(url).unwrap();
+
+            assert!(policy.check_proxied_host(&url).is_ok());
+        }
+    }
+
+    #[test]
+    fn check_headers_checks_content_type_and_length() {
+        let policy = policy(vec![], vec![]);
+        let mut headers = HeaderMap::new();
+
+        assert!(policy.check_headers(&headers).is_ok());
+
+        for content_type in [
+            "application/rss+xml; charset=utf-8",
+            "application/feed+json",
+            "text/html",
+        ] {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+
+            assert!(policy.check_headers(&headers).is_ok());
+        }
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("image/png"));
+
+        assert_eq!(
+            Err(FetchPolicyError::UnsupportedContentType(
+                "image/png".to_string()
+            )),
+            policy.check_headers(&headers)
+        );
+
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/xml"));
+        headers.insert(CONTENT_LENGTH, HeaderValue::from_static("11"));
+
+        assert_eq!(
+            Err(FetchPolicyError::BodyTooLarge(10)),
+            policy.check_headers(&headers)
+        );
+    }
+
+    #[test]
+    fn read_body_limits_body_size() {
+        let policy = policy(vec![], vec![]);
+
+        assert_eq!(
+            b"0123456789".to_vec(),
+            policy.read_body(&b"0123456789"[..]).unwrap()
+        );
+        assert_eq!(
+            Err(FetchPolicyError::BodyTooLarge(10)),
+            policy.read_body(&b"0123456789a"[..])
+        );
+    }
+}
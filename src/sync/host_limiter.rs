@@ -0,0 +1,84 @@
+use super::fetch_policy::MAX_REDIRECTS;
+use crate::config::Config;
+use crate::db::sync_hosts;
+use crate::db::sync_hosts::HostToken;
+use chrono::Duration;
+use diesel::PgConnection;
+use diesel::result::Error;
+use rand::Rng;
+use url::Url;
+
+// Limits requests to the same host across all sync workers.
+// The state is stored in postgres so it's shared between processes
+pub struct HostLimiter {
+    max_concurrency: i64,
+    interval: Duration,
+    lease: Duration,
+    backoff: Duration,
+    max_backoff: Duration,
+}
+
+impl HostLimiter {
+    pub fn from_config() -> Self {
+        // a token is held at most as long as a fetch with all redirects can take
+        let lease_seconds =
+            Config::request_timeout_in_seconds() as i64 * (MAX_REDIRECTS as i64 + 1);
+
+        Self {
+            max_concurrency: Config::sync_host_concurrency().max(1),
+            interval: Duration::milliseconds(Config::sync_host_interval_milliseconds().max(0)),
+            lease: Duration::seconds(lease_seconds),
+            backoff: Duration::seconds(Config::sync_host_backoff_seconds().max(1)),
+            max_backoff: Duration::seconds(Config::sync_host_max_backoff_seconds().max(1)),
+        }
+    }
+
+    pub fn host(url: &str) -> Option<String> {
+        Url::parse(url)
+            .ok()?
+            .host_str()
+            .map(|host| host.to_lowercase())
+    }
+
+    pub fn acquire(&self, conn: &mut PgConnection, host: &str) -> Result<HostToken, Error> {
+        sync_hosts::acquire_token(conn, host, self.max_concurrency, self.interval, self.lease)
+    }
+
+    pub fn release(&self, conn: &mut PgConnection, token_id: i64) -> Result<usize, Error> {
+        sync_hosts::release_token(conn, token_id)
+    }
+
+    pub fn back_off(
+        &self,
+        conn: &mut PgConnection,
+        host: &str,
+        retry_after_seconds: u64,
+    ) -> Result<(), Error> {
+        let retry_after = Duration::seconds(retry_after_seconds.min(i64::MAX as u64) as i64)
+            .min(self.max_backoff);
+
+        let sync_host =
+            sync_hosts::set_backoff(conn, host, retry_after, self.backoff, self.max_backoff)?;
+
+        log::warn!(
+            "Host {host} asked to slow down. Backing off until {:?}",
+            sync_host.backoff_until
+        );
+
+        Ok(())
+    }
+
+    pub fn reset(&self, conn: &mut PgConnection, host: &str) -> Result<usize, Error> {
+        sync_hosts::reset_backoff(conn, host)
+    }
+}
+
+pub fn jitter(max_seconds: u64) -> Duration {
+    if max_seconds == 0 {
+        return Duration::zero();
+    }
+
+    let milliseconds = rand::thread_rng().gen_range(0..max_seconds * 1000);
+
+    Duration::milliseconds(milliseconds as i64)
+}
@@ -1,11 +1,25 @@
 use self::atom::AtomReader;
 use self::json::JsonReader;
 use self::rss::RssReader;
+use super::fetch_options::FetchAuth;
+use super::fetch_options::FetchOptions;
+use super::fetch_policy::FetchPolicy;
+use super::fetch_policy::FetchPolicyError;
+use super::fetch_policy::MAX_REDIRECTS;
 use crate::http_client;
 use chrono::DateTime;
 use chrono::Utc;
 use isahc::Request;
-use std::io;
+use isahc::auth::Authentication;
+use isahc::auth::Credentials;
+use isahc::config::Dialer;
+use isahc::config::RedirectPolicy;
+use isahc::http::StatusCode;
+use isahc::http::header::LOCATION;
+use isahc::http::header::RETRY_AFTER;
+use isahc::http::request::Builder;
+use isahc::prelude::*;
+use url::Url;
 
 pub mod atom;
 pub mod json;
@@ -14,6 +28,17 @@ pub mod rss;
 #[derive(Debug)]
 pub struct FeedReaderError {
     pub msg: String,
+    // set when the host asked to slow down (429 or 503), seconds from `Retry-After` or 0
+    pub retry_after: Option<u64>,
+}
+
+impl From<FetchPolicyError> for FeedReaderError {
+    fn from(error: FetchPolicyError) -> Self {
+        FeedReaderError {
+            msg: error.to_string(),
+            retry_after: None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -33,6 +58,9 @@ pub struct FetchedFeed {
     pub description: String,
     pub feed_type: String,
     pub items: Vec<FetchedFeedItem>,
+    // a link to the page with older items (RFC 5005 or JSON Feed `next_url`)
+    pub next_page_link: Option<String>,
+    pub generator: Option<String>,
 }
 
 pub trait ReadFeed {
@@ -48,46 +76,195 @@ pub trait ReadFeed {
 }
 
 pub fn read_url(url: &str) -> Result<Vec<u8>, FeedReaderError> {
+    read_url_with_options(url, &FetchOptions::default())
+}
+
+pub fn read_url_with_options(
+    url: &str,
+    options: &FetchOptions,
+) -> Result<Vec<u8>, FeedReaderError> {
     let client = http_client::client();
+    let policy = FetchPolicy::from_config();
 
-    let request = Request::get(url)
-        .header("User-Agent", "el_monitorro")
-        .body(());
+    let original_url = policy.parse_url(url)?;
+    let mut current_url = original_url.clone();
+    let mut send_credentials = true;
 
-    if let Err(_error) = request {
-        return Err(FeedReaderError {
-            msg: "Invalid URL".to_string(),
-        });
-    };
+    // redirects are followed manually so every location is checked by the fetch policy
+    for _ in 0..=MAX_REDIRECTS {
+        let mut request_builder = Request::get(current_url.as_str())
+            .header("User-Agent", options.user_agent())
+            .redirect_policy(RedirectPolicy::None);
+
+        match options.proxy_for(&current_url)? {
+            Some(proxy) => {
+                let mut proxy_url = policy.parse_proxy_url(&proxy.url)?;
+
+                // the checked address is used so the proxy host can't be re-resolved
+                if !proxy.trusted {
+                    let address = policy.resolve(&proxy_url)?;
+
+                    proxy_url
+                        .set_ip_host(address.ip())
+                        .map_err(|_| FetchPolicyError::InvalidUrl)?;
+                }
+
+                policy.check_proxied_host(&current_url)?;
+
+                let proxy_uri = proxy_url
+                    .as_str()
+                    .parse()
+                    .map_err(|_| FetchPolicyError::InvalidUrl)?;
+
+                request_builder = request_builder.proxy(Some(proxy_uri));
+            }
+            None => {
+                let address = policy.resolve(&current_url)?;
+
+                request_builder = request_builder.dial(Dialer::ip_socket(address));
+            }
+        }
+
+        if send_credentials {
+            request_builder = with_credentials(request_builder, options);
+        }
 
-    match client.send(request.unwrap()) {
-        Ok(mut response) => {
-            let mut writer: Vec<u8> = vec![];
+        let request = match request_builder.body(()) {
+            Ok(request) => request,
+            Err(_error) => {
+                return Err(FeedReaderError {
+                    msg: "Invalid URL".to_string(),
+                    retry_after: None,
+                });
+            }
+        };
 
-            if let Err(err) = io::copy(response.body_mut(), &mut writer) {
-                let msg = format!("{err:?}");
+        let mut response = match client.send(request) {
+            Ok(response) => response,
+            Err(error) => {
+                let msg = format!("Failed to fetch the url: {error}");
 
-                return Err(FeedReaderError { msg });
+                return Err(FeedReaderError {
+                    msg,
+                    retry_after: None,
+                });
             }
+        };
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .ok_or(FetchPolicyError::InvalidUrl)?;
+
+            let next_url = current_url
+                .join(location)
+                .map_err(|_| FetchPolicyError::InvalidUrl)?;
+
+            current_url = policy.parse_url(next_url.as_str())?;
+
+            // credentials are sent only to the origin of the feed, once a redirect
+            // leaves it (or downgrades to http) they're not sent anymore
+            send_credentials = send_credentials && same_origin(&current_url, &original_url);
 
-            Ok(writer)
+            continue;
         }
-        Err(error) => {
-            let msg = format!("{error:?}");
 
-            Err(FeedReaderError { msg })
+        let status = response.status();
+
+        if status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after)
+                .unwrap_or(0);
+
+            return Err(FeedReaderError {
+                msg: format!("The host responded with {status}"),
+                retry_after: Some(retry_after),
+            });
         }
+
+        policy.check_headers(response.headers())?;
+
+        let body = policy.read_body(response.body_mut())?;
+
+        return Ok(body);
+    }
+
+    Err(FetchPolicyError::TooManyRedirects.into())
+}
+
+// `Retry-After` is either a number of seconds or an http date
+fn parse_retry_after(value: &str) -> Option<u64> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(seconds);
+    }
+
+    let date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    let seconds = (date.with_timezone(&Utc) - Utc::now()).num_seconds();
+
+    Some(seconds.max(0) as u64)
+}
+
+fn same_origin(url: &Url, other_url: &Url) -> bool {
+    url.scheme() == other_url.scheme()
+        && url.host() == other_url.host()
+        && url.port_or_known_default() == other_url.port_or_known_default()
+}
+
+fn with_credentials(mut request_builder: Builder, options: &FetchOptions) -> Builder {
+    match &options.auth {
+        Some(FetchAuth::Basic { username, password }) => {
+            request_builder = request_builder
+                .authentication(Authentication::basic())
+                .credentials(Credentials::new(username.as_str(), password.as_str()));
+        }
+        Some(FetchAuth::Bearer { token }) => {
+            request_builder = request_builder.header("Authorization", format!("Bearer {token}"));
+        }
+        None => (),
+    }
+
+    for (header, value) in &options.headers {
+        request_builder = request_builder.header(header.as_str(), value.as_str());
+    }
+
+    if let Some(cookie) = options.cookie_header() {
+        request_builder = request_builder.header("Cookie", cookie);
+    }
+
+    request_builder
+}
+
+pub fn parse_feed(url: &str, feed_type: &str, data: &[u8]) -> Result<FetchedFeed, FeedReaderError> {
+    let url = url.to_string();
+
+    match feed_type {
+        "rss" => RssReader { url }.read_from_bytes(data),
+        "atom" => AtomReader { url }.read_from_bytes(data),
+        "json" => JsonReader { url }.read_from_bytes(data),
+        &_ => Err(FeedReaderError {
+            msg: "Unknown feed type".to_string(),
+            retry_after: None,
+        }),
     }
 }
 
 pub fn validate_rss_url(url: &str) -> Result<String, FeedReaderError> {
     let data = read_url(url)?;
 
+    detect_feed_type(url, &data)
+}
+
+pub fn detect_feed_type(url: &str, data: &[u8]) -> Result<String, FeedReaderError> {
     let rss_reader = RssReader {
         url: url.to_string(),
     };
 
-    if rss_reader.read_from_bytes(&data).is_ok() {
+    if rss_reader.read_from_bytes(data).is_ok() {
         return Ok("rss".to_string());
     }
 
@@ -95,7 +272,7 @@ pub fn validate_rss_url(url: &str) -> Result<String, FeedReaderError> {
         url: url.to_string(),
     };
 
-    if atom_reader.read_from_bytes(&data).is_ok() {
+    if atom_reader.read_from_bytes(data).is_ok() {
         return Ok("atom".to_string());
     }
 
@@ -103,11 +280,50 @@ pub fn validate_rss_url(url: &str) -> Result<String, FeedReaderError> {
         url: url.to_string(),
     };
 
-    if json_reader.read_from_bytes(&data).is_ok() {
+    if json_reader.read_from_bytes(data).is_ok() {
         return Ok("json".to_string());
     }
 
     Err(FeedReaderError {
         msg: "Url is not a feed".to_string(),
+        retry_after: None,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::same_origin;
+    use url::Url;
+
+    #[test]
+    fn same_origin_compares_scheme_host_and_port() {
+        let feed_url = Url::parse("https://example.com/feed").unwrap();
+
+        for url in [
+            "https://example.com/other",
+            "https://example.com:443/feed",
+            "HTTPS://EXAMPLE.COM/feed",
+        ] {
+            let url = Url::parse(url).unwrap();
+
+            assert!(
+                same_origin(&url, &feed_url),
+                "{url} should be the same origin"
+            );
+        }
+
+        for url in [
+            "http://example.com/feed",
+            "https://example.com:8443/feed",
+            "https://cdn.example.com/feed",
+            "https://example.org/feed",
+        ] {
+            let url = Url::parse(url).unwrap();
+
+            assert!(
+                !same_origin(&url, &feed_url),
+                "{url} should be another origin"
+            );
+        }
+    }
+}
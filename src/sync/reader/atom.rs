@@ -1,4 +1,5 @@
 use crate::db;
+use crate::sync::archive::ARCHIVE_LINK_RELS;
 use crate::sync::reader::{FeedReaderError, FetchedFeed, FetchedFeedItem, ReadFeed};
 use atom_syndication::Entry;
 use atom_syndication::Feed as AtomFeed;
@@ -23,7 +24,10 @@ impl ReadFeed for AtomReader {
             }
             Err(err) => {
                 let msg = format!("{err}");
-                Err(FeedReaderError { msg })
+                Err(FeedReaderError {
+                    msg,
+                    retry_after: None,
+                })
             }
         }
     }
@@ -78,6 +82,16 @@ impl From<AtomFeed> for FetchedFeed {
                 .map_or_else(|| "".to_string(), |s| s.to_string()),
             items,
             feed_type: "atom".to_string(),
+            next_page_link: ARCHIVE_LINK_RELS.iter().find_map(|rel| {
+                feed.links()
+                    .iter()
+                    .find(|link| link.rel == *rel)
+                    .map(|link| link.href.to_string())
+            }),
+            generator: feed.generator().map(|generator| match generator.uri() {
+                Some(uri) => format!("{} {uri}", generator.value()),
+                None => generator.value().to_string(),
+            }),
         }
     }
 }
@@ -143,6 +157,8 @@ mod tests {
                     .unwrap()
                     .into(),
             }],
+            next_page_link: None,
+            generator: None,
         };
 
         assert_eq!(expected_result, fetched_feed);
@@ -1,4 +1,5 @@
 use crate::db;
+use crate::sync::archive::ARCHIVE_LINK_RELS;
 use crate::sync::reader::FeedReaderError;
 use crate::sync::reader::FetchedFeed;
 use crate::sync::reader::FetchedFeedItem;
@@ -18,7 +19,10 @@ impl ReadFeed for RssReader {
             Ok(channel) => Ok(FetchedFeed::from(channel)),
             Err(err) => {
                 let msg = format!("{err}");
-                Err(FeedReaderError { msg })
+                Err(FeedReaderError {
+                    msg,
+                    retry_after: None,
+                })
             }
         }
     }
@@ -57,10 +61,23 @@ impl From<Channel> for FetchedFeed {
             description: channel.description().to_string(),
             feed_type: "rss".to_string(),
             items,
+            next_page_link: next_page_link(&channel),
+            generator: channel.generator().map(|s| s.to_string()),
         }
     }
 }
 
+fn next_page_link(channel: &Channel) -> Option<String> {
+    let links = channel.atom_ext()?.links();
+
+    ARCHIVE_LINK_RELS.iter().find_map(|rel| {
+        links
+            .iter()
+            .find(|link| link.rel() == *rel)
+            .map(|link| link.href().to_string())
+    })
+}
+
 fn author(item: &Item) -> Option<String> {
     let author = item.author().map(|s| s.to_string());
 
@@ -1,33 +1,45 @@
 use crate::bot::SimpleMessageParams;
 use crate::bot::telegram_client;
+use crate::config::Config;
 use crate::db;
 use crate::db::feed_items;
 use crate::db::feed_items::ContentHashable;
 use crate::db::feeds;
+use crate::db::sync_hosts::HostToken;
 use crate::db::telegram;
+use crate::deliver::DeliverChatUpdatesJob;
 use crate::models::feed::Feed;
+use crate::sync::BackfillFeedJob;
 use crate::sync::FetchedFeed;
+use crate::sync::fetch_options::FetchOptions;
+use crate::sync::host_limiter;
+use crate::sync::host_limiter::HostLimiter;
+use crate::sync::reader;
 use crate::sync::reader::FeedReaderError;
-use crate::sync::reader::ReadFeed;
-use crate::sync::reader::atom::AtomReader;
-use crate::sync::reader::json::JsonReader;
-use crate::sync::reader::rss::RssReader;
+use chrono::DateTime;
 use chrono::Duration;
 use chrono::Utc;
 use diesel::pg::PgConnection;
 use diesel::result::Error;
 use fang::FangError;
+use fang::Queue;
 use fang::Queueable;
 use fang::Runnable;
+use fang::Scheduled;
 use fang::typetag;
 use log::error;
 use serde::{Deserialize, Serialize};
 
 const SYNC_FAILURE_LIMIT_IN_HOURS: i64 = 48;
+const THROTTLED_JITTER_SECONDS: u64 = 5;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct SyncFeedJob {
     feed_id: i64,
+
+    // not serialized so rescheduled jobs stay unique per feed
+    #[serde(skip)]
+    scheduled_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -54,16 +66,53 @@ impl From<FeedSyncError> for FangError {
 
 #[typetag::serde]
 impl Runnable for SyncFeedJob {
-    fn run(&self, _queue: &dyn Queueable) -> Result<(), FangError> {
+    fn run(&self, queue: &dyn Queueable) -> Result<(), FangError> {
         let mut db_connection = crate::db::pool().get()?;
 
-        self.sync_feed(&mut db_connection)
+        let host = match feeds::find(&mut db_connection, self.feed_id) {
+            Some(feed) => HostLimiter::host(&feed.link),
+            None => return Ok(()),
+        };
+
+        let host = match host {
+            Some(host) => host,
+            None => {
+                self.sync_feed(&mut db_connection)?;
+
+                return self.deliver_updates(&mut db_connection);
+            }
+        };
+
+        let limiter = HostLimiter::from_config();
+
+        match limiter.acquire(&mut db_connection, &host)? {
+            HostToken::Wait(until) => {
+                let scheduled_at = until + host_limiter::jitter(THROTTLED_JITTER_SECONDS);
+
+                queue.schedule_task(&SyncFeedJob::scheduled(self.feed_id, scheduled_at))?;
+
+                Ok(())
+            }
+            HostToken::Acquired(token_id) => {
+                let result = self.sync_feed(&mut db_connection);
+
+                limiter.release(&mut db_connection, token_id)?;
+
+                result?;
+
+                self.deliver_updates(&mut db_connection)
+            }
+        }
     }
 
     fn uniq(&self) -> bool {
         true
     }
 
+    fn cron(&self) -> Option<Scheduled> {
+        self.scheduled_at.map(Scheduled::ScheduleOnce)
+    }
+
     fn max_retries(&self) -> i32 {
         0
     }
@@ -75,7 +124,17 @@ impl Runnable for SyncFeedJob {
 
 impl SyncFeedJob {
     pub fn new(feed_id: i64) -> Self {
-        Self { feed_id }
+        Self {
+            feed_id,
+            scheduled_at: None,
+        }
+    }
+
+    pub fn scheduled(feed_id: i64, scheduled_at: DateTime<Utc>) -> Self {
+        Self {
+            feed_id,
+            scheduled_at: Some(scheduled_at),
+        }
     }
 
     pub fn sync_feed(&self, db_connection: &mut PgConnection) -> Result<(), FangError> {
@@ -94,6 +153,23 @@ impl SyncFeedJob {
         Ok(())
     }
 
+    // New items are delivered right after sync, only to chats that have them.
+    // DeliverJob still checks all chats periodically in case this step fails
+    fn deliver_updates(&self, db_connection: &mut PgConnection) -> Result<(), FangError> {
+        let chat_ids =
+            telegram::find_chat_ids_with_updates_by_feed_id(db_connection, self.feed_id)?;
+
+        for chat_id in chat_ids {
+            Queue::insert_query(
+                db_connection,
+                &DeliverChatUpdatesJob::new(chat_id),
+                Utc::now(),
+            )?;
+        }
+
+        Ok(())
+    }
+
     fn remove_feed_and_notify_subscribers(
         &self,
         db_connection: &mut PgConnection,
@@ -126,8 +202,35 @@ impl SyncFeedJob {
         })?;
 
         match self.read_feed(&feed) {
-            Ok(fetched_feed) => self.maybe_upsert_feed_items(db_connection, feed, fetched_feed),
-            Err(err) => self.check_staleness(err, db_connection, feed),
+            Ok(fetched_feed) => {
+                self.maybe_reset_host_backoff(db_connection, &feed);
+
+                self.maybe_upsert_feed_items(db_connection, feed, fetched_feed)
+            }
+            Err(err) => {
+                if let Some(retry_after) = err.retry_after {
+                    self.back_off_host(db_connection, &feed, retry_after);
+                }
+
+                self.check_staleness(err, db_connection, feed)
+            }
+        }
+    }
+
+    fn back_off_host(&self, db_connection: &mut PgConnection, feed: &Feed, retry_after: u64) {
+        if let Some(host) = HostLimiter::host(&feed.link) {
+            if let Err(err) = HostLimiter::from_config().back_off(db_connection, &host, retry_after)
+            {
+                error!("Failed to set backoff for host {host}: {err:?}");
+            }
+        }
+    }
+
+    fn maybe_reset_host_backoff(&self, db_connection: &mut PgConnection, feed: &Feed) {
+        if let Some(host) = HostLimiter::host(&feed.link) {
+            if let Err(err) = HostLimiter::from_config().reset(db_connection, &host) {
+                error!("Failed to reset backoff for host {host}: {err:?}");
+            }
         }
     }
 
@@ -147,6 +250,19 @@ impl SyncFeedJob {
         match last_item_in_db_option {
             None => {
                 self.create_feed_items(db_connection, feed, fetched_feed)?;
+
+                // older pages are saved later as archived items so they're not delivered as new.
+                // The job is inserted on the same connection so it's dropped if the sync is rolled back
+                if Config::sync_archive_depth() > 0 {
+                    Queue::insert_query(
+                        db_connection,
+                        &BackfillFeedJob::new(self.feed_id),
+                        Utc::now(),
+                    )
+                    .map_err(|error| FeedSyncError::DbError {
+                        msg: format!("Error: failed to schedule backfill {error:?}"),
+                    })?;
+                }
             }
             Some(last_item_in_db) => {
                 let api_item_older =
@@ -282,25 +398,14 @@ impl SyncFeedJob {
     }
 
     fn read_feed(&self, feed: &Feed) -> Result<FetchedFeed, FeedReaderError> {
-        match feed.feed_type.as_str() {
-            "rss" => RssReader {
-                url: feed.link.clone(),
-            }
-            .read(),
+        let options = FetchOptions::from_feed(feed).map_err(|error| FeedReaderError {
+            msg: error.to_string(),
+            retry_after: None,
+        })?;
 
-            "atom" => AtomReader {
-                url: feed.link.clone(),
-            }
-            .read(),
+        let data = reader::read_url_with_options(&feed.link, &options)?;
 
-            "json" => JsonReader {
-                url: feed.link.clone(),
-            }
-            .read(),
-            &_ => Err(FeedReaderError {
-                msg: "Unknown feed type".to_string(),
-            }),
-        }
+        reader::parse_feed(&feed.link, &feed.feed_type, &data)
     }
 }
 
@@ -309,8 +414,18 @@ mod tests {
     use super::FeedSyncError;
     use super::SyncFeedJob;
     use crate::db;
+    use crate::db::telegram;
+    use crate::db::telegram::{NewTelegramChat, NewTelegramSubscription};
+    use crate::db::telegram_outbox;
+    use crate::db::telegram_outbox::NewTelegramOutboxMessage;
     use crate::db::{feed_items, feeds};
+    use crate::deliver::DeliverChatUpdatesJob;
+    use crate::sync::BackfillFeedJob;
+    use crate::sync::archive;
     use diesel::Connection;
+    use diesel::prelude::*;
+    use fang::Runnable;
+    use fang::fang_tasks;
 
     #[test]
     fn it_saves_rss_items() {
@@ -318,6 +433,10 @@ mod tests {
         let path = "/feed";
         let mut server = mockito::Server::new();
 
+        unsafe {
+            std::env::set_var("FETCH_ALLOWED_HOSTS", "127.0.0.1");
+        }
+
         let _m = server
             .mock("GET", path)
             .with_status(200)
@@ -328,7 +447,7 @@ mod tests {
 
         connection.test_transaction::<(), (), _>(|connection| {
             let feed = feeds::create(connection, &link, "rss".to_string()).unwrap();
-            let sync_job = SyncFeedJob { feed_id: feed.id };
+            let sync_job = SyncFeedJob::new(feed.id);
 
             sync_job.execute(connection).unwrap();
 
@@ -345,10 +464,179 @@ mod tests {
         })
     }
 
+    #[test]
+    fn it_saves_only_the_first_page_of_a_new_feed() {
+        let mut server = mockito::Server::new();
+
+        unsafe {
+            std::env::set_var("FETCH_ALLOWED_HOSTS", "127.0.0.1");
+        }
+
+        let url = server.url();
+
+        for (path, name) in [
+            ("/feed", "atom_archive_page1.xml"),
+            ("/archive/2", "atom_archive_page2.xml"),
+            ("/archive/3", "atom_archive_page3.xml"),
+        ] {
+            let body = std::fs::read_to_string(format!("./tests/support/{name}"))
+                .unwrap()
+                .replace("{server_url}", &url);
+
+            server
+                .mock("GET", path)
+                .with_status(200)
+                .with_body(body)
+                .create();
+        }
+
+        let link = format!("{url}/feed");
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let feed = feeds::create(connection, &link, "atom".to_string()).unwrap();
+            let chat = telegram::create_chat(
+                connection,
+                NewTelegramChat {
+                    id: 9301,
+                    kind: "private".to_string(),
+                    username: None,
+                    first_name: None,
+                    last_name: None,
+                    title: None,
+                },
+            )
+            .unwrap();
+            let subscription = telegram::create_subscription(
+                connection,
+                NewTelegramSubscription::builder()
+                    .chat_id(chat.id)
+                    .feed_id(feed.id)
+                    .build(),
+            )
+            .unwrap();
+
+            SyncFeedJob::new(feed.id).execute(connection).unwrap();
+
+            let undelivered =
+                telegram::find_undelivered_feed_items(connection, &subscription, 10).unwrap();
+            let titles: Vec<&str> = undelivered.iter().map(|item| item.title.as_str()).collect();
+
+            assert_eq!(vec!["Item 1"], titles);
+
+            let job: &dyn Runnable = &BackfillFeedJob::new(feed.id);
+            let jobs = fang_tasks::table
+                .filter(fang_tasks::metadata.eq(serde_json::to_value(job).unwrap()))
+                .count()
+                .get_result::<i64>(connection)
+                .unwrap();
+
+            assert_eq!(1, jobs);
+
+            // the first page is delivered, then the backfill job saves older pages
+            let subscription = telegram::set_subscription_last_delivered_at(
+                connection,
+                &subscription,
+                undelivered[0].created_at,
+            )
+            .unwrap();
+
+            assert_eq!(Ok(2), archive::backfill(connection, &feed));
+            assert_eq!(3, feed_items::count(connection, feed.id).unwrap());
+            assert!(
+                telegram::find_undelivered_feed_items(connection, &subscription, 10)
+                    .unwrap()
+                    .is_empty()
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn it_enqueues_deliveries_right_after_sync() {
+        let response = std::fs::read_to_string("./tests/support/rss_feed_example.xml").unwrap();
+        let mut server = mockito::Server::new();
+
+        unsafe {
+            std::env::set_var("FETCH_ALLOWED_HOSTS", "127.0.0.1");
+        }
+
+        server
+            .mock("GET", "/feed")
+            .with_status(200)
+            .with_body(response)
+            .create();
+        let link = format!("{}/feed", server.url());
+        let mut connection = db::establish_test_connection();
+
+        connection.test_transaction::<(), (), _>(|connection| {
+            let feed = feeds::create(connection, &link, "rss".to_string()).unwrap();
+
+            for id in [9302, 9303] {
+                let chat = telegram::create_chat(
+                    connection,
+                    NewTelegramChat {
+                        id,
+                        kind: "private".to_string(),
+                        username: None,
+                        first_name: None,
+                        last_name: None,
+                        title: None,
+                    },
+                )
+                .unwrap();
+
+                telegram::create_subscription(
+                    connection,
+                    NewTelegramSubscription::builder()
+                        .chat_id(chat.id)
+                        .feed_id(feed.id)
+                        .build(),
+                )
+                .unwrap();
+            }
+
+            // the second chat already has a held message
+            telegram_outbox::enqueue(
+                connection,
+                vec![NewTelegramOutboxMessage {
+                    chat_id: 9303,
+                    feed_id: feed.id,
+                    idempotency_key: "held".to_string(),
+                    message: "Held".to_string(),
+                    thread_id: None,
+                    preview_enabled: false,
+                    parse_mode: "HTML".to_string(),
+                    published_at: None,
+                }],
+            )
+            .unwrap();
+
+            let sync_job = SyncFeedJob::new(feed.id);
+
+            sync_job.sync_feed(connection).unwrap();
+            sync_job.deliver_updates(connection).unwrap();
+
+            for (chat_id, expected_jobs) in [(9302, 1), (9303, 0)] {
+                let job: &dyn Runnable = &DeliverChatUpdatesJob::new(chat_id);
+                let jobs = fang_tasks::table
+                    .filter(fang_tasks::metadata.eq(serde_json::to_value(job).unwrap()))
+                    .count()
+                    .get_result::<i64>(connection)
+                    .unwrap();
+
+                assert_eq!(expected_jobs, jobs);
+            }
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn it_returns_error_feed_is_not_found() {
         let mut connection = db::establish_test_connection();
-        let sync_job = SyncFeedJob { feed_id: 5 };
+        let sync_job = SyncFeedJob::new(5);
 
         let result = sync_job.execute(&mut connection);
 
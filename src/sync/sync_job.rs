@@ -1,4 +1,5 @@
 use super::SyncFeedJob;
+use super::host_limiter;
 use crate::Config;
 use crate::db;
 use crate::db::feeds;
@@ -43,6 +44,7 @@ impl Runnable for SyncJob {
         let mut total_number = 0;
 
         let last_synced_at = db::current_time();
+        let jitter_seconds = Config::sync_jitter_seconds();
         loop {
             unsynced_feed_ids = match feeds::find_unsynced_feeds(
                 &mut connection,
@@ -60,8 +62,11 @@ impl Runnable for SyncJob {
 
             page += 1;
 
+            // jitter spreads requests so feeds of the same host are not fetched at once
             for id in &unsynced_feed_ids {
-                queue.insert_task(&SyncFeedJob::new(*id))?;
+                let scheduled_at = db::current_time() + host_limiter::jitter(jitter_seconds);
+
+                queue.schedule_task(&SyncFeedJob::scheduled(*id, scheduled_at))?;
             }
 
             if unsynced_feed_ids.is_empty() {